@@ -90,7 +90,7 @@ type StringInternerWith<B> = StringInterner<B, FxBuildHasher>;
 
 pub trait BackendBenchmark {
     const NAME: &'static str;
-    type Backend: Backend;
+    type Backend: Backend + Default;
 
     fn setup() -> StringInternerWith<Self::Backend> {
         <StringInternerWith<Self::Backend>>::new()
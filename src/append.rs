@@ -0,0 +1,473 @@
+//! Append-only string interner with a single writer and lock-free readers.
+//!
+//! The [`AppendInterner`] interns strings while any number of [`AppendReader`]s
+//! resolve symbols concurrently without ever taking a lock.
+//!
+//! # Design
+//!
+//! Like the [`BucketBackend`](crate::backend::BucketBackend) the interner copies
+//! strings into buckets of bytes that are never reallocated, so interned strings
+//! never move. The spans of the strings are stored in chunks of doubling sizes
+//! that are never reallocated either. The writer fills the span of a new string
+//! before it publishes the increased length with a `Release` store. Readers load
+//! the length and the chunk of a span with `Acquire` loads and only ever read
+//! published spans that are never modified again.
+
+use crate::{
+    compat::{
+        Arc,
+        DefaultHashBuilder,
+        HashTable,
+        Vec,
+    },
+    interner::make_hash,
+    DefaultSymbol,
+    Symbol,
+};
+use core::{
+    fmt,
+    hash::BuildHasher,
+    marker::PhantomData,
+    mem,
+    ptr,
+    ptr::NonNull,
+    sync::atomic::{
+        AtomicPtr,
+        AtomicUsize,
+        Ordering,
+    },
+};
+use std::sync::{
+    Mutex,
+    PoisonError,
+};
+
+/// The base 2 logarithm of the number of spans in the first chunk.
+const FIRST_CHUNK_BITS: u32 = 5;
+
+/// The number of chunks required to store up to `usize::MAX` spans.
+const CHUNKS: usize = (usize::BITS - FIRST_CHUNK_BITS) as usize;
+
+/// The location of an interned string within a bucket.
+#[derive(Copy, Clone)]
+struct Span {
+    ptr: NonNull<u8>,
+    len: usize,
+}
+
+/// Returns the chunk and the offset within the chunk of the span at `index`.
+///
+/// The chunk `k` holds the `32 << k` spans following the spans of all
+/// previous chunks.
+#[inline]
+fn locate(index: usize) -> (usize, usize) {
+    let shifted = index + (1 << FIRST_CHUNK_BITS);
+    let bits = shifted.ilog2();
+    let chunk = bits - FIRST_CHUNK_BITS;
+    (chunk as usize, shifted - (1 << bits))
+}
+
+/// Returns the number of spans held by the chunk `k`.
+#[inline]
+fn chunk_len(chunk: usize) -> usize {
+    1 << (chunk as u32 + FIRST_CHUNK_BITS)
+}
+
+/// Allocates an uninitialized buffer with the given capacity.
+fn alloc_buffer<T>(cap: usize) -> NonNull<T> {
+    let mut buffer = Vec::<T>::with_capacity(cap);
+    let ptr = buffer.as_mut_ptr();
+    mem::forget(buffer);
+    // SAFETY: The pointer of a vector is never null.
+    unsafe { NonNull::new_unchecked(ptr) }
+}
+
+/// Frees a buffer allocated via [`alloc_buffer`].
+///
+/// # Safety
+///
+/// The buffer must have been allocated via [`alloc_buffer`] with the same
+/// capacity and must no longer be used. `T` must not require dropping.
+unsafe fn free_buffer<T>(ptr: NonNull<T>, cap: usize) {
+    // SAFETY: Guaranteed by the caller.
+    drop(unsafe { Vec::from_raw_parts(ptr.as_ptr(), 0, cap) });
+}
+
+/// The storage shared between the writer and the readers.
+struct Shared {
+    /// The number of published spans.
+    len: AtomicUsize,
+    /// The chunks of spans that are allocated on demand.
+    chunks: [AtomicPtr<Span>; CHUNKS],
+    /// The pointers and capacities of all buckets of bytes.
+    ///
+    /// Only locked by the writer when it allocates a new bucket.
+    buckets: Mutex<Vec<(NonNull<u8>, usize)>>,
+}
+
+// SAFETY: Spans are written only by the single writer before they are published
+//         via a `Release` store of `len` and are never modified thereafter. The
+//         bytes of published strings are never modified either. Chunks and
+//         buckets are never moved and are only freed once the storage is dropped.
+unsafe impl Send for Shared {}
+// SAFETY: See the `Send` impl above.
+unsafe impl Sync for Shared {}
+
+impl Shared {
+    fn new() -> Self {
+        Self {
+            len: AtomicUsize::new(0),
+            chunks: core::array::from_fn(|_| AtomicPtr::new(ptr::null_mut())),
+            buckets: Mutex::default(),
+        }
+    }
+
+    /// Returns the number of published strings.
+    #[inline]
+    fn len(&self) -> usize {
+        self.len.load(Ordering::Acquire)
+    }
+
+    /// Returns the published string at the given index if any.
+    #[inline]
+    fn resolve(&self, index: usize) -> Option<&str> {
+        if index >= self.len() {
+            return None
+        }
+        let (chunk, offset) = locate(index);
+        let chunk = self.chunks[chunk].load(Ordering::Acquire);
+        // SAFETY: The chunk of a published span has been allocated and its span
+        //         has been written before the span was published. Both are never
+        //         modified or moved thereafter.
+        let span = unsafe { *chunk.add(offset) };
+        // SAFETY: The span refers to the bytes of a valid UTF-8 string that have
+        //         been written before the span was published and that live as
+        //         long as the storage.
+        Some(unsafe {
+            core::str::from_utf8_unchecked(core::slice::from_raw_parts(
+                span.ptr.as_ptr(),
+                span.len,
+            ))
+        })
+    }
+
+    /// Writes the span at the next index and publishes it.
+    ///
+    /// # Safety
+    ///
+    /// Must only be called by the single writer.
+    unsafe fn push(&self, span: Span) {
+        let index = self.len.load(Ordering::Relaxed);
+        let (chunk, offset) = locate(index);
+        let slot = &self.chunks[chunk];
+        let mut spans = slot.load(Ordering::Relaxed);
+        if spans.is_null() {
+            spans = alloc_buffer::<Span>(chunk_len(chunk)).as_ptr();
+            slot.store(spans, Ordering::Release);
+        }
+        // SAFETY: The span is not published yet and thus not read by any reader.
+        unsafe { spans.add(offset).write(span) };
+        self.len.store(index + 1, Ordering::Release);
+    }
+}
+
+impl Drop for Shared {
+    fn drop(&mut self) {
+        for (chunk, spans) in self.chunks.iter_mut().enumerate() {
+            if let Some(spans) = NonNull::new(*spans.get_mut()) {
+                // SAFETY: The chunk has been allocated with its length and
+                //         spans do not require dropping.
+                unsafe { free_buffer(spans, chunk_len(chunk)) };
+            }
+        }
+        let buckets = self
+            .buckets
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner);
+        for &(bytes, cap) in buckets.iter() {
+            // SAFETY: The bucket has been allocated with its capacity.
+            unsafe { free_buffer(bytes, cap) };
+        }
+    }
+}
+
+/// Append-only string interner with a single writer and lock-free readers.
+///
+/// The interner itself is the single writer that interns strings. Its
+/// [`AppendReader`]s resolve symbols from other threads without locking and
+/// without contending with the writer.
+///
+/// Strings are never removed and never move in memory, so resolved strings
+/// remain valid as long as the interner or reader they have been resolved with.
+///
+/// # Example
+///
+/// ```
+/// use string_interner::AppendInterner;
+///
+/// let mut interner = <AppendInterner>::new();
+/// let reader = interner.reader();
+/// let tiger = interner.get_or_intern("Tiger");
+/// std::thread::spawn(move || {
+///     assert_eq!(reader.resolve(tiger), Some("Tiger"));
+/// })
+/// .join()
+/// .unwrap();
+/// ```
+pub struct AppendInterner<S = DefaultSymbol, H = DefaultHashBuilder>
+where
+    S: Symbol,
+    H: BuildHasher,
+{
+    shared: Arc<Shared>,
+    /// Maps the strings to their symbols.
+    dedup: HashTable<S>,
+    hasher: H,
+    /// The bucket that new strings are copied into.
+    head: NonNull<u8>,
+    head_len: usize,
+    head_cap: usize,
+}
+
+// SAFETY: The head bucket is owned by the storage and only written by the
+//         writer through `&mut self`.
+unsafe impl<S, H> Send for AppendInterner<S, H>
+where
+    S: Symbol + Send,
+    H: BuildHasher + Send,
+{
+}
+
+// SAFETY: The head bucket is never accessed through `&self`.
+unsafe impl<S, H> Sync for AppendInterner<S, H>
+where
+    S: Symbol + Sync,
+    H: BuildHasher + Sync,
+{
+}
+
+impl<S, H> fmt::Debug for AppendInterner<S, H>
+where
+    S: Symbol,
+    H: BuildHasher,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AppendInterner")
+            .field("len", &self.len())
+            .finish()
+    }
+}
+
+impl<S, H> Default for AppendInterner<S, H>
+where
+    S: Symbol,
+    H: BuildHasher + Default,
+{
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S, H> AppendInterner<S, H>
+where
+    S: Symbol,
+    H: BuildHasher + Default,
+{
+    /// Creates a new empty `AppendInterner`.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn new() -> Self {
+        Self::with_hasher(H::default())
+    }
+}
+
+impl<S, H> AppendInterner<S, H>
+where
+    S: Symbol,
+    H: BuildHasher,
+{
+    /// Creates a new empty `AppendInterner` with the given hasher.
+    pub fn with_hasher(hash_builder: H) -> Self {
+        Self {
+            shared: Arc::new(Shared::new()),
+            dedup: HashTable::new(),
+            hasher: hash_builder,
+            head: NonNull::dangling(),
+            head_len: 0,
+            head_cap: 0,
+        }
+    }
+
+    /// Returns a new reader that resolves the symbols of the interner.
+    #[inline]
+    pub fn reader(&self) -> AppendReader<S> {
+        AppendReader {
+            shared: self.shared.clone(),
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns the number of strings interned by the interner.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.dedup.len()
+    }
+
+    /// Returns `true` if the interner has no interned strings.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the symbol for the given string if any.
+    pub fn get<T>(&self, string: T) -> Option<S>
+    where
+        T: AsRef<str>,
+    {
+        let string = string.as_ref();
+        let hash = make_hash(&self.hasher, string);
+        let shared = &*self.shared;
+        self.dedup
+            .find(hash, |symbol| shared.resolve(symbol.to_usize()) == Some(string))
+            .copied()
+    }
+
+    /// Interns the given string and returns its symbol.
+    ///
+    /// The string is visible to all readers once this returns.
+    ///
+    /// # Panics
+    ///
+    /// If the interner already interns the maximum number of strings possible
+    /// by the chosen symbol type.
+    pub fn get_or_intern<T>(&mut self, string: T) -> S
+    where
+        T: AsRef<str>,
+    {
+        let string = string.as_ref();
+        if let Some(symbol) = self.get(string) {
+            return symbol
+        }
+        let symbol = S::try_from_usize(self.shared.len.load(Ordering::Relaxed))
+            .expect("encountered invalid symbol");
+        let span = self.alloc(string);
+        // SAFETY: The interner is the single writer of its storage.
+        unsafe { self.shared.push(span) };
+        let hash = make_hash(&self.hasher, string);
+        let Self {
+            shared,
+            dedup,
+            hasher,
+            ..
+        } = self;
+        dedup.insert_unique(hash, symbol, |symbol| {
+            let string = shared
+                .resolve(symbol.to_usize())
+                .expect("encountered missing string for interned symbol");
+            make_hash(hasher, string)
+        });
+        symbol
+    }
+
+    /// Returns the string for the given symbol if any.
+    #[inline]
+    pub fn resolve(&self, symbol: S) -> Option<&str> {
+        self.shared.resolve(symbol.to_usize())
+    }
+
+    /// Copies the string into the head bucket and returns its span.
+    fn alloc(&mut self, string: &str) -> Span {
+        if self.head_cap - self.head_len < string.len() {
+            let new_cap =
+                (usize::max(self.head_cap, string.len()) + 1).next_power_of_two();
+            let head = alloc_buffer::<u8>(new_cap);
+            self.shared
+                .buckets
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .push((head, new_cap));
+            self.head = head;
+            self.head_len = 0;
+            self.head_cap = new_cap;
+        }
+        // SAFETY: The head bucket has enough capacity for the string and its
+        //         remaining bytes are not yet referred to by any span.
+        let ptr = unsafe {
+            let ptr = self.head.as_ptr().add(self.head_len);
+            ptr::copy_nonoverlapping(string.as_ptr(), ptr, string.len());
+            NonNull::new_unchecked(ptr)
+        };
+        self.head_len += string.len();
+        Span {
+            ptr,
+            len: string.len(),
+        }
+    }
+}
+
+/// A handle that resolves the symbols of an [`AppendInterner`] without locking.
+///
+/// Readers are cheap to clone and may be sent to other threads. They keep the
+/// strings of their interner alive even after the interner has been dropped.
+pub struct AppendReader<S = DefaultSymbol> {
+    shared: Arc<Shared>,
+    marker: PhantomData<fn() -> S>,
+}
+
+impl<S> Clone for AppendReader<S> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            shared: self.shared.clone(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<S> fmt::Debug for AppendReader<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AppendReader")
+            .field("len", &self.shared.len())
+            .finish()
+    }
+}
+
+impl<S> AppendReader<S>
+where
+    S: Symbol,
+{
+    /// Returns the number of strings published by the interner so far.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.shared.len()
+    }
+
+    /// Returns `true` if the interner has not published any strings yet.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the string for the given symbol if it has been published.
+    #[inline]
+    pub fn resolve(&self, symbol: S) -> Option<&str> {
+        self.shared.resolve(symbol.to_usize())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn locate_works() {
+        assert_eq!(locate(0), (0, 0));
+        assert_eq!(locate(31), (0, 31));
+        assert_eq!(locate(32), (1, 0));
+        assert_eq!(locate(95), (1, 63));
+        assert_eq!(locate(96), (2, 0));
+        let (chunk, offset) = locate(usize::MAX - 32);
+        assert_eq!(chunk, CHUNKS - 1);
+        assert_eq!(offset, chunk_len(chunk) - 1);
+    }
+}
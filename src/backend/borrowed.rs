@@ -0,0 +1,485 @@
+#![cfg(feature = "backends")]
+
+#[cfg(feature = "allocator-api")]
+use super::BackendIn;
+use super::{
+    owned_str::OwnedStr,
+    Backend,
+    ContiguousBackend,
+};
+use crate::{
+    compat::{
+        Allocator,
+        Global,
+        VecIn,
+    },
+    symbol::{
+        expect_valid_symbol,
+        try_valid_symbol,
+    },
+    DefaultSymbol,
+    MemoryUsage,
+    Symbol,
+    TryReserveError,
+};
+use core::{
+    iter::Enumerate,
+    marker::PhantomData,
+    slice,
+};
+
+/// A backend that references borrowed strings instead of copying them.
+///
+/// Use this if the interned strings outlive the string interner anyway,
+/// e.g. identifiers of a source file that is kept alive for the whole compilation.
+///
+/// Strings interned via [`StringInterner::get_or_intern_borrowed`] are not copied
+/// and [`StringInterner::resolve_borrowed`] returns them as `&'a str` that is not
+/// tied to the borrow of the string interner. Strings interned via
+/// [`StringInterner::get_or_intern`] are copied since they might not live for `'a`.
+///
+/// Deserialization borrows the strings from the input if the format supports it.
+/// However, deserializing a string interner from human-readable input with
+/// duplicate strings copies all strings.
+///
+/// [`StringInterner::get_or_intern_borrowed`]: crate::StringInterner::get_or_intern_borrowed
+/// [`StringInterner::resolve_borrowed`]: crate::StringInterner::resolve_borrowed
+/// [`StringInterner::get_or_intern`]: crate::StringInterner::get_or_intern
+///
+/// # Usage Hint
+///
+/// Use this backend if the interned strings are already kept alive elsewhere.
+///
+/// # Usage
+///
+/// - **Fill:** Efficiency of filling an empty string interner.
+/// - **Resolve:** Efficiency of interned string look-up given a symbol.
+/// - **Allocations:** The number of allocations performed by the backend.
+/// - **Footprint:** The total heap memory consumed by the backend.
+/// - **Contiguous:** True if the returned symbols have contiguous values.
+///
+/// Rating varies between **bad**, **ok**, **good** and **best**.
+///
+/// | Scenario    |  Rating  |
+/// |:------------|:--------:|
+/// | Fill        | **best** |
+/// | Resolve     | **good** |
+/// | Allocations | **best** |
+/// | Footprint   | **good** |
+/// | Supports `get_or_intern_static` | **yes** |
+/// | `Send` + `Sync` | **yes** |
+/// | Contiguous  | **yes**  |
+#[derive(Debug)]
+pub struct BorrowedBackend<'a, S = DefaultSymbol, A = Global>
+where
+    A: Allocator,
+{
+    strings: VecIn<Entry<'a, A>, A>,
+    symbol_marker: PhantomData<fn() -> S>,
+}
+
+/// A string that is either borrowed or copied into the backend.
+#[derive(Debug, Clone)]
+enum Entry<'a, A>
+where
+    A: Allocator,
+{
+    Borrowed(&'a str),
+    Owned(OwnedStr<A>),
+}
+
+impl<'a, A> Entry<'a, A>
+where
+    A: Allocator,
+{
+    /// Returns the string of the entry.
+    #[inline]
+    fn as_str(&self) -> &str {
+        match self {
+            Self::Borrowed(string) => string,
+            Self::Owned(string) => string,
+        }
+    }
+}
+
+impl<'a, S, A> Default for BorrowedBackend<'a, S, A>
+where
+    A: Allocator + Default,
+{
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn default() -> Self {
+        Self {
+            strings: VecIn::new_in(A::default()),
+            symbol_marker: Default::default(),
+        }
+    }
+}
+
+impl<'a, S, A> BorrowedBackend<'a, S, A>
+where
+    A: Allocator,
+{
+    /// Creates a new empty backend that allocates from `alloc`.
+    ///
+    /// Unlike [`Default::default`] this does not require the allocator to
+    /// implement [`Default`], e.g. to allocate from a `&Bump` arena.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn new_in(alloc: A) -> Self {
+        Self {
+            strings: VecIn::new_in(alloc),
+            symbol_marker: Default::default(),
+        }
+    }
+}
+
+impl<'a, S, A> BorrowedBackend<'a, S, A>
+where
+    S: Symbol,
+    A: Allocator,
+{
+    /// Interns the borrowed string without copying it.
+    #[inline]
+    pub fn intern_borrowed(&mut self, string: &'a str) -> S {
+        self.push(Entry::Borrowed(string))
+    }
+
+    /// Resolves the given symbol to its borrowed string.
+    ///
+    /// Returns `None` if the symbol is invalid or if its string has been copied.
+    #[inline]
+    pub fn resolve_borrowed(&self, symbol: S) -> Option<&'a str> {
+        match self.strings.get(symbol.to_usize()) {
+            Some(Entry::Borrowed(string)) => Some(string),
+            _ => None,
+        }
+    }
+
+    /// Replaces the copied string of the symbol by the equal borrowed string.
+    #[inline]
+    pub(crate) fn borrow(&mut self, symbol: S, string: &'a str) {
+        if let Some(entry @ Entry::Owned(_)) = self.strings.get_mut(symbol.to_usize()) {
+            debug_assert_eq!(entry.as_str(), string);
+            *entry = Entry::Borrowed(string);
+        }
+    }
+
+    /// Pushes the string and returns its symbol.
+    #[inline]
+    fn push(&mut self, string: Entry<'a, A>) -> S {
+        let symbol = expect_valid_symbol(self.strings.len());
+        self.strings.push(string);
+        symbol
+    }
+}
+
+impl<'a, S, A> Backend for BorrowedBackend<'a, S, A>
+where
+    S: Symbol,
+    A: Allocator + Clone,
+{
+    type Symbol = S;
+
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn with_capacity(cap: usize) -> Self
+    where
+        Self: Default,
+    {
+        let mut backend = Self::default();
+        backend.strings.reserve_exact(cap);
+        backend
+    }
+
+    #[inline]
+    fn intern(&mut self, string: &str) -> Self::Symbol {
+        let alloc = self.strings.allocator().clone();
+        self.push(Entry::Owned(OwnedStr::new_in(string, alloc)))
+    }
+
+    #[inline]
+    fn try_intern(&mut self, string: &str) -> Result<Self::Symbol, TryReserveError> {
+        try_valid_symbol::<S>(self.strings.len())?;
+        self.strings.try_reserve(1)?;
+        let alloc = self.strings.allocator().clone();
+        Ok(self.push(Entry::Owned(OwnedStr::try_new_in(string, alloc)?)))
+    }
+
+    fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.strings.try_reserve(additional)?;
+        Ok(())
+    }
+
+    #[inline]
+    fn truncate(&mut self, len: usize) -> bool {
+        self.strings.truncate(len);
+        true
+    }
+
+    #[inline]
+    fn intern_static(&mut self, string: &'static str) -> Self::Symbol {
+        self.intern_borrowed(string)
+    }
+
+    fn shrink_to_fit(&mut self) {
+        self.strings.shrink_to_fit()
+    }
+
+    fn memory_usage(&self) -> Option<MemoryUsage> {
+        let usage = self
+            .strings
+            .iter()
+            .fold(MemoryUsage::of_vec_in(&self.strings), |usage, string| {
+                match string {
+                    Entry::Borrowed(_) => usage,
+                    Entry::Owned(string) => {
+                        usage
+                            + MemoryUsage {
+                                heap_bytes: string.len(),
+                                used_bytes: string.len(),
+                            }
+                    }
+                }
+            });
+        Some(usage)
+    }
+
+    #[inline]
+    fn resolve(&self, symbol: Self::Symbol) -> Option<&str> {
+        self.strings.get(symbol.to_usize()).map(Entry::as_str)
+    }
+
+    #[inline]
+    unsafe fn resolve_unchecked(&self, symbol: Self::Symbol) -> &str {
+        // SAFETY: The function is marked unsafe so that the caller guarantees
+        //         that required invariants are checked.
+        unsafe { self.strings.get_unchecked(symbol.to_usize()).as_str() }
+    }
+}
+
+impl<'a, S, A> ContiguousBackend for BorrowedBackend<'a, S, A>
+where
+    S: Symbol,
+    A: Allocator + Clone,
+{
+}
+
+#[cfg(feature = "allocator-api")]
+impl<'a, S, A> BackendIn<A> for BorrowedBackend<'a, S, A>
+where
+    S: Symbol,
+    A: Allocator + Clone,
+{
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn with_capacity_in(cap: usize, alloc: A) -> Self {
+        Self {
+            strings: VecIn::with_capacity_in(cap, alloc),
+            symbol_marker: Default::default(),
+        }
+    }
+}
+
+impl<'a, S, A> Clone for BorrowedBackend<'a, S, A>
+where
+    A: Allocator + Clone,
+{
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn clone(&self) -> Self {
+        Self {
+            strings: self.strings.clone(),
+            symbol_marker: Default::default(),
+        }
+    }
+}
+
+impl<'a, S, A> Eq for BorrowedBackend<'a, S, A>
+where
+    S: Symbol,
+    A: Allocator,
+{
+}
+
+impl<'a, S, A> PartialEq for BorrowedBackend<'a, S, A>
+where
+    S: Symbol,
+    A: Allocator,
+{
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn eq(&self, other: &Self) -> bool {
+        self.strings.len() == other.strings.len()
+            && self
+                .strings
+                .iter()
+                .zip(other.strings.iter())
+                .all(|(lhs, rhs)| lhs.as_str() == rhs.as_str())
+    }
+}
+
+impl<'a, 'b, S, A> IntoIterator for &'b BorrowedBackend<'a, S, A>
+where
+    S: Symbol,
+    A: Allocator,
+{
+    type Item = (S, &'b str);
+    type IntoIter = Iter<'a, 'b, S, A>;
+
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn into_iter(self) -> Self::IntoIter {
+        Self::IntoIter::new(self)
+    }
+}
+
+pub struct Iter<'a, 'b, S, A = Global>
+where
+    A: Allocator,
+{
+    iter: Enumerate<slice::Iter<'b, Entry<'a, A>>>,
+    symbol_marker: PhantomData<fn() -> S>,
+}
+
+impl<'a, 'b, S, A> Iter<'a, 'b, S, A>
+where
+    A: Allocator,
+{
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn new(backend: &'b BorrowedBackend<'a, S, A>) -> Self {
+        Self {
+            iter: backend.strings.iter().enumerate(),
+            symbol_marker: Default::default(),
+        }
+    }
+}
+
+impl<'a, 'b, S, A> Iterator for Iter<'a, 'b, S, A>
+where
+    S: Symbol,
+    A: Allocator,
+{
+    type Item = (S, &'b str);
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter
+            .next()
+            .map(|(id, string)| (expect_valid_symbol(id), string.as_str()))
+    }
+}
+
+/// Serializes the strings of the backend as a sequence.
+#[cfg(feature = "serde-1")]
+impl<'a, S, A> serde::Serialize for BorrowedBackend<'a, S, A>
+where
+    S: Symbol,
+    A: Allocator + Clone,
+{
+    fn serialize<T>(&self, serializer: T) -> Result<T::Ok, T::Error>
+    where
+        T: serde::Serializer,
+    {
+        crate::serde_impl::serialize_strings::<Self, T>(self, serializer)
+    }
+}
+
+/// Deserializes the backend from a sequence of strings.
+///
+/// Strings are borrowed from the input if the deserializer supports it and copied otherwise.
+#[cfg(feature = "serde-1")]
+impl<'de, 'a, S, A> serde::Deserialize<'de> for BorrowedBackend<'a, S, A>
+where
+    'de: 'a,
+    S: Symbol,
+    A: Allocator + Clone + Default,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(BorrowedVisitor(PhantomData))
+    }
+}
+
+#[cfg(feature = "serde-1")]
+struct BorrowedVisitor<'a, S, A>(PhantomData<fn() -> BorrowedBackend<'a, S, A>>)
+where
+    A: Allocator;
+
+#[cfg(feature = "serde-1")]
+impl<'de, 'a, S, A> serde::de::Visitor<'de> for BorrowedVisitor<'a, S, A>
+where
+    'de: 'a,
+    S: Symbol,
+    A: Allocator + Clone + Default,
+{
+    type Value = BorrowedBackend<'a, S, A>;
+
+    fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter.write_str("Expected a sequence of strings.")
+    }
+
+    fn visit_seq<V>(self, mut seq: V) -> Result<Self::Value, V::Error>
+    where
+        V: serde::de::SeqAccess<'de>,
+    {
+        use serde::de::Error as _;
+        let capacity = crate::serde_impl::cautious_capacity::<&str>(seq.size_hint());
+        let mut backend = BorrowedBackend::<S, A>::with_capacity(capacity);
+        let alloc = backend.strings.allocator().clone();
+        while let Some(string) = seq.next_element_seed(BorrowedStrSeed(&alloc))? {
+            S::try_from_usize(backend.strings.len()).ok_or_else(|| {
+                V::Error::custom("encountered too many strings for symbol")
+            })?;
+            backend.strings.push(string);
+        }
+        Ok(backend)
+    }
+}
+
+/// Deserializes a string that borrows from the input if possible.
+///
+/// Otherwise copies the string into an allocation of the given allocator.
+#[cfg(feature = "serde-1")]
+struct BorrowedStrSeed<'b, A>(&'b A);
+
+#[cfg(feature = "serde-1")]
+impl<'de, 'b, A> serde::de::DeserializeSeed<'de> for BorrowedStrSeed<'b, A>
+where
+    A: Allocator + Clone,
+{
+    type Value = Entry<'de, A>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_str(self)
+    }
+}
+
+#[cfg(feature = "serde-1")]
+impl<'de, 'b, A> serde::de::Visitor<'de> for BorrowedStrSeed<'b, A>
+where
+    A: Allocator + Clone,
+{
+    type Value = Entry<'de, A>;
+
+    fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter.write_str("Expected a string.")
+    }
+
+    fn visit_borrowed_str<E>(self, string: &'de str) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(Entry::Borrowed(string))
+    }
+
+    fn visit_str<E>(self, string: &str) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(Entry::Owned(OwnedStr::new_in(string, self.0.clone())))
+    }
+}
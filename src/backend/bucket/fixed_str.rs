@@ -1,29 +1,70 @@
 use super::InternedStr;
-#[cfg(not(feature = "std"))]
-use alloc::string::String;
+use crate::{
+    compat::{
+        Allocator,
+        Global,
+        VecIn,
+    },
+    TryReserveError,
+};
 
-#[derive(Debug, Default, Clone, PartialEq, Eq)]
-pub struct FixedString {
-    contents: String,
+/// A string with a fixed capacity that is allocated from an allocator of type `A`.
+///
+/// The contents are always valid UTF-8.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FixedString<A = Global>
+where
+    A: Allocator,
+{
+    contents: VecIn<u8, A>,
 }
 
-impl FixedString {
-    /// Creates a new fixed string with the given fixed capacity.
+impl<A> Default for FixedString<A>
+where
+    A: Allocator + Default,
+{
     #[inline]
-    pub fn with_capacity(cap: usize) -> Self {
+    fn default() -> Self {
         Self {
-            contents: String::with_capacity(cap),
+            contents: VecIn::new_in(A::default()),
+        }
+    }
+}
+
+impl<A> FixedString<A>
+where
+    A: Allocator,
+{
+    /// Creates a new fixed string with the given fixed capacity allocated from `alloc`.
+    #[inline]
+    pub fn with_capacity_in(cap: usize, alloc: A) -> Self {
+        Self {
+            contents: VecIn::with_capacity_in(cap, alloc),
         }
     }
 
-    /// Returns the underlying [`Box<str>`].
+    /// Tries to create a new fixed string with the given fixed capacity allocated from `alloc`.
+    #[inline]
+    pub fn try_with_capacity_in(cap: usize, alloc: A) -> Result<Self, TryReserveError> {
+        let mut contents = VecIn::new_in(alloc);
+        contents.try_reserve_exact(cap)?;
+        Ok(Self { contents })
+    }
+
+    /// Returns the underlying buffer.
     ///
     /// Guarantees not to perform any reallocations in this process.
     #[inline]
-    pub fn finish(self) -> String {
+    pub fn finish(self) -> VecIn<u8, A> {
         self.contents
     }
 
+    /// Returns the allocator of the fixed string.
+    #[inline]
+    pub fn allocator(&self) -> &A {
+        self.contents.allocator()
+    }
+
     /// Returns the capacity in bytes of the fixed string.
     #[inline]
     pub fn capacity(&self) -> usize {
@@ -46,14 +87,14 @@ impl FixedString {
         if self.capacity() < len + string.len() {
             return None
         }
-        self.contents.push_str(string);
+        self.contents.extend_from_slice(string.as_bytes());
         debug_assert_eq!(self.contents.len(), len + string.len());
         Some(InternedStr::new(
             // SAFETY: We convert from bytes to utf8 from which we know through the
             //         input string that they must represent valid utf8.
             unsafe {
                 core::str::from_utf8_unchecked(
-                    &self.contents.as_bytes()[len..len + string.len()],
+                    &self.contents[len..len + string.len()],
                 )
             },
         ))
@@ -7,15 +7,27 @@ use self::{
     fixed_str::FixedString,
     interned_str::InternedStr,
 };
-use super::Backend;
+#[cfg(feature = "allocator-api")]
+use super::BackendIn;
+use super::{
+    Backend,
+    ContiguousBackend,
+};
 use crate::{
-    compat::Vec,
-    symbol::expect_valid_symbol,
+    compat::{
+        Allocator,
+        Global,
+        VecIn,
+    },
+    symbol::{
+        expect_valid_symbol,
+        try_valid_symbol,
+    },
     DefaultSymbol,
+    MemoryUsage,
     Symbol,
+    TryReserveError,
 };
-#[cfg(not(feature = "std"))]
-use alloc::string::String;
 use core::{
     iter::Enumerate,
     marker::PhantomData,
@@ -53,10 +65,13 @@ use core::{
 /// | `Send` + `Sync` | **yes** |
 /// | Contiguous  | **yes**  |
 #[derive(Debug)]
-pub struct BucketBackend<S = DefaultSymbol> {
-    spans: Vec<InternedStr>,
-    head: FixedString,
-    full: Vec<String>,
+pub struct BucketBackend<S = DefaultSymbol, A = Global>
+where
+    A: Allocator,
+{
+    spans: VecIn<InternedStr, A>,
+    head: FixedString<A>,
+    full: VecIn<VecIn<u8, A>, A>,
     marker: PhantomData<fn() -> S>,
 }
 
@@ -65,42 +80,77 @@ pub struct BucketBackend<S = DefaultSymbol> {
 /// The bucket backend requires a manual [`Send`] impl because it is self
 /// referential. When cloning a bucket backend a deep clone is performed and
 /// all references to itself are updated for the clone.
-unsafe impl<S> Send for BucketBackend<S> where S: Symbol {}
+unsafe impl<S, A> Send for BucketBackend<S, A>
+where
+    S: Symbol,
+    A: Allocator + Send,
+{
+}
 
 /// # Safety
 ///
 /// The bucket backend requires a manual [`Send`] impl because it is self
 /// referential. Those references won't escape its own scope and also
 /// the bucket backend has no interior mutability.
-unsafe impl<S> Sync for BucketBackend<S> where S: Symbol {}
+unsafe impl<S, A> Sync for BucketBackend<S, A>
+where
+    S: Symbol,
+    A: Allocator + Sync,
+{
+}
 
-impl<S> Default for BucketBackend<S> {
+impl<S, A> Default for BucketBackend<S, A>
+where
+    A: Allocator + Default,
+{
     #[cfg_attr(feature = "inline-more", inline)]
     fn default() -> Self {
         Self {
-            spans: Vec::new(),
+            spans: VecIn::new_in(A::default()),
             head: FixedString::default(),
-            full: Vec::new(),
+            full: VecIn::new_in(A::default()),
             marker: Default::default(),
         }
     }
 }
 
-impl<S> Backend for BucketBackend<S>
+impl<S, A> BucketBackend<S, A>
 where
-    S: Symbol,
+    A: Allocator + Clone,
 {
-    type Symbol = S;
-
+    /// Creates a new empty backend that allocates from `alloc`.
+    ///
+    /// Unlike [`Default::default`] this does not require the allocator to
+    /// implement [`Default`], e.g. to allocate from a `&Bump` arena.
     #[cfg_attr(feature = "inline-more", inline)]
-    fn with_capacity(cap: usize) -> Self {
+    pub fn new_in(alloc: A) -> Self {
         Self {
-            spans: Vec::with_capacity(cap),
-            head: FixedString::with_capacity(cap),
-            full: Vec::new(),
+            spans: VecIn::new_in(alloc.clone()),
+            head: FixedString::with_capacity_in(0, alloc.clone()),
+            full: VecIn::new_in(alloc),
             marker: Default::default(),
         }
     }
+}
+
+impl<S, A> Backend for BucketBackend<S, A>
+where
+    S: Symbol,
+    A: Allocator + Clone,
+{
+    type Symbol = S;
+
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn with_capacity(cap: usize) -> Self
+    where
+        Self: Default,
+    {
+        let mut backend = Self::default();
+        backend.spans.reserve_exact(cap);
+        let alloc = backend.head.allocator().clone();
+        backend.head = FixedString::with_capacity_in(cap, alloc);
+        backend
+    }
 
     #[inline]
     fn intern(&mut self, string: &str) -> Self::Symbol {
@@ -111,6 +161,29 @@ where
         self.push_span(interned)
     }
 
+    #[inline]
+    fn try_intern(&mut self, string: &str) -> Result<Self::Symbol, TryReserveError> {
+        try_valid_symbol::<S>(self.spans.len())?;
+        self.spans.try_reserve(1)?;
+        // SAFETY: This is safe because we never hand out the returned
+        //         interned string instance to the outside and only operate
+        //         on it within this backend.
+        let interned = unsafe { self.try_alloc(string)? };
+        Ok(self.push_span(interned))
+    }
+
+    fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.spans.try_reserve(additional)?;
+        Ok(())
+    }
+
+    #[inline]
+    fn truncate(&mut self, len: usize) -> bool {
+        // The buckets keep the bytes of the removed strings until dropped.
+        self.spans.truncate(len);
+        true
+    }
+
     #[cfg_attr(feature = "inline-more", inline)]
     fn intern_static(&mut self, string: &'static str) -> Self::Symbol {
         let interned = InternedStr::new(string);
@@ -123,6 +196,20 @@ where
         self.full.shrink_to_fit();
     }
 
+    fn memory_usage(&self) -> Option<MemoryUsage> {
+        let head = MemoryUsage {
+            heap_bytes: self.head.capacity(),
+            used_bytes: self.head.len(),
+        };
+        let usage = self.full.iter().fold(
+            MemoryUsage::of_vec_in(&self.spans)
+                + MemoryUsage::of_vec_in(&self.full)
+                + head,
+            |usage, full| usage + MemoryUsage::of_vec_in(full),
+        );
+        Some(usage)
+    }
+
     #[inline]
     fn resolve(&self, symbol: Self::Symbol) -> Option<&str> {
         self.spans.get(symbol.to_usize()).map(InternedStr::as_str)
@@ -136,9 +223,34 @@ where
     }
 }
 
-impl<S> BucketBackend<S>
+impl<S, A> ContiguousBackend for BucketBackend<S, A>
 where
     S: Symbol,
+    A: Allocator + Clone,
+{
+}
+
+#[cfg(feature = "allocator-api")]
+impl<S, A> BackendIn<A> for BucketBackend<S, A>
+where
+    S: Symbol,
+    A: Allocator + Clone,
+{
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn with_capacity_in(cap: usize, alloc: A) -> Self {
+        Self {
+            spans: VecIn::with_capacity_in(cap, alloc.clone()),
+            head: FixedString::with_capacity_in(cap, alloc.clone()),
+            full: VecIn::new_in(alloc),
+            marker: Default::default(),
+        }
+    }
+}
+
+impl<S, A> BucketBackend<S, A>
+where
+    S: Symbol,
+    A: Allocator + Clone,
 {
     /// Returns the next available symbol.
     fn next_symbol(&self) -> S {
@@ -157,7 +269,8 @@ where
         let cap = self.head.capacity();
         if cap < self.head.len() + string.len() {
             let new_cap = (usize::max(cap, string.len()) + 1).next_power_of_two();
-            let new_head = FixedString::with_capacity(new_cap);
+            let alloc = self.head.allocator().clone();
+            let new_head = FixedString::with_capacity_in(new_cap, alloc);
             let old_head = core::mem::replace(&mut self.head, new_head);
             self.full.push(old_head.finish());
         }
@@ -165,16 +278,41 @@ where
             .push_str(string)
             .expect("encountered invalid head capacity (2)")
     }
+
+    /// Tries to intern a new string into the backend and returns a reference to it.
+    ///
+    /// Leaves the backend unchanged if an allocation fails.
+    unsafe fn try_alloc(&mut self, string: &str) -> Result<InternedStr, TryReserveError> {
+        let cap = self.head.capacity();
+        if cap < self.head.len() + string.len() {
+            let new_cap = (usize::max(cap, string.len()) + 1)
+                .checked_next_power_of_two()
+                .ok_or(TryReserveError::CapacityOverflow)?;
+            self.full.try_reserve(1)?;
+            let alloc = self.head.allocator().clone();
+            let new_head = FixedString::try_with_capacity_in(new_cap, alloc)?;
+            let old_head = core::mem::replace(&mut self.head, new_head);
+            self.full.push(old_head.finish());
+        }
+        Ok(self
+            .head
+            .push_str(string)
+            .expect("encountered invalid head capacity (3)"))
+    }
 }
 
-impl<S> Clone for BucketBackend<S> {
+impl<S, A> Clone for BucketBackend<S, A>
+where
+    A: Allocator + Clone,
+{
     fn clone(&self) -> Self {
         // For performance reasons we copy all cloned strings into a single cloned
         // head string leaving the cloned `full` empty.
         let new_head_cap =
             self.head.capacity() + self.full.iter().fold(0, |lhs, rhs| lhs + rhs.len());
-        let mut head = FixedString::with_capacity(new_head_cap);
-        let mut spans = Vec::with_capacity(self.spans.len());
+        let alloc = self.head.allocator();
+        let mut head = FixedString::with_capacity_in(new_head_cap, alloc.clone());
+        let mut spans = VecIn::with_capacity_in(self.spans.len(), alloc.clone());
         for span in &self.spans {
             let string = span.as_str();
             let interned = head
@@ -185,17 +323,23 @@ impl<S> Clone for BucketBackend<S> {
         Self {
             spans,
             head,
-            full: Vec::new(),
+            full: VecIn::new_in(alloc.clone()),
             marker: Default::default(),
         }
     }
 }
 
-impl<S> Eq for BucketBackend<S> where S: Symbol {}
+impl<S, A> Eq for BucketBackend<S, A>
+where
+    S: Symbol,
+    A: Allocator,
+{
+}
 
-impl<S> PartialEq for BucketBackend<S>
+impl<S, A> PartialEq for BucketBackend<S, A>
 where
     S: Symbol,
+    A: Allocator,
 {
     #[cfg_attr(feature = "inline-more", inline)]
     fn eq(&self, other: &Self) -> bool {
@@ -203,9 +347,10 @@ where
     }
 }
 
-impl<'a, S> IntoIterator for &'a BucketBackend<S>
+impl<'a, S, A> IntoIterator for &'a BucketBackend<S, A>
 where
     S: Symbol,
+    A: Allocator,
 {
     type Item = (S, &'a str);
     type IntoIter = Iter<'a, S>;
@@ -223,7 +368,10 @@ pub struct Iter<'a, S> {
 
 impl<'a, S> Iter<'a, S> {
     #[cfg_attr(feature = "inline-more", inline)]
-    pub fn new(backend: &'a BucketBackend<S>) -> Self {
+    pub fn new<A>(backend: &'a BucketBackend<S, A>) -> Self
+    where
+        A: Allocator,
+    {
         Self {
             iter: backend.spans.iter().enumerate(),
             symbol_marker: Default::default(),
@@ -249,3 +397,33 @@ where
             .map(|(id, interned)| (expect_valid_symbol(id), interned.as_str()))
     }
 }
+
+/// Serializes the strings of the backend as a sequence.
+#[cfg(feature = "serde-1")]
+impl<S, A> serde::Serialize for BucketBackend<S, A>
+where
+    S: Symbol,
+    A: Allocator + Clone,
+{
+    fn serialize<T>(&self, serializer: T) -> Result<T::Ok, T::Error>
+    where
+        T: serde::Serializer,
+    {
+        crate::serde_impl::serialize_strings::<Self, T>(self, serializer)
+    }
+}
+
+/// Deserializes the backend by interning a sequence of strings.
+#[cfg(feature = "serde-1")]
+impl<'de, S, A> serde::Deserialize<'de> for BucketBackend<S, A>
+where
+    S: Symbol,
+    A: Allocator + Clone + Default,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        crate::serde_impl::deserialize_strings::<Self, D>(deserializer)
+    }
+}
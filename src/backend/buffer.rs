@@ -1,11 +1,22 @@
 #![cfg(feature = "backends")]
 
+#[cfg(feature = "allocator-api")]
+use super::BackendIn;
 use super::Backend;
 use crate::{
-    compat::Vec,
-    symbol::expect_valid_symbol,
+    compat::{
+        Allocator,
+        Global,
+        VecIn,
+    },
+    symbol::{
+        expect_valid_symbol,
+        try_valid_symbol,
+    },
     DefaultSymbol,
+    MemoryUsage,
     Symbol,
+    TryReserveError,
 };
 use core::{
     marker::PhantomData,
@@ -40,24 +51,36 @@ use core::{
 /// | `Send` + `Sync` | **yes** |
 /// | Contiguous  | **no**   |
 #[derive(Debug)]
-pub struct BufferBackend<S = DefaultSymbol> {
+pub struct BufferBackend<S = DefaultSymbol, A = Global>
+where
+    A: Allocator,
+{
     len_strings: usize,
-    buffer: Vec<u8>,
+    buffer: VecIn<u8, A>,
     marker: PhantomData<fn() -> S>,
 }
 
-impl<S> PartialEq for BufferBackend<S>
+impl<S, A> PartialEq for BufferBackend<S, A>
 where
     S: Symbol,
+    A: Allocator,
 {
     fn eq(&self, other: &Self) -> bool {
         self.len_strings.eq(&other.len_strings) && self.buffer.eq(&other.buffer)
     }
 }
 
-impl<S> Eq for BufferBackend<S> where S: Symbol {}
+impl<S, A> Eq for BufferBackend<S, A>
+where
+    S: Symbol,
+    A: Allocator,
+{
+}
 
-impl<S> Clone for BufferBackend<S> {
+impl<S, A> Clone for BufferBackend<S, A>
+where
+    A: Allocator + Clone,
+{
     fn clone(&self) -> Self {
         Self {
             len_strings: self.len_strings,
@@ -67,20 +90,57 @@ impl<S> Clone for BufferBackend<S> {
     }
 }
 
-impl<S> Default for BufferBackend<S> {
+impl<S, A> Default for BufferBackend<S, A>
+where
+    A: Allocator + Default,
+{
     #[cfg_attr(feature = "inline-more", inline)]
     fn default() -> Self {
         Self {
             len_strings: 0,
-            buffer: Default::default(),
+            buffer: VecIn::new_in(A::default()),
             marker: Default::default(),
         }
     }
 }
 
-impl<S> BufferBackend<S>
+impl<S, A> BufferBackend<S, A>
+where
+    A: Allocator,
+{
+    /// Creates a new empty backend that allocates from `alloc`.
+    ///
+    /// Unlike [`Default::default`] this does not require the allocator to
+    /// implement [`Default`], e.g. to allocate from a `&Bump` arena.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn new_in(alloc: A) -> Self {
+        Self {
+            len_strings: 0,
+            buffer: VecIn::new_in(alloc),
+            marker: Default::default(),
+        }
+    }
+}
+
+/// We encode the `usize` string length into the buffer as well.
+const LEN_USIZE: usize = mem::size_of::<usize>();
+
+/// According to google the approx. word length is 5.
+const DEFAULT_STR_LEN: usize = 5;
+
+/// The maximum number of bytes of a `var7` encoded `usize` value.
+const MAX_VAR_USIZE_LEN: usize = (usize::BITS as usize).div_ceil(7);
+
+/// Returns the estimated number of buffer bytes for the given number of strings.
+#[inline]
+fn buffer_capacity(len_strings: usize) -> usize {
+    len_strings * DEFAULT_STR_LEN * LEN_USIZE
+}
+
+impl<S, A> BufferBackend<S, A>
 where
     S: Symbol,
+    A: Allocator,
 {
     /// Returns the next available symbol.
     #[inline]
@@ -156,24 +216,21 @@ where
     }
 }
 
-impl<S> Backend for BufferBackend<S>
+impl<S, A> Backend for BufferBackend<S, A>
 where
     S: Symbol,
+    A: Allocator,
 {
     type Symbol = S;
 
     #[cfg_attr(feature = "inline-more", inline)]
-    fn with_capacity(capacity: usize) -> Self {
-        /// We encode the `usize` string length into the buffer as well.
-        const LEN_USIZE: usize = mem::size_of::<usize>();
-        /// According to google the approx. word length is 5.
-        const DEFAULT_STR_LEN: usize = 5;
-        let bytes_per_string = DEFAULT_STR_LEN * LEN_USIZE;
-        Self {
-            len_strings: 0,
-            buffer: Vec::with_capacity(capacity * bytes_per_string),
-            marker: Default::default(),
-        }
+    fn with_capacity(capacity: usize) -> Self
+    where
+        Self: Default,
+    {
+        let mut backend = Self::default();
+        backend.buffer.reserve_exact(buffer_capacity(capacity));
+        backend
     }
 
     #[inline]
@@ -181,6 +238,40 @@ where
         self.push_string(string)
     }
 
+    #[inline]
+    fn try_intern(&mut self, string: &str) -> Result<Self::Symbol, TryReserveError> {
+        let bytes = string
+            .len()
+            .checked_add(MAX_VAR_USIZE_LEN)
+            .ok_or(TryReserveError::CapacityOverflow)?;
+        try_valid_symbol::<S>(self.buffer.len())?;
+        self.buffer.try_reserve(bytes)?;
+        Ok(self.push_string(string))
+    }
+
+    fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        let bytes = additional
+            .checked_mul(DEFAULT_STR_LEN * LEN_USIZE)
+            .ok_or(TryReserveError::CapacityOverflow)?;
+        self.buffer.try_reserve(bytes)?;
+        Ok(())
+    }
+
+    fn truncate(&mut self, len: usize) -> bool {
+        if len < self.len_strings {
+            let mut end = 0;
+            for _ in 0..len {
+                end = self
+                    .resolve_index_to_str(end)
+                    .map(|(_string, next)| next)
+                    .expect("encountered invalid string in buffer");
+            }
+            self.buffer.truncate(end);
+            self.len_strings = len;
+        }
+        true
+    }
+
     #[inline]
     fn resolve(&self, symbol: Self::Symbol) -> Option<&str> {
         self.resolve_index_to_str(symbol.to_usize())
@@ -191,6 +282,35 @@ where
         self.buffer.shrink_to_fit();
     }
 
+    fn memory_usage(&self) -> Option<MemoryUsage> {
+        Some(MemoryUsage::of_vec_in(&self.buffer))
+    }
+
+    #[cfg(feature = "serde-1")]
+    fn serialize_storage<T>(&self, serializer: T) -> Result<Result<T::Ok, T::Error>, T>
+    where
+        T: serde::Serializer,
+    {
+        if serializer.is_human_readable() {
+            return Err(serializer)
+        }
+        Ok(self.serialize_raw(serializer))
+    }
+
+    #[cfg(feature = "serde-1")]
+    fn deserialize_storage<'de, D>(
+        &mut self,
+        deserializer: D,
+    ) -> Result<Result<(), D::Error>, D>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        if deserializer.is_human_readable() {
+            return Err(deserializer)
+        }
+        Ok(self.deserialize_raw(deserializer))
+    }
+
     #[inline]
     unsafe fn resolve_unchecked(&self, symbol: Self::Symbol) -> &str {
         // SAFETY: The function is marked unsafe so that the caller guarantees
@@ -199,11 +319,30 @@ where
     }
 }
 
+#[cfg(feature = "allocator-api")]
+impl<S, A> BackendIn<A> for BufferBackend<S, A>
+where
+    S: Symbol,
+    A: Allocator,
+{
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn with_capacity_in(capacity: usize, alloc: A) -> Self {
+        Self {
+            len_strings: 0,
+            buffer: VecIn::with_capacity_in(buffer_capacity(capacity), alloc),
+            marker: Default::default(),
+        }
+    }
+}
+
 /// Encodes the value using variable length encoding into the buffer.
 ///
 /// Returns the amount of bytes used for the encoding.
 #[inline]
-fn encode_var_usize(buffer: &mut Vec<u8>, mut value: usize) -> usize {
+fn encode_var_usize<A>(buffer: &mut VecIn<u8, A>, mut value: usize) -> usize
+where
+    A: Allocator,
+{
     if value <= 0x7F {
         // Shortcut the common case for low value.
         buffer.push(value as u8);
@@ -275,14 +414,191 @@ fn decode_var_usize(buffer: &[u8]) -> Option<(usize, usize)> {
     Some((result, i + 1))
 }
 
+impl<'a, S, A> IntoIterator for &'a BufferBackend<S, A>
+where
+    S: Symbol,
+    A: Allocator,
+{
+    type Item = (S, &'a str);
+    type IntoIter = Iter<'a, S, A>;
+
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn into_iter(self) -> Self::IntoIter {
+        Self::IntoIter::new(self)
+    }
+}
+
+pub struct Iter<'a, S, A = Global>
+where
+    A: Allocator,
+{
+    backend: &'a BufferBackend<S, A>,
+    yielded: usize,
+    current: usize,
+}
+
+impl<'a, S, A> Iter<'a, S, A>
+where
+    A: Allocator,
+{
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn new(backend: &'a BufferBackend<S, A>) -> Self {
+        Self {
+            backend,
+            yielded: 0,
+            current: 0,
+        }
+    }
+}
+
+impl<'a, S, A> Iterator for Iter<'a, S, A>
+where
+    S: Symbol,
+    A: Allocator,
+{
+    type Item = (S, &'a str);
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.len();
+        (remaining, Some(remaining))
+    }
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.backend.resolve_index_to_str(self.current).and_then(
+            |(string, next_string_index)| {
+                let symbol = S::try_from_usize(self.current)?;
+                self.current = next_string_index;
+                self.yielded += 1;
+                Some((symbol, string))
+            },
+        )
+    }
+}
+
+impl<'a, S, A> ExactSizeIterator for Iter<'a, S, A>
+where
+    S: Symbol,
+    A: Allocator,
+{
+    fn len(&self) -> usize {
+        self.backend.len_strings - self.yielded
+    }
+}
+
+#[cfg(feature = "serde-1")]
+impl<S, A> BufferBackend<S, A>
+where
+    S: Symbol,
+    A: Allocator,
+{
+    /// Serializes the number of strings and the raw buffer of the backend as a byte blob.
+    fn serialize_raw<T>(&self, serializer: T) -> Result<T::Ok, T::Error>
+    where
+        T: serde::Serializer,
+    {
+        use crate::serde_impl::Bytes;
+        use serde::ser::SerializeTuple;
+        let mut tuple = serializer.serialize_tuple(2)?;
+        tuple.serialize_element(&(self.len_strings as u64))?;
+        tuple.serialize_element(&Bytes(&self.buffer))?;
+        tuple.end()
+    }
+
+    /// Replaces the strings of the backend with the number of strings and raw
+    /// buffer written by [`BufferBackend::serialize_raw`].
+    ///
+    /// Validates that the buffer consists of exactly the given number of
+    /// length prefixed UTF-8 strings.
+    fn deserialize_raw<'de, D>(&mut self, deserializer: D) -> Result<(), D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use crate::serde_impl::ByteBuf;
+        use serde::{
+            de::Error as _,
+            Deserialize as _,
+        };
+        let (len_strings, ByteBuf(buffer)) = <(u64, ByteBuf)>::deserialize(deserializer)?;
+        let mut index = 0;
+        let mut count = 0_u64;
+        while index < buffer.len() {
+            S::try_from_usize(index)
+                .ok_or_else(|| D::Error::custom("encountered too many strings for symbol"))?;
+            let (str_len, str_len_bytes) = decode_var_usize(&buffer[index..])
+                .ok_or_else(|| D::Error::custom("encountered invalid string length"))?;
+            let str_bytes = (index + str_len_bytes)
+                .checked_add(str_len)
+                .and_then(|end| buffer.get(index + str_len_bytes..end))
+                .ok_or_else(|| D::Error::custom("encountered out of bounds string"))?;
+            str::from_utf8(str_bytes)
+                .map_err(|_| D::Error::custom("encountered invalid UTF-8 string"))?;
+            index += str_len_bytes + str_len;
+            count += 1;
+        }
+        if count != len_strings {
+            return Err(D::Error::custom("encountered mismatching number of strings"))
+        }
+        self.len_strings = count as usize;
+        self.buffer.clear();
+        self.buffer.extend_from_slice(&buffer);
+        Ok(())
+    }
+}
+
+/// Serializes the strings of the backend as a sequence for human-readable formats.
+///
+/// Otherwise serializes the number of strings and the raw buffer of the
+/// backend as a byte blob.
+#[cfg(feature = "serde-1")]
+impl<S, A> serde::Serialize for BufferBackend<S, A>
+where
+    S: Symbol,
+    A: Allocator,
+{
+    fn serialize<T>(&self, serializer: T) -> Result<T::Ok, T::Error>
+    where
+        T: serde::Serializer,
+    {
+        if serializer.is_human_readable() {
+            return crate::serde_impl::serialize_strings::<Self, T>(self, serializer)
+        }
+        self.serialize_raw(serializer)
+    }
+}
+
+/// Deserializes the backend by interning a sequence of strings for human-readable
+/// formats. Otherwise deserializes the number of strings and the raw buffer of the backend.
+///
+/// Validates that the buffer consists of exactly the given number of
+/// length prefixed UTF-8 strings.
+#[cfg(feature = "serde-1")]
+impl<'de, S, A> serde::Deserialize<'de> for BufferBackend<S, A>
+where
+    S: Symbol,
+    A: Allocator + Default,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        if deserializer.is_human_readable() {
+            return crate::serde_impl::deserialize_strings::<Self, D>(deserializer)
+        }
+        let mut backend = Self::default();
+        backend.deserialize_raw(deserializer)?;
+        Ok(backend)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::{
         decode_var_usize,
         encode_var_usize,
     };
-    #[cfg(not(feature = "std"))]
-    use alloc::vec::Vec;
+    use crate::compat::VecIn as Vec;
 
     #[test]
     fn encode_var_usize_1_byte_works() {
@@ -405,67 +721,3 @@ mod tests {
         // );
     }
 }
-
-impl<'a, S> IntoIterator for &'a BufferBackend<S>
-where
-    S: Symbol,
-{
-    type Item = (S, &'a str);
-    type IntoIter = Iter<'a, S>;
-
-    #[cfg_attr(feature = "inline-more", inline)]
-    fn into_iter(self) -> Self::IntoIter {
-        Self::IntoIter::new(self)
-    }
-}
-
-pub struct Iter<'a, S> {
-    backend: &'a BufferBackend<S>,
-    yielded: usize,
-    current: usize,
-}
-
-impl<'a, S> Iter<'a, S> {
-    #[cfg_attr(feature = "inline-more", inline)]
-    pub fn new(backend: &'a BufferBackend<S>) -> Self {
-        Self {
-            backend,
-            yielded: 0,
-            current: 0,
-        }
-    }
-}
-
-impl<'a, S> Iterator for Iter<'a, S>
-where
-    S: Symbol,
-{
-    type Item = (S, &'a str);
-
-    #[inline]
-    fn size_hint(&self) -> (usize, Option<usize>) {
-        let remaining = self.len();
-        (remaining, Some(remaining))
-    }
-
-    #[inline]
-    fn next(&mut self) -> Option<Self::Item> {
-        self.backend.resolve_index_to_str(self.current).and_then(
-            |(string, next_string_index)| {
-                let symbol = S::try_from_usize(self.current)?;
-                self.current = next_string_index;
-                self.yielded += 1;
-                Some((symbol, string))
-            },
-        )
-    }
-}
-
-impl<'a, S> ExactSizeIterator for Iter<'a, S>
-where
-    S: Symbol,
-{
-    fn len(&self) -> usize {
-        self.backend.len_strings - self.yielded
-    }
-}
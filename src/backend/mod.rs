@@ -4,19 +4,30 @@
 //! There are trade-offs for the different kinds of backends. A user should
 //! find the backend that suits their use case best.
 
+mod borrowed;
 mod bucket;
 mod buffer;
+mod owned_str;
 mod simple;
+mod source;
 mod string;
 
 #[cfg(feature = "backends")]
 pub use self::{
+    borrowed::BorrowedBackend,
     bucket::BucketBackend,
     buffer::BufferBackend,
     simple::SimpleBackend,
+    source::SourceBackend,
     string::StringBackend,
 };
-use crate::Symbol;
+#[cfg(feature = "allocator-api")]
+use crate::compat::Allocator;
+use crate::{
+    MemoryUsage,
+    Symbol,
+    TryReserveError,
+};
 
 #[cfg(not(feature = "backends"))]
 /// Indicates that no proper backend is in use.
@@ -37,14 +48,22 @@ cfg_if::cfg_if! {
 /// The job of a backend is to actually store, manage and organize the interned
 /// strings. Different backends have different trade-offs. Users should pick
 /// their backend with hinsight of their personal use-case.
-pub trait Backend: Default {
+///
+/// Backends are not required to implement [`Default`] so that they can hold
+/// allocators that cannot be created out of thin air such as `&Bump`. However,
+/// only backends implementing it can be created via [`StringInterner::new`].
+///
+/// [`StringInterner::new`]: crate::StringInterner::new
+pub trait Backend: Sized {
     /// The symbol used by the string interner backend.
     type Symbol: Symbol;
 
     /// Creates a new backend for the given capacity.
     ///
     /// The capacity denotes how many strings are expected to be interned.
-    fn with_capacity(cap: usize) -> Self;
+    fn with_capacity(cap: usize) -> Self
+    where
+        Self: Default;
 
     /// Interns the given string and returns its interned ref and symbol.
     ///
@@ -54,6 +73,24 @@ pub trait Backend: Default {
     /// original string in its [`resolve`](`Backend::resolve`) method.
     fn intern(&mut self, string: &str) -> Self::Symbol;
 
+    /// Tries to intern the given string and returns its symbol.
+    ///
+    /// # Note
+    ///
+    /// The backend must be left unchanged if an error is returned. Backends return
+    /// [`TryReserveError::CapacityOverflow`] if their symbols are exhausted.
+    ///
+    /// The default implementation forwards to [`intern`](`Backend::intern`) and
+    /// thus panics on symbol exhaustion and aborts on allocation failure.
+    /// Backends should implement this method to support
+    /// [`StringInterner::try_get_or_intern`].
+    ///
+    /// [`StringInterner::try_get_or_intern`]: crate::StringInterner::try_get_or_intern
+    #[inline]
+    fn try_intern(&mut self, string: &str) -> Result<Self::Symbol, TryReserveError> {
+        Ok(self.intern(string))
+    }
+
     /// Interns the given static string and returns its interned ref and symbol.
     ///
     /// # Note
@@ -68,9 +105,97 @@ pub trait Backend: Default {
         self.intern(string)
     }
 
+    /// Tries to reserve capacity for at least `additional` more strings.
+    ///
+    /// # Note
+    ///
+    /// The default implementation reserves nothing.
+    #[inline]
+    fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        let _ = additional;
+        Ok(())
+    }
+
+    /// Removes all strings but the first `len` interned strings.
+    ///
+    /// Returns `false` if the backend does not support removing strings in
+    /// which case it must be left unchanged.
+    ///
+    /// # Note
+    ///
+    /// The default implementation returns `false`. Backends should implement this
+    /// method so that [`StringInterner::apply_delta`] rolls back deltas that fail
+    /// to apply.
+    ///
+    /// [`StringInterner::apply_delta`]: crate::StringInterner::apply_delta
+    #[inline]
+    fn truncate(&mut self, len: usize) -> bool {
+        let _ = len;
+        false
+    }
+
     /// Shrink backend capacity to fit interned symbols exactly.
     fn shrink_to_fit(&mut self);
 
+    /// Returns the heap memory used by the backend or `None` if it is unknown.
+    ///
+    /// # Note
+    ///
+    /// The default implementation returns `None`. Backends should implement
+    /// this method to support [`StringInterner::memory_stats`].
+    ///
+    /// [`StringInterner::memory_stats`]: crate::StringInterner::memory_stats
+    #[inline]
+    fn memory_usage(&self) -> Option<MemoryUsage> {
+        None
+    }
+
+    /// Serializes the storage of the backend in a representation of its own.
+    ///
+    /// Returns the serializer back if the backend has no such representation
+    /// for it in which case the string interner serializes its strings as a
+    /// sequence. Used by [`serde::with_storage`].
+    ///
+    /// # Note
+    ///
+    /// The default implementation returns the serializer back. Backends that
+    /// override it must also override [`deserialize_storage`] accordingly.
+    ///
+    /// [`serde::with_storage`]: crate::serde::with_storage
+    /// [`deserialize_storage`]: Backend::deserialize_storage
+    #[cfg(feature = "serde-1")]
+    #[inline]
+    fn serialize_storage<T>(&self, serializer: T) -> Result<Result<T::Ok, T::Error>, T>
+    where
+        T: serde::Serializer,
+    {
+        Err(serializer)
+    }
+
+    /// Deserializes the storage written by [`serialize_storage`] into the empty backend.
+    ///
+    /// Returns the deserializer back if the backend has no representation of its
+    /// own for it in which case the string interner interns a sequence of strings.
+    /// Used by [`serde::with_storage`].
+    ///
+    /// # Note
+    ///
+    /// The default implementation returns the deserializer back.
+    ///
+    /// [`serde::with_storage`]: crate::serde::with_storage
+    /// [`serialize_storage`]: Backend::serialize_storage
+    #[cfg(feature = "serde-1")]
+    #[inline]
+    fn deserialize_storage<'de, D>(
+        &mut self,
+        deserializer: D,
+    ) -> Result<Result<(), D::Error>, D>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        Err(deserializer)
+    }
+
     /// Resolves the given symbol to its original string contents.
     fn resolve(&self, symbol: Self::Symbol) -> Option<&str>;
 
@@ -85,3 +210,31 @@ pub trait Backend: Default {
     /// interner backend.
     unsafe fn resolve_unchecked(&self, symbol: Self::Symbol) -> &str;
 }
+
+/// Backends whose symbols are contiguous indices starting at zero.
+///
+/// The `n`-th string interned by such a backend has a symbol whose
+/// [`Symbol::to_usize`] is `n`, which allows to associate values with the
+/// interned strings in a vector indexed by their symbols such as the
+/// [`InternMap`](crate::InternMap) does.
+pub trait ContiguousBackend: Backend {}
+
+/// Backends that allocate their storage from an allocator of type `A`.
+///
+/// Used by [`StringInterner::new_in`] and friends to create the backend
+/// from the same allocator as the deduplication table of the string interner.
+///
+/// Unlike [`Backend::with_capacity`] this does not require the allocator to
+/// implement [`Default`], e.g. to allocate from a `&Bump` arena.
+///
+/// [`StringInterner::new_in`]: crate::StringInterner::new_in
+#[cfg(feature = "allocator-api")]
+pub trait BackendIn<A>: Backend
+where
+    A: Allocator,
+{
+    /// Creates a new backend for the given capacity that allocates from `alloc`.
+    ///
+    /// The capacity denotes how many strings are expected to be interned.
+    fn with_capacity_in(cap: usize, alloc: A) -> Self;
+}
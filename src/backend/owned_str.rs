@@ -0,0 +1,97 @@
+#![cfg(feature = "backends")]
+
+use crate::{
+    compat::{
+        Allocator,
+        BoxIn,
+        Global,
+        VecIn,
+    },
+    TryReserveError,
+};
+use core::{
+    fmt,
+    ops::Deref,
+};
+
+/// An owned string that is allocated from an allocator of type `A`.
+pub struct OwnedStr<A = Global>
+where
+    A: Allocator,
+{
+    /// The contents of the string which are always valid UTF-8.
+    bytes: BoxIn<[u8], A>,
+}
+
+impl<A> OwnedStr<A>
+where
+    A: Allocator,
+{
+    /// Copies the string into a new allocation of `alloc`.
+    #[inline]
+    pub fn new_in(string: &str, alloc: A) -> Self {
+        let mut bytes = VecIn::with_capacity_in(string.len(), alloc);
+        bytes.extend_from_slice(string.as_bytes());
+        Self {
+            bytes: bytes.into_boxed_slice(),
+        }
+    }
+
+    /// Tries to copy the string into a new allocation of `alloc`.
+    #[inline]
+    pub fn try_new_in(string: &str, alloc: A) -> Result<Self, TryReserveError> {
+        let mut bytes = VecIn::new_in(alloc);
+        bytes.try_reserve_exact(string.len())?;
+        bytes.extend_from_slice(string.as_bytes());
+        // Does not reallocate since the capacity matches the length exactly.
+        Ok(Self {
+            bytes: bytes.into_boxed_slice(),
+        })
+    }
+}
+
+impl<A> Deref for OwnedStr<A>
+where
+    A: Allocator,
+{
+    type Target = str;
+
+    #[inline]
+    fn deref(&self) -> &str {
+        // SAFETY: The bytes are only ever copied from a valid `str`.
+        unsafe { core::str::from_utf8_unchecked(&self.bytes) }
+    }
+}
+
+impl<A> fmt::Debug for OwnedStr<A>
+where
+    A: Allocator,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<A> Clone for OwnedStr<A>
+where
+    A: Allocator + Clone,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            bytes: self.bytes.clone(),
+        }
+    }
+}
+
+impl<A> PartialEq for OwnedStr<A>
+where
+    A: Allocator,
+{
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+impl<A> Eq for OwnedStr<A> where A: Allocator {}
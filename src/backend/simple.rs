@@ -1,15 +1,26 @@
 #![cfg(feature = "backends")]
 
-use super::Backend;
+#[cfg(feature = "allocator-api")]
+use super::BackendIn;
+use super::{
+    owned_str::OwnedStr,
+    Backend,
+    ContiguousBackend,
+};
 use crate::{
     compat::{
-        Box,
-        ToString,
-        Vec,
+        Allocator,
+        Global,
+        VecIn,
+    },
+    symbol::{
+        expect_valid_symbol,
+        try_valid_symbol,
     },
-    symbol::expect_valid_symbol,
     DefaultSymbol,
+    MemoryUsage,
     Symbol,
+    TryReserveError,
 };
 use core::{
     iter::Enumerate,
@@ -47,47 +58,103 @@ use core::{
 /// | `Send` + `Sync` | **yes** |
 /// | Contiguous  | **yes**  |
 #[derive(Debug)]
-pub struct SimpleBackend<S = DefaultSymbol> {
-    strings: Vec<Box<str>>,
+pub struct SimpleBackend<S = DefaultSymbol, A = Global>
+where
+    A: Allocator,
+{
+    strings: VecIn<OwnedStr<A>, A>,
     symbol_marker: PhantomData<fn() -> S>,
 }
 
-impl<S> Default for SimpleBackend<S> {
+impl<S, A> Default for SimpleBackend<S, A>
+where
+    A: Allocator + Default,
+{
     #[cfg_attr(feature = "inline-more", inline)]
     fn default() -> Self {
         Self {
-            strings: Vec::new(),
+            strings: VecIn::new_in(A::default()),
             symbol_marker: Default::default(),
         }
     }
 }
 
-impl<S> Backend for SimpleBackend<S>
+impl<S, A> SimpleBackend<S, A>
 where
-    S: Symbol,
+    A: Allocator,
 {
-    type Symbol = S;
-
+    /// Creates a new empty backend that allocates from `alloc`.
+    ///
+    /// Unlike [`Default::default`] this does not require the allocator to
+    /// implement [`Default`], e.g. to allocate from a `&Bump` arena.
     #[cfg_attr(feature = "inline-more", inline)]
-    fn with_capacity(cap: usize) -> Self {
+    pub fn new_in(alloc: A) -> Self {
         Self {
-            strings: Vec::with_capacity(cap),
+            strings: VecIn::new_in(alloc),
             symbol_marker: Default::default(),
         }
     }
+}
+
+impl<S, A> Backend for SimpleBackend<S, A>
+where
+    S: Symbol,
+    A: Allocator + Clone,
+{
+    type Symbol = S;
+
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn with_capacity(cap: usize) -> Self
+    where
+        Self: Default,
+    {
+        let mut backend = Self::default();
+        backend.strings.reserve_exact(cap);
+        backend
+    }
 
     #[inline]
     fn intern(&mut self, string: &str) -> Self::Symbol {
         let symbol = expect_valid_symbol(self.strings.len());
-        let str = string.to_string().into_boxed_str();
+        let str = OwnedStr::new_in(string, self.strings.allocator().clone());
         self.strings.push(str);
         symbol
     }
 
+    #[inline]
+    fn try_intern(&mut self, string: &str) -> Result<Self::Symbol, TryReserveError> {
+        let symbol = try_valid_symbol(self.strings.len())?;
+        self.strings.try_reserve(1)?;
+        let str = OwnedStr::try_new_in(string, self.strings.allocator().clone())?;
+        self.strings.push(str);
+        Ok(symbol)
+    }
+
+    fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.strings.try_reserve(additional)?;
+        Ok(())
+    }
+
+    #[inline]
+    fn truncate(&mut self, len: usize) -> bool {
+        self.strings.truncate(len);
+        true
+    }
+
     fn shrink_to_fit(&mut self) {
         self.strings.shrink_to_fit()
     }
 
+    fn memory_usage(&self) -> Option<MemoryUsage> {
+        let strings = self.strings.iter().map(|string| string.len()).sum();
+        let usage = MemoryUsage::of_vec_in(&self.strings)
+            + MemoryUsage {
+                heap_bytes: strings,
+                used_bytes: strings,
+            };
+        Some(usage)
+    }
+
     #[inline]
     fn resolve(&self, symbol: Self::Symbol) -> Option<&str> {
         self.strings.get(symbol.to_usize()).map(|pinned| &**pinned)
@@ -101,7 +168,32 @@ where
     }
 }
 
-impl<S> Clone for SimpleBackend<S> {
+impl<S, A> ContiguousBackend for SimpleBackend<S, A>
+where
+    S: Symbol,
+    A: Allocator + Clone,
+{
+}
+
+#[cfg(feature = "allocator-api")]
+impl<S, A> BackendIn<A> for SimpleBackend<S, A>
+where
+    S: Symbol,
+    A: Allocator + Clone,
+{
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn with_capacity_in(cap: usize, alloc: A) -> Self {
+        Self {
+            strings: VecIn::with_capacity_in(cap, alloc),
+            symbol_marker: Default::default(),
+        }
+    }
+}
+
+impl<S, A> Clone for SimpleBackend<S, A>
+where
+    A: Allocator + Clone,
+{
     #[cfg_attr(feature = "inline-more", inline)]
     fn clone(&self) -> Self {
         Self {
@@ -111,11 +203,17 @@ impl<S> Clone for SimpleBackend<S> {
     }
 }
 
-impl<S> Eq for SimpleBackend<S> where S: Symbol {}
+impl<S, A> Eq for SimpleBackend<S, A>
+where
+    S: Symbol,
+    A: Allocator,
+{
+}
 
-impl<S> PartialEq for SimpleBackend<S>
+impl<S, A> PartialEq for SimpleBackend<S, A>
 where
     S: Symbol,
+    A: Allocator,
 {
     #[cfg_attr(feature = "inline-more", inline)]
     fn eq(&self, other: &Self) -> bool {
@@ -123,12 +221,13 @@ where
     }
 }
 
-impl<'a, S> IntoIterator for &'a SimpleBackend<S>
+impl<'a, S, A> IntoIterator for &'a SimpleBackend<S, A>
 where
     S: Symbol,
+    A: Allocator,
 {
     type Item = (S, &'a str);
-    type IntoIter = Iter<'a, S>;
+    type IntoIter = Iter<'a, S, A>;
 
     #[cfg_attr(feature = "inline-more", inline)]
     fn into_iter(self) -> Self::IntoIter {
@@ -136,14 +235,20 @@ where
     }
 }
 
-pub struct Iter<'a, S> {
-    iter: Enumerate<slice::Iter<'a, Box<str>>>,
+pub struct Iter<'a, S, A = Global>
+where
+    A: Allocator,
+{
+    iter: Enumerate<slice::Iter<'a, OwnedStr<A>>>,
     symbol_marker: PhantomData<fn() -> S>,
 }
 
-impl<'a, S> Iter<'a, S> {
+impl<'a, S, A> Iter<'a, S, A>
+where
+    A: Allocator,
+{
     #[cfg_attr(feature = "inline-more", inline)]
-    pub fn new(backend: &'a SimpleBackend<S>) -> Self {
+    pub fn new(backend: &'a SimpleBackend<S, A>) -> Self {
         Self {
             iter: backend.strings.iter().enumerate(),
             symbol_marker: Default::default(),
@@ -151,9 +256,10 @@ impl<'a, S> Iter<'a, S> {
     }
 }
 
-impl<'a, S> Iterator for Iter<'a, S>
+impl<'a, S, A> Iterator for Iter<'a, S, A>
 where
     S: Symbol,
+    A: Allocator,
 {
     type Item = (S, &'a str);
 
@@ -169,3 +275,33 @@ where
             .map(|(id, pinned)| (expect_valid_symbol(id), &**pinned))
     }
 }
+
+/// Serializes the strings of the backend as a sequence.
+#[cfg(feature = "serde-1")]
+impl<S, A> serde::Serialize for SimpleBackend<S, A>
+where
+    S: Symbol,
+    A: Allocator + Clone,
+{
+    fn serialize<T>(&self, serializer: T) -> Result<T::Ok, T::Error>
+    where
+        T: serde::Serializer,
+    {
+        crate::serde_impl::serialize_strings::<Self, T>(self, serializer)
+    }
+}
+
+/// Deserializes the backend by interning a sequence of strings.
+#[cfg(feature = "serde-1")]
+impl<'de, S, A> serde::Deserialize<'de> for SimpleBackend<S, A>
+where
+    S: Symbol,
+    A: Allocator + Clone + Default,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        crate::serde_impl::deserialize_strings::<Self, D>(deserializer)
+    }
+}
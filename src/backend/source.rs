@@ -0,0 +1,474 @@
+#![cfg(feature = "backends")]
+
+#[cfg(feature = "allocator-api")]
+use super::BackendIn;
+use super::{
+    Backend,
+    ContiguousBackend,
+};
+use crate::{
+    compat::{
+        Allocator,
+        Arc,
+        Global,
+        VecIn,
+    },
+    symbol::{
+        expect_valid_symbol,
+        try_valid_symbol,
+    },
+    DefaultSymbol,
+    MemoryUsage,
+    Symbol,
+    TryReserveError,
+};
+use core::{
+    iter::Enumerate,
+    marker::PhantomData,
+    slice,
+};
+
+/// A backend that references strings within registered source buffers instead of copying them.
+///
+/// Source buffers are registered as `Arc<str>` via [`SourceBackend::register_source`]
+/// or [`StringInterner::register_source`]. Interned strings that are slices of a
+/// registered source buffer are stored as spans into it while all other strings
+/// are copied into an owned buffer. Source buffers are released when the backend
+/// is dropped.
+///
+/// Source buffers are not serialized so that deserialized backends and string
+/// interners hold owned copies of all their strings.
+///
+/// [`StringInterner::register_source`]: crate::StringInterner::register_source
+///
+/// # Example
+///
+/// ```
+/// use std::sync::Arc;
+/// use string_interner::{backend::SourceBackend, StringInterner};
+///
+/// let source: Arc<str> = Arc::from("let tiger = horse;");
+/// let mut interner = StringInterner::<SourceBackend>::new();
+/// interner.register_source(source.clone());
+/// let tiger = interner.get_or_intern(&source[4..9]);
+/// assert!(core::ptr::eq(interner.resolve(tiger).unwrap(), &source[4..9]));
+/// ```
+///
+/// # Usage Hint
+///
+/// Use this backend if the interned strings mostly stem from reference-counted source buffers.
+///
+/// # Usage
+///
+/// - **Fill:** Efficiency of filling an empty string interner.
+/// - **Resolve:** Efficiency of interned string look-up given a symbol.
+/// - **Allocations:** The number of allocations performed by the backend.
+/// - **Footprint:** The total heap memory consumed by the backend.
+/// - **Contiguous:** True if the returned symbols have contiguous values.
+///
+/// Rating varies between **bad**, **ok**, **good** and **best**.
+///
+/// | Scenario    |  Rating  |
+/// |:------------|:--------:|
+/// | Fill        | **good** |
+/// | Resolve     | **good** |
+/// | Allocations | **best** |
+/// | Footprint   | **ok**   |
+/// | Supports `get_or_intern_static` | **no** |
+/// | `Send` + `Sync` | **yes** |
+/// | Contiguous  | **yes**  |
+#[derive(Debug)]
+pub struct SourceBackend<S = DefaultSymbol, A = Global>
+where
+    A: Allocator,
+{
+    /// The registered source buffers.
+    sources: VecIn<Arc<str>, A>,
+    /// Indices into `sources` ordered by the address of their buffers.
+    by_address: VecIn<usize, A>,
+    spans: VecIn<Span, A>,
+    /// Holds the copies of strings that are not part of a source buffer.
+    ///
+    /// The contents are always valid UTF-8.
+    buffer: VecIn<u8, A>,
+    marker: PhantomData<fn() -> S>,
+}
+
+/// Represents a `[from, to)` index into a source buffer or the owned buffer.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct Span {
+    /// The index of the source buffer or [`OWNED`] for the owned buffer.
+    source: usize,
+    from: usize,
+    to: usize,
+}
+
+/// The source index of spans into the owned buffer.
+const OWNED: usize = usize::MAX;
+
+impl<S, A> PartialEq for SourceBackend<S, A>
+where
+    S: Symbol,
+    A: Allocator,
+{
+    fn eq(&self, other: &Self) -> bool {
+        if self.spans.len() != other.spans.len() {
+            return false
+        }
+        for ((_, lhs), (_, rhs)) in self.into_iter().zip(other) {
+            if lhs != rhs {
+                return false
+            }
+        }
+        true
+    }
+}
+
+impl<S, A> Eq for SourceBackend<S, A>
+where
+    S: Symbol,
+    A: Allocator,
+{
+}
+
+impl<S, A> Clone for SourceBackend<S, A>
+where
+    A: Allocator + Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            sources: self.sources.clone(),
+            by_address: self.by_address.clone(),
+            spans: self.spans.clone(),
+            buffer: self.buffer.clone(),
+            marker: Default::default(),
+        }
+    }
+}
+
+impl<S, A> Default for SourceBackend<S, A>
+where
+    A: Allocator + Default,
+{
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn default() -> Self {
+        Self {
+            sources: VecIn::new_in(A::default()),
+            by_address: VecIn::new_in(A::default()),
+            spans: VecIn::new_in(A::default()),
+            buffer: VecIn::new_in(A::default()),
+            marker: Default::default(),
+        }
+    }
+}
+
+impl<S, A> SourceBackend<S, A>
+where
+    A: Allocator + Clone,
+{
+    /// Creates a new empty backend that allocates from `alloc`.
+    ///
+    /// Unlike [`Default::default`] this does not require the allocator to
+    /// implement [`Default`], e.g. to allocate from a `&Bump` arena.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn new_in(alloc: A) -> Self {
+        Self {
+            sources: VecIn::new_in(alloc.clone()),
+            by_address: VecIn::new_in(alloc.clone()),
+            spans: VecIn::new_in(alloc.clone()),
+            buffer: VecIn::new_in(alloc),
+            marker: Default::default(),
+        }
+    }
+}
+
+impl<S, A> SourceBackend<S, A>
+where
+    A: Allocator,
+{
+    /// Registers the source buffer so that interned slices of it are not copied.
+    ///
+    /// Registering the same source buffer multiple times has no effect.
+    pub fn register_source(&mut self, source: Arc<str>) {
+        if source.is_empty() {
+            // Empty buffers might share their dangling address.
+            return
+        }
+        let address = source.as_ptr() as usize;
+        let position = self
+            .by_address
+            .partition_point(|&index| (self.sources[index].as_ptr() as usize) < address);
+        if let Some(&index) = self.by_address.get(position) {
+            if self.sources[index].as_ptr() as usize == address {
+                return
+            }
+        }
+        self.by_address.insert(position, self.sources.len());
+        self.sources.push(source);
+    }
+
+    /// Returns the registered source buffers.
+    pub fn sources(&self) -> &[Arc<str>] {
+        &self.sources
+    }
+
+    /// Returns the span of `string` into a registered source buffer if
+    /// `string` is a slice of it.
+    fn find_source(&self, string: &str) -> Option<Span> {
+        let start = string.as_ptr() as usize;
+        let position = self
+            .by_address
+            .partition_point(|&index| (self.sources[index].as_ptr() as usize) <= start);
+        let index = *self.by_address.get(position.checked_sub(1)?)?;
+        let source = &self.sources[index];
+        let from = start - source.as_ptr() as usize;
+        let to = from + string.len();
+        (to <= source.len()).then_some(Span {
+            source: index,
+            from,
+            to,
+        })
+    }
+
+    /// Copies `string` into the owned buffer and returns its span.
+    fn push_owned(&mut self, string: &str) -> Span {
+        let from = self.buffer.len();
+        self.buffer.extend_from_slice(string.as_bytes());
+        Span {
+            source: OWNED,
+            from,
+            to: self.buffer.len(),
+        }
+    }
+
+    /// Returns the string associated to the span.
+    fn span_to_str(&self, span: Span) -> &str {
+        let buffer = match span.source {
+            OWNED => &self.buffer[..],
+            index => self.sources[index].as_bytes(),
+        };
+        // SAFETY: - The spans are always constructed from slices of the buffers
+        //           and thus are valid UTF-8 byte ranges of them.
+        //         - Nothing mutates the buffers in between since this is a `&self`
+        //           method and source buffers are immutable.
+        unsafe { core::str::from_utf8_unchecked(&buffer[span.from..span.to]) }
+    }
+}
+
+impl<S, A> SourceBackend<S, A>
+where
+    S: Symbol,
+    A: Allocator,
+{
+    /// Returns the next available symbol.
+    fn next_symbol(&self) -> S {
+        expect_valid_symbol(self.spans.len())
+    }
+}
+
+impl<S, A> Backend for SourceBackend<S, A>
+where
+    S: Symbol,
+    A: Allocator,
+{
+    type Symbol = S;
+
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn with_capacity(cap: usize) -> Self
+    where
+        Self: Default,
+    {
+        let mut backend = Self::default();
+        backend.spans.reserve_exact(cap);
+        backend
+    }
+
+    #[inline]
+    fn intern(&mut self, string: &str) -> Self::Symbol {
+        let symbol = self.next_symbol();
+        let span = match self.find_source(string) {
+            Some(span) => span,
+            None => self.push_owned(string),
+        };
+        self.spans.push(span);
+        symbol
+    }
+
+    #[inline]
+    fn try_intern(&mut self, string: &str) -> Result<Self::Symbol, TryReserveError> {
+        let symbol = try_valid_symbol(self.spans.len())?;
+        self.spans.try_reserve(1)?;
+        let span = match self.find_source(string) {
+            Some(span) => span,
+            None => {
+                self.buffer.try_reserve(string.len())?;
+                self.push_owned(string)
+            }
+        };
+        self.spans.push(span);
+        Ok(symbol)
+    }
+
+    fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.spans.try_reserve(additional)?;
+        Ok(())
+    }
+
+    fn truncate(&mut self, len: usize) -> bool {
+        self.spans.truncate(len);
+        let end = self
+            .spans
+            .iter()
+            .rev()
+            .find(|span| span.source == OWNED)
+            .map_or(0, |span| span.to);
+        self.buffer.truncate(end);
+        true
+    }
+
+    #[inline]
+    fn resolve(&self, symbol: Self::Symbol) -> Option<&str> {
+        self.spans
+            .get(symbol.to_usize())
+            .map(|&span| self.span_to_str(span))
+    }
+
+    fn shrink_to_fit(&mut self) {
+        self.sources.shrink_to_fit();
+        self.by_address.shrink_to_fit();
+        self.spans.shrink_to_fit();
+        self.buffer.shrink_to_fit();
+    }
+
+    /// Does not include the registered source buffers since they are shared.
+    fn memory_usage(&self) -> Option<MemoryUsage> {
+        let usage = MemoryUsage::of_vec_in(&self.sources)
+            + MemoryUsage::of_vec_in(&self.by_address)
+            + MemoryUsage::of_vec_in(&self.spans)
+            + MemoryUsage::of_vec_in(&self.buffer);
+        Some(usage)
+    }
+
+    #[inline]
+    unsafe fn resolve_unchecked(&self, symbol: Self::Symbol) -> &str {
+        // SAFETY: The function is marked unsafe so that the caller guarantees
+        //         that required invariants are checked.
+        unsafe { self.span_to_str(*self.spans.get_unchecked(symbol.to_usize())) }
+    }
+}
+
+impl<S, A> ContiguousBackend for SourceBackend<S, A>
+where
+    S: Symbol,
+    A: Allocator,
+{
+}
+
+#[cfg(feature = "allocator-api")]
+impl<S, A> BackendIn<A> for SourceBackend<S, A>
+where
+    S: Symbol,
+    A: Allocator + Clone,
+{
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn with_capacity_in(cap: usize, alloc: A) -> Self {
+        Self {
+            sources: VecIn::new_in(alloc.clone()),
+            by_address: VecIn::new_in(alloc.clone()),
+            spans: VecIn::with_capacity_in(cap, alloc.clone()),
+            buffer: VecIn::new_in(alloc),
+            marker: Default::default(),
+        }
+    }
+}
+
+impl<'a, S, A> IntoIterator for &'a SourceBackend<S, A>
+where
+    S: Symbol,
+    A: Allocator,
+{
+    type Item = (S, &'a str);
+    type IntoIter = Iter<'a, S, A>;
+
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn into_iter(self) -> Self::IntoIter {
+        Self::IntoIter::new(self)
+    }
+}
+
+pub struct Iter<'a, S, A = Global>
+where
+    A: Allocator,
+{
+    backend: &'a SourceBackend<S, A>,
+    spans: Enumerate<slice::Iter<'a, Span>>,
+}
+
+impl<'a, S, A> Iter<'a, S, A>
+where
+    A: Allocator,
+{
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn new(backend: &'a SourceBackend<S, A>) -> Self {
+        Self {
+            backend,
+            spans: backend.spans.iter().enumerate(),
+        }
+    }
+}
+
+impl<'a, S, A> Iterator for Iter<'a, S, A>
+where
+    S: Symbol,
+    A: Allocator,
+{
+    type Item = (S, &'a str);
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.spans.size_hint()
+    }
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.spans
+            .next()
+            .map(|(id, &span)| (expect_valid_symbol(id), self.backend.span_to_str(span)))
+    }
+}
+
+/// Serializes the strings of the backend as a sequence.
+#[cfg(feature = "serde-1")]
+impl<S, A> serde::Serialize for SourceBackend<S, A>
+where
+    S: Symbol,
+    A: Allocator,
+{
+    fn serialize<T>(&self, serializer: T) -> Result<T::Ok, T::Error>
+    where
+        T: serde::Serializer,
+    {
+        crate::serde_impl::serialize_strings::<Self, T>(self, serializer)
+    }
+}
+
+/// Deserializes the backend by copying a sequence of strings into its owned buffer.
+///
+/// # Note
+///
+/// Sources are not serialized. The strings of a deserialized backend are owned
+/// copies even if they were slices of registered source buffers before, so that
+/// a round trip loses the sources and the memory savings of sharing them.
+#[cfg(feature = "serde-1")]
+impl<'de, S, A> serde::Deserialize<'de> for SourceBackend<S, A>
+where
+    S: Symbol,
+    A: Allocator + Default,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        crate::serde_impl::deserialize_strings::<Self, D>(deserializer)
+    }
+}
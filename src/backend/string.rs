@@ -1,14 +1,25 @@
 #![cfg(feature = "backends")]
 
-use super::Backend;
+#[cfg(feature = "allocator-api")]
+use super::BackendIn;
+use super::{
+    Backend,
+    ContiguousBackend,
+};
 use crate::{
     compat::{
-        String,
-        Vec,
+        Allocator,
+        Global,
+        VecIn,
+    },
+    symbol::{
+        expect_valid_symbol,
+        try_valid_symbol,
     },
-    symbol::expect_valid_symbol,
     DefaultSymbol,
+    MemoryUsage,
     Symbol,
+    TryReserveError,
 };
 use core::{
     iter::Enumerate,
@@ -47,9 +58,13 @@ use core::{
 /// | `Send` + `Sync` | **yes** |
 /// | Contiguous  | **yes**  |
 #[derive(Debug)]
-pub struct StringBackend<S = DefaultSymbol> {
-    ends: Vec<usize>,
-    buffer: String,
+pub struct StringBackend<S = DefaultSymbol, A = Global>
+where
+    A: Allocator,
+{
+    ends: VecIn<usize, A>,
+    /// Holds the contents of all interned strings which are valid UTF-8.
+    buffer: VecIn<u8, A>,
     marker: PhantomData<fn() -> S>,
 }
 
@@ -60,9 +75,10 @@ pub struct Span {
     to: usize,
 }
 
-impl<S> PartialEq for StringBackend<S>
+impl<S, A> PartialEq for StringBackend<S, A>
 where
     S: Symbol,
+    A: Allocator,
 {
     fn eq(&self, other: &Self) -> bool {
         if self.ends.len() != other.ends.len() {
@@ -77,9 +93,17 @@ where
     }
 }
 
-impl<S> Eq for StringBackend<S> where S: Symbol {}
+impl<S, A> Eq for StringBackend<S, A>
+where
+    S: Symbol,
+    A: Allocator,
+{
+}
 
-impl<S> Clone for StringBackend<S> {
+impl<S, A> Clone for StringBackend<S, A>
+where
+    A: Allocator + Clone,
+{
     fn clone(&self) -> Self {
         Self {
             ends: self.ends.clone(),
@@ -89,20 +113,45 @@ impl<S> Clone for StringBackend<S> {
     }
 }
 
-impl<S> Default for StringBackend<S> {
+impl<S, A> Default for StringBackend<S, A>
+where
+    A: Allocator + Default,
+{
     #[cfg_attr(feature = "inline-more", inline)]
     fn default() -> Self {
         Self {
-            ends: Vec::default(),
-            buffer: String::default(),
+            ends: VecIn::new_in(A::default()),
+            buffer: VecIn::new_in(A::default()),
             marker: Default::default(),
         }
     }
 }
 
-impl<S> StringBackend<S>
+impl<S, A> StringBackend<S, A>
+where
+    A: Allocator + Clone,
+{
+    /// Creates a new empty backend that allocates from `alloc`.
+    ///
+    /// Unlike [`Default::default`] this does not require the allocator to
+    /// implement [`Default`], e.g. to allocate from a `&Bump` arena.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn new_in(alloc: A) -> Self {
+        Self {
+            ends: VecIn::new_in(alloc.clone()),
+            buffer: VecIn::new_in(alloc),
+            marker: Default::default(),
+        }
+    }
+}
+
+/// According to google the approx. word length is 5.
+const DEFAULT_WORD_LEN: usize = 5;
+
+impl<S, A> StringBackend<S, A>
 where
     S: Symbol,
+    A: Allocator,
 {
     /// Returns the next available symbol.
     fn next_symbol(&self) -> S {
@@ -111,15 +160,15 @@ where
 
     /// Returns the string associated to the span.
     fn span_to_str(&self, span: Span) -> &str {
-        // SAFETY: - We convert a `String` into its underlying bytes and then
-        //           directly reinterpret it as `&str` again which is safe.
+        // SAFETY: - The buffer only ever has valid UTF-8 strings appended
+        //           to it so reinterpreting its bytes as `&str` is safe.
         //         - Nothing mutates the string in between since this is a `&self`
         //           method.
         //         - The spans we use for `(start..end]` ranges are always
         //           constructed in accordance to valid utf8 byte ranges.
         unsafe {
             core::str::from_utf8_unchecked(
-                &self.buffer.as_bytes()[(span.from as usize)..(span.to as usize)],
+                &self.buffer[span.from..span.to],
             )
         }
     }
@@ -149,29 +198,30 @@ where
     ///
     /// If the backend ran out of symbols.
     fn push_string(&mut self, string: &str) -> S {
-        self.buffer.push_str(string);
-        let to = self.buffer.as_bytes().len();
+        self.buffer.extend_from_slice(string.as_bytes());
+        let to = self.buffer.len();
         let symbol = self.next_symbol();
         self.ends.push(to);
         symbol
     }
 }
 
-impl<S> Backend for StringBackend<S>
+impl<S, A> Backend for StringBackend<S, A>
 where
     S: Symbol,
+    A: Allocator,
 {
     type Symbol = S;
 
     #[cfg_attr(feature = "inline-more", inline)]
-    fn with_capacity(cap: usize) -> Self {
-        // According to google the approx. word length is 5.
-        let default_word_len = 5;
-        Self {
-            ends: Vec::with_capacity(cap),
-            buffer: String::with_capacity(cap * default_word_len),
-            marker: Default::default(),
-        }
+    fn with_capacity(cap: usize) -> Self
+    where
+        Self: Default,
+    {
+        let mut backend = Self::default();
+        backend.ends.reserve_exact(cap);
+        backend.buffer.reserve_exact(cap * DEFAULT_WORD_LEN);
+        backend
     }
 
     #[inline]
@@ -179,6 +229,31 @@ where
         self.push_string(string)
     }
 
+    #[inline]
+    fn try_intern(&mut self, string: &str) -> Result<Self::Symbol, TryReserveError> {
+        try_valid_symbol::<S>(self.ends.len())?;
+        self.ends.try_reserve(1)?;
+        self.buffer.try_reserve(string.len())?;
+        Ok(self.push_string(string))
+    }
+
+    fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        let bytes = additional
+            .checked_mul(DEFAULT_WORD_LEN)
+            .ok_or(TryReserveError::CapacityOverflow)?;
+        self.ends.try_reserve(additional)?;
+        self.buffer.try_reserve(bytes)?;
+        Ok(())
+    }
+
+    fn truncate(&mut self, len: usize) -> bool {
+        if len < self.ends.len() {
+            self.ends.truncate(len);
+            self.buffer.truncate(self.ends.last().copied().unwrap_or(0));
+        }
+        true
+    }
+
     #[inline]
     fn resolve(&self, symbol: Self::Symbol) -> Option<&str> {
         self.symbol_to_span(symbol)
@@ -190,6 +265,35 @@ where
         self.buffer.shrink_to_fit();
     }
 
+    fn memory_usage(&self) -> Option<MemoryUsage> {
+        Some(MemoryUsage::of_vec_in(&self.ends) + MemoryUsage::of_vec_in(&self.buffer))
+    }
+
+    #[cfg(feature = "serde-1")]
+    fn serialize_storage<T>(&self, serializer: T) -> Result<Result<T::Ok, T::Error>, T>
+    where
+        T: serde::Serializer,
+    {
+        if serializer.is_human_readable() {
+            return Err(serializer)
+        }
+        Ok(self.serialize_raw(serializer))
+    }
+
+    #[cfg(feature = "serde-1")]
+    fn deserialize_storage<'de, D>(
+        &mut self,
+        deserializer: D,
+    ) -> Result<Result<(), D::Error>, D>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        if deserializer.is_human_readable() {
+            return Err(deserializer)
+        }
+        Ok(self.deserialize_raw(deserializer))
+    }
+
     #[inline]
     unsafe fn resolve_unchecked(&self, symbol: Self::Symbol) -> &str {
         // SAFETY: The function is marked unsafe so that the caller guarantees
@@ -198,12 +302,36 @@ where
     }
 }
 
-impl<'a, S> IntoIterator for &'a StringBackend<S>
+impl<S, A> ContiguousBackend for StringBackend<S, A>
 where
     S: Symbol,
+    A: Allocator,
+{
+}
+
+#[cfg(feature = "allocator-api")]
+impl<S, A> BackendIn<A> for StringBackend<S, A>
+where
+    S: Symbol,
+    A: Allocator + Clone,
+{
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn with_capacity_in(cap: usize, alloc: A) -> Self {
+        Self {
+            ends: VecIn::with_capacity_in(cap, alloc.clone()),
+            buffer: VecIn::with_capacity_in(cap * DEFAULT_WORD_LEN, alloc),
+            marker: Default::default(),
+        }
+    }
+}
+
+impl<'a, S, A> IntoIterator for &'a StringBackend<S, A>
+where
+    S: Symbol,
+    A: Allocator,
 {
     type Item = (S, &'a str);
-    type IntoIter = Iter<'a, S>;
+    type IntoIter = Iter<'a, S, A>;
 
     #[cfg_attr(feature = "inline-more", inline)]
     fn into_iter(self) -> Self::IntoIter {
@@ -211,15 +339,21 @@ where
     }
 }
 
-pub struct Iter<'a, S> {
-    backend: &'a StringBackend<S>,
+pub struct Iter<'a, S, A = Global>
+where
+    A: Allocator,
+{
+    backend: &'a StringBackend<S, A>,
     start: usize,
     ends: Enumerate<slice::Iter<'a, usize>>,
 }
 
-impl<'a, S> Iter<'a, S> {
+impl<'a, S, A> Iter<'a, S, A>
+where
+    A: Allocator,
+{
     #[cfg_attr(feature = "inline-more", inline)]
-    pub fn new(backend: &'a StringBackend<S>) -> Self {
+    pub fn new(backend: &'a StringBackend<S, A>) -> Self {
         Self {
             backend,
             start: 0,
@@ -228,9 +362,10 @@ impl<'a, S> Iter<'a, S> {
     }
 }
 
-impl<'a, S> Iterator for Iter<'a, S>
+impl<'a, S, A> Iterator for Iter<'a, S, A>
 where
     S: Symbol,
+    A: Allocator,
 {
     type Item = (S, &'a str);
 
@@ -250,3 +385,131 @@ where
         })
     }
 }
+
+#[cfg(feature = "serde-1")]
+impl<S, A> StringBackend<S, A>
+where
+    S: Symbol,
+    A: Allocator,
+{
+    /// Serializes the raw end offsets and buffer of the backend as byte blobs.
+    ///
+    /// End offsets are encoded as little-endian `u64` values.
+    fn serialize_raw<T>(&self, serializer: T) -> Result<T::Ok, T::Error>
+    where
+        T: serde::Serializer,
+    {
+        use crate::{
+            compat::Vec,
+            serde_impl::Bytes,
+        };
+        use serde::ser::SerializeTuple;
+        let ends = self
+            .ends
+            .iter()
+            .flat_map(|&end| (end as u64).to_le_bytes())
+            .collect::<Vec<u8>>();
+        let mut tuple = serializer.serialize_tuple(2)?;
+        tuple.serialize_element(&Bytes(&ends))?;
+        tuple.serialize_element(&Bytes(&self.buffer))?;
+        tuple.end()
+    }
+
+    /// Replaces the strings of the backend with the raw end offsets and buffer
+    /// written by [`StringBackend::serialize_raw`].
+    ///
+    /// Validates that the buffer is valid UTF-8 and that the end offsets are
+    /// ascending char boundaries of the buffer.
+    fn deserialize_raw<'de, D>(&mut self, deserializer: D) -> Result<(), D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use crate::{
+            compat::Vec,
+            serde_impl::ByteBuf,
+        };
+        use serde::{
+            de::Error as _,
+            Deserialize as _,
+        };
+        let (ByteBuf(ends), ByteBuf(buffer)) = <(ByteBuf, ByteBuf)>::deserialize(deserializer)?;
+        let buffer = core::str::from_utf8(&buffer)
+            .map_err(|_| D::Error::custom("encountered invalid UTF-8 buffer"))?;
+        if ends.len() % 8 != 0 {
+            return Err(D::Error::custom("encountered invalid end offsets"))
+        }
+        let ends = ends
+            .chunks_exact(8)
+            .map(|chunk| {
+                let mut bytes = [0x00; 8];
+                bytes.copy_from_slice(chunk);
+                usize::try_from(u64::from_le_bytes(bytes))
+            })
+            .collect::<Result<Vec<usize>, _>>()
+            .map_err(|_| D::Error::custom("encountered out of bounds end offset"))?;
+        let mut from = 0;
+        for &to in &ends {
+            if to < from || !buffer.is_char_boundary(to) {
+                return Err(D::Error::custom("encountered invalid end offset"))
+            }
+            from = to;
+        }
+        if from != buffer.len() {
+            return Err(D::Error::custom("encountered trailing bytes in buffer"))
+        }
+        if let Some(last) = ends.len().checked_sub(1) {
+            S::try_from_usize(last)
+                .ok_or_else(|| D::Error::custom("encountered too many strings for symbol"))?;
+        }
+        self.ends.clear();
+        self.ends.extend_from_slice(&ends);
+        self.buffer.clear();
+        self.buffer.extend_from_slice(buffer.as_bytes());
+        Ok(())
+    }
+}
+
+/// Serializes the strings of the backend as a sequence for human-readable formats.
+///
+/// Otherwise serializes the raw end offsets and buffer of the backend as byte
+/// blobs. End offsets are encoded as little-endian `u64` values.
+#[cfg(feature = "serde-1")]
+impl<S, A> serde::Serialize for StringBackend<S, A>
+where
+    S: Symbol,
+    A: Allocator,
+{
+    fn serialize<T>(&self, serializer: T) -> Result<T::Ok, T::Error>
+    where
+        T: serde::Serializer,
+    {
+        if serializer.is_human_readable() {
+            return crate::serde_impl::serialize_strings::<Self, T>(self, serializer)
+        }
+        self.serialize_raw(serializer)
+    }
+}
+
+/// Deserializes the backend by interning a sequence of strings for human-readable
+/// formats. Otherwise deserializes the raw end offsets and buffer of the backend.
+///
+/// Validates that the buffer is valid UTF-8 and that the end offsets are
+/// ascending char boundaries of the buffer.
+#[cfg(feature = "serde-1")]
+impl<'de, S, A> serde::Deserialize<'de> for StringBackend<S, A>
+where
+    S: Symbol,
+    A: Allocator + Default,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        if deserializer.is_human_readable() {
+            return crate::serde_impl::deserialize_strings::<Self, D>(deserializer)
+        }
+        let mut backend = Self::default();
+        backend.deserialize_raw(deserializer)?;
+        Ok(backend)
+    }
+}
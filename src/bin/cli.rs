@@ -0,0 +1,329 @@
+//! Command-line tool to inspect string interners serialized as JSON.
+//!
+//! Run `string-interner help` for usage information.
+
+use serde::{
+    de::DeserializeOwned,
+    Deserialize,
+};
+use serde_json::Value;
+use std::{
+    error::Error,
+    fs::File,
+    io::{
+        self,
+        BufReader,
+        BufWriter,
+        Write,
+    },
+    process::ExitCode,
+};
+use string_interner::{
+    backend::{
+        Backend,
+        BucketBackend,
+        BufferBackend,
+        StringBackend,
+    },
+    DefaultSymbol,
+    LineFormat,
+    StringInterner,
+    Symbol,
+};
+
+const USAGE: &str = "\
+Inspects string interners serialized as JSON.
+
+Files are either sequences of strings or sequences of `[symbol, string]` pairs
+whose symbols are verified against the backend.
+
+Usage: string-interner [--backend <backend>] <command>
+       string-interner convert --backend <backend> [--from <backend>] <a> <b>
+
+Commands:
+    stats <file>                          Prints the number of strings, their total
+                                          bytes, the heap memory used by the interner
+                                          and a histogram of their lengths.
+    lookup <file> <string>                Prints the symbol of the string.
+    resolve <file> <symbol>               Prints the string of the symbol.
+    dump <file>                           Prints all `symbol<TAB>string` pairs.
+    diff <a> <b>                          Prints the strings that are only in `a`
+                                          or `b` and strings with different symbols.
+    convert <a> <b>                       Re-interns the strings of `a` with the
+                                          backend given by --backend and writes
+                                          them to `b` as `[symbol, string]` pairs.
+    help                                  Prints this message.
+
+Options:
+    --backend <backend>   The backend used to interpret files: buffer, string or bucket.
+                          Defaults to string. For convert the backend whose symbols
+                          are written, which is required.
+    --from <backend>      The backend used by convert to interpret `a`.
+                          Defaults to string.";
+
+/// Error for invalid command-line usage.
+#[derive(Debug)]
+struct UsageError(String);
+
+impl std::fmt::Display for UsageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}\n\n{}", self.0, USAGE)
+    }
+}
+
+impl Error for UsageError {}
+
+/// Returns a usage error with the given message.
+fn usage<T>(message: impl Into<String>) -> Result<T, Box<dyn Error>> {
+    Err(Box::new(UsageError(message.into())))
+}
+
+/// A parsed command.
+enum Command {
+    Stats(String),
+    Lookup(String, String),
+    Resolve(String, String),
+    Dump(String),
+    Diff(String, String),
+    Convert(String, String, BackendKind),
+    Help,
+}
+
+/// The backends that may be selected via `--backend`.
+#[derive(Copy, Clone)]
+enum BackendKind {
+    Buffer,
+    String,
+    Bucket,
+}
+
+fn main() -> ExitCode {
+    match run(std::env::args().skip(1).collect()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("error: {}", error);
+            if error.is::<UsageError>() {
+                ExitCode::from(2)
+            } else {
+                ExitCode::FAILURE
+            }
+        }
+    }
+}
+
+/// Parses the arguments and runs the command with the selected backend.
+fn run(args: Vec<String>) -> Result<(), Box<dyn Error>> {
+    let mut backend = None;
+    let mut from = None;
+    let mut positional = Vec::new();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--backend" => backend = Some(parse_backend(&arg, args.next())?),
+            "--from" => from = Some(parse_backend(&arg, args.next())?),
+            "--help" | "-h" => positional.push(String::from("help")),
+            _ => positional.push(arg),
+        }
+    }
+    let mut positional = positional.into_iter();
+    let name = positional.next();
+    let mut arg = |what: &str| {
+        positional
+            .next()
+            .map_or_else(|| usage(format!("missing argument: {}", what)), Ok)
+    };
+    let command = match name.as_deref() {
+        Some("stats") => Command::Stats(arg("file")?),
+        Some("lookup") => Command::Lookup(arg("file")?, arg("string")?),
+        Some("resolve") => Command::Resolve(arg("file")?, arg("symbol")?),
+        Some("dump") => Command::Dump(arg("file")?),
+        Some("diff") => Command::Diff(arg("a")?, arg("b")?),
+        Some("convert") => {
+            let Some(to) = backend.take() else {
+                return usage("convert requires --backend")
+            };
+            // The input of convert is interpreted with the backend given by --from.
+            backend = from.take();
+            Command::Convert(arg("a")?, arg("b")?, to)
+        }
+        Some("help") => Command::Help,
+        Some(name) => return usage(format!("unknown command: {}", name)),
+        None => return usage("missing command"),
+    };
+    if let Some(extra) = positional.next() {
+        return usage(format!("unexpected argument: {}", extra))
+    }
+    if from.is_some() {
+        return usage("--from is only supported by convert")
+    }
+    match backend.unwrap_or(BackendKind::String) {
+        BackendKind::Buffer => execute::<BufferBackend<DefaultSymbol>>(command),
+        BackendKind::String => execute::<StringBackend<DefaultSymbol>>(command),
+        BackendKind::Bucket => execute::<BucketBackend<DefaultSymbol>>(command),
+    }
+}
+
+/// Parses the backend named by the value of the given option.
+fn parse_backend(
+    option: &str,
+    name: Option<String>,
+) -> Result<BackendKind, Box<dyn Error>> {
+    match name.as_deref() {
+        Some("buffer") => Ok(BackendKind::Buffer),
+        Some("string") => Ok(BackendKind::String),
+        Some("bucket") => Ok(BackendKind::Bucket),
+        Some(name) => usage(format!("unknown backend: {}", name)),
+        None => usage(format!("missing value for {}", option)),
+    }
+}
+
+/// Reads the serialized string interner from the given file.
+///
+/// Files of `[symbol, string]` pairs fail to load if the backend `B` does not
+/// assign the recorded symbols.
+fn load<B>(path: &str) -> Result<StringInterner<B>, Box<dyn Error>>
+where
+    B: Backend<Symbol = DefaultSymbol> + Default + DeserializeOwned,
+    for<'a> &'a B: IntoIterator<Item = (DefaultSymbol, &'a str)>,
+{
+    let file = File::open(path).map_err(|error| format!("{}: {}", path, error))?;
+    let value: Value = serde_json::from_reader(BufReader::new(file))
+        .map_err(|error| format!("{}: {}", path, error))?;
+    let with_symbols = match &value {
+        Value::Array(elements) => elements.first().is_some_and(Value::is_array),
+        _ => false,
+    };
+    let interner = if with_symbols {
+        string_interner::serde::with_symbols::deserialize(value)
+    } else {
+        StringInterner::<B>::deserialize(value)
+    };
+    interner.map_err(|error| format!("{}: {}", path, error).into())
+}
+
+/// Re-interns the strings of the string interner with the backend `B` and
+/// writes them to the given file as `[symbol, string]` pairs.
+fn write_converted<B, I>(
+    interner: &StringInterner<I>,
+    path: &str,
+) -> Result<(), Box<dyn Error>>
+where
+    B: Backend<Symbol = DefaultSymbol> + Default,
+    for<'a> &'a B: IntoIterator<Item = (DefaultSymbol, &'a str)>,
+    I: Backend<Symbol = DefaultSymbol>,
+    for<'a> &'a I: IntoIterator<Item = (DefaultSymbol, &'a str)>,
+{
+    let converted = interner
+        .into_iter()
+        .map(|(_symbol, string)| string)
+        .collect::<StringInterner<B>>();
+    let file = File::create(path).map_err(|error| format!("{}: {}", path, error))?;
+    let mut writer = BufWriter::new(file);
+    let mut serializer = serde_json::Serializer::new(&mut writer);
+    string_interner::serde::with_symbols::serialize(&converted, &mut serializer)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Executes the command using the backend `B` to interpret files.
+fn execute<B>(command: Command) -> Result<(), Box<dyn Error>>
+where
+    B: Backend<Symbol = DefaultSymbol> + Default + DeserializeOwned,
+    for<'a> &'a B: IntoIterator<Item = (DefaultSymbol, &'a str)>,
+{
+    let stdout = io::stdout();
+    let mut out = BufWriter::new(stdout.lock());
+    match command {
+        Command::Stats(path) => {
+            let stats = load::<B>(&path)?.memory_stats();
+            writeln!(out, "strings: {}", stats.len_strings)?;
+            writeln!(out, "bytes:   {}", stats.string_bytes)?;
+            match stats.total() {
+                Some(total) => {
+                    writeln!(
+                        out,
+                        "heap:    {} ({} slack)",
+                        total.heap_bytes,
+                        total.slack_bytes()
+                    )?;
+                }
+                None => writeln!(out, "heap:    unknown")?,
+            }
+            writeln!(out, "lengths:")?;
+            for (bucket, &count) in stats.length_histogram.iter().enumerate() {
+                if count == 0 {
+                    continue
+                }
+                let (from, to) = match bucket {
+                    0 => (0, 1),
+                    bucket => (1 << (bucket - 1), 1 << bucket),
+                };
+                writeln!(out, "    {:>6}..{:<6} {}", from, to, count)?;
+            }
+        }
+        Command::Lookup(path, string) => {
+            let interner = load::<B>(&path)?;
+            match interner.get(&string) {
+                Some(symbol) => writeln!(out, "{}", symbol.to_usize())?,
+                None => return Err(format!("string not found: {:?}", string).into()),
+            }
+        }
+        Command::Resolve(path, symbol) => {
+            let interner = load::<B>(&path)?;
+            let resolved = symbol
+                .parse::<usize>()
+                .ok()
+                .and_then(DefaultSymbol::try_from_usize)
+                .and_then(|symbol| interner.resolve(symbol));
+            match resolved {
+                Some(string) => writeln!(out, "{}", string)?,
+                None => return Err(format!("symbol not found: {}", symbol).into()),
+            }
+        }
+        Command::Dump(path) => {
+            load::<B>(&path)?.write_lines(&mut out, LineFormat::Symbols)?;
+        }
+        Command::Diff(lhs_path, rhs_path) => {
+            let lhs = load::<B>(&lhs_path)?;
+            let rhs = load::<B>(&rhs_path)?;
+            for (symbol, string) in &lhs {
+                match rhs.get(string) {
+                    None => writeln!(out, "- {}\t{:?}", symbol.to_usize(), string)?,
+                    Some(other) if other != symbol => {
+                        writeln!(
+                            out,
+                            "~ {} -> {}\t{:?}",
+                            symbol.to_usize(),
+                            other.to_usize(),
+                            string
+                        )?
+                    }
+                    Some(_) => {}
+                }
+            }
+            for (symbol, string) in &rhs {
+                if lhs.get(string).is_none() {
+                    writeln!(out, "+ {}\t{:?}", symbol.to_usize(), string)?
+                }
+            }
+        }
+        Command::Convert(input, output, to) => {
+            let interner = load::<B>(&input)?;
+            let (interner, output) = (&interner, output.as_str());
+            match to {
+                BackendKind::Buffer => {
+                    write_converted::<BufferBackend<DefaultSymbol>, B>(interner, output)?
+                }
+                BackendKind::String => {
+                    write_converted::<StringBackend<DefaultSymbol>, B>(interner, output)?
+                }
+                BackendKind::Bucket => {
+                    write_converted::<BucketBackend<DefaultSymbol>, B>(interner, output)?
+                }
+            }
+        }
+        Command::Help => writeln!(out, "{}", USAGE)?,
+    }
+    out.flush()?;
+    Ok(())
+}
@@ -0,0 +1,480 @@
+//! String interner with a fixed capacity that evicts least-recently-used strings.
+//!
+//! Unlike the [`StringInterner`](`crate::StringInterner`) that keeps all strings
+//! alive until it is dropped, the [`BoundedInterner`] is suited to cache strings
+//! of bounded lifetime such as request-scoped user agents or URL paths.
+
+use crate::{
+    backend::{
+        Backend,
+        ContiguousBackend,
+    },
+    compat::{
+        Box,
+        DefaultHashBuilder,
+        String,
+        ToString,
+        Vec,
+    },
+    DefaultBackend,
+    DefaultSymbol,
+    StringInterner,
+    Symbol,
+};
+use core::{
+    fmt,
+    fmt::{
+        Debug,
+        Formatter,
+    },
+    hash::BuildHasher,
+    num::NonZeroU32,
+};
+
+/// Marks the end of the recency list and strings of the storage without a slot.
+const NIL: u32 = u32::MAX;
+
+/// The symbol of a string interned by a [`BoundedInterner`].
+///
+/// Besides the slot of the string the symbol stores the generation of the slot
+/// at the time the string was interned. Slots are reused for new strings after
+/// their strings have been evicted which increments their generation, so that
+/// symbols of evicted strings are detected as invalid.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct BoundedSymbol {
+    index: u32,
+    generation: NonZeroU32,
+}
+
+impl BoundedSymbol {
+    /// Returns the index of the slot of the symbol.
+    #[inline]
+    pub fn index(self) -> usize {
+        self.index as usize
+    }
+
+    /// Returns the generation of the slot of the symbol.
+    #[inline]
+    pub fn generation(self) -> u32 {
+        self.generation.get()
+    }
+}
+
+/// A slot of the [`BoundedInterner`] that holds a string.
+#[derive(Debug)]
+struct Slot<S> {
+    /// The symbol of the string of the slot in the storage if it is occupied.
+    string: Option<S>,
+    /// The generation of the current or next string of the slot.
+    generation: NonZeroU32,
+    /// The next more recently used slot or [`NIL`].
+    prev: u32,
+    /// The next less recently used slot or [`NIL`].
+    next: u32,
+}
+
+/// Callback that is invoked with the symbol and string of evicted strings.
+type EvictFn = Box<dyn FnMut(BoundedSymbol, &str) + Send>;
+
+/// String interner with a fixed capacity that evicts least-recently-used strings.
+///
+/// Interning a new string into a full interner evicts the least recently used
+/// string. Strings are used by interning them via [`BoundedInterner::get_or_intern`]
+/// or by [`BoundedInterner::touch`]ing their symbols.
+///
+/// Symbols of evicted strings become invalid: [`BoundedInterner::resolve`] returns
+/// `None` for them and never the string that replaced the evicted one.
+///
+/// # Note
+///
+/// The strings are stored and deduplicated by a [`StringInterner`] with backend `B`.
+/// Since backends cannot remove strings, evicted strings stay in the storage until
+/// it holds twice the capacity of strings. Then the storage is rebuilt from the
+/// strings that have not been evicted. Evicted strings that are interned again
+/// before that reuse their storage.
+///
+/// A slot is retired once its generations are exhausted after about four billion
+/// evictions, reducing the capacity by one so that symbols are never ambiguous.
+///
+/// # Example
+///
+/// ```
+/// use string_interner::BoundedInterner;
+///
+/// let mut interner = <BoundedInterner>::new(2);
+/// let tiger = interner.get_or_intern("Tiger");
+/// let horse = interner.get_or_intern("Horse");
+/// interner.touch(tiger);
+/// let bird = interner.get_or_intern("Bird");
+/// assert_eq!(interner.resolve(tiger), Some("Tiger"));
+/// assert_eq!(interner.resolve(horse), None);
+/// assert_eq!(interner.resolve(bird), Some("Bird"));
+/// ```
+pub struct BoundedInterner<B = DefaultBackend<DefaultSymbol>, H = DefaultHashBuilder>
+where
+    B: ContiguousBackend,
+    H: BuildHasher,
+{
+    /// Stores the strings of the slots as well as evicted strings.
+    storage: StringInterner<B, H>,
+    /// Maps the symbols of the storage to the indices of their slots or [`NIL`].
+    slot_of: Vec<u32>,
+    slots: Vec<Slot<<B as Backend>::Symbol>>,
+    /// The indices of the vacant slots that are not retired.
+    vacant: Vec<u32>,
+    capacity: usize,
+    /// The number of occupied slots.
+    len: usize,
+    /// The most recently used slot or [`NIL`].
+    head: u32,
+    /// The least recently used slot or [`NIL`].
+    tail: u32,
+    on_evict: Option<EvictFn>,
+}
+
+impl<B, H> Debug for BoundedInterner<B, H>
+where
+    B: ContiguousBackend + Debug,
+    <B as Backend>::Symbol: Debug,
+    H: BuildHasher,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BoundedInterner")
+            .field("storage", &self.storage)
+            .field("capacity", &self.capacity)
+            .field("slots", &self.slots)
+            .field("head", &self.head)
+            .field("tail", &self.tail)
+            .finish()
+    }
+}
+
+impl<B, H> BoundedInterner<B, H>
+where
+    B: ContiguousBackend + Default,
+    H: BuildHasher + Default,
+{
+    /// Creates a new empty `BoundedInterner` that holds up to `capacity` strings.
+    ///
+    /// # Panics
+    ///
+    /// If `capacity` is zero or exceeds `u32::MAX` or the symbols of `B`.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn new(capacity: usize) -> Self {
+        Self::with_hasher(capacity, H::default())
+    }
+}
+
+impl<B, H> BoundedInterner<B, H>
+where
+    B: ContiguousBackend + Default,
+    H: BuildHasher,
+{
+    /// Creates a new empty `BoundedInterner` with the given capacity and hasher.
+    ///
+    /// # Panics
+    ///
+    /// If `capacity` is zero or exceeds `u32::MAX` or the symbols of `B`.
+    pub fn with_hasher(capacity: usize, hash_builder: H) -> Self {
+        assert!(
+            capacity != 0
+                && capacity <= u32::MAX as usize
+                && B::Symbol::try_from_usize(capacity - 1).is_some(),
+            "encountered invalid capacity {} for bounded interner",
+            capacity
+        );
+        Self {
+            storage: StringInterner::with_hasher(hash_builder),
+            slot_of: Vec::new(),
+            slots: Vec::new(),
+            vacant: Vec::new(),
+            capacity,
+            len: 0,
+            head: NIL,
+            tail: NIL,
+            on_evict: None,
+        }
+    }
+
+    /// Sets the callback that is invoked with the symbol and string of every
+    /// evicted string.
+    ///
+    /// The callback is invoked after the string has been evicted, so that its
+    /// symbol is already invalid.
+    pub fn set_on_evict<F>(&mut self, on_evict: F)
+    where
+        F: FnMut(BoundedSymbol, &str) + Send + 'static,
+    {
+        self.on_evict = Some(Box::new(on_evict));
+    }
+
+    /// Returns the maximum number of strings held by the interner.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the number of strings held by the interner.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the interner holds no strings.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the symbol of the slot at the given index.
+    #[inline]
+    fn symbol(&self, index: u32) -> BoundedSymbol {
+        BoundedSymbol {
+            index,
+            generation: self.slots[index as usize].generation,
+        }
+    }
+
+    /// Returns the index of the slot holding the given string if any.
+    fn find(&self, string: &str) -> Option<u32> {
+        let symbol = self.storage.get(string)?;
+        let index = self.slot_of[symbol.to_usize()];
+        (index != NIL).then_some(index)
+    }
+
+    /// Returns the symbol for the given string if any.
+    ///
+    /// Unlike [`BoundedInterner::get_or_intern`] this does not mark the string as used.
+    #[inline]
+    pub fn get<T>(&self, string: T) -> Option<BoundedSymbol>
+    where
+        T: AsRef<str>,
+    {
+        self.find(string.as_ref()).map(|index| self.symbol(index))
+    }
+
+    /// Interns the given string and marks it as the most recently used string.
+    ///
+    /// Evicts the least recently used string if the interner is full.
+    ///
+    /// # Panics
+    ///
+    /// If all slots have been retired.
+    pub fn get_or_intern<T>(&mut self, string: T) -> BoundedSymbol
+    where
+        T: AsRef<str>,
+    {
+        let string = string.as_ref();
+        if let Some(index) = self.find(string) {
+            self.unlink(index);
+            self.push_front(index);
+            return self.symbol(index)
+        }
+        let index = self.vacant_slot();
+        let symbol = match self.storage.get(string) {
+            Some(symbol) => symbol,
+            None => {
+                if self.needs_compaction() {
+                    self.compact();
+                }
+                self.slot_of.push(NIL);
+                self.storage.get_or_intern(string)
+            }
+        };
+        self.slot_of[symbol.to_usize()] = index;
+        self.slots[index as usize].string = Some(symbol);
+        self.len += 1;
+        self.push_front(index);
+        self.symbol(index)
+    }
+
+    /// Marks the string of the symbol as the most recently used string.
+    ///
+    /// Returns `false` if the string of the symbol has been evicted.
+    pub fn touch(&mut self, symbol: BoundedSymbol) -> bool {
+        if self.resolve(symbol).is_none() {
+            return false
+        }
+        self.unlink(symbol.index);
+        self.push_front(symbol.index);
+        true
+    }
+
+    /// Resolves the symbol to its string.
+    ///
+    /// Returns `None` if the string of the symbol has been evicted.
+    /// Does not mark the string as used.
+    #[inline]
+    pub fn resolve(&self, symbol: BoundedSymbol) -> Option<&str> {
+        let slot = self.slots.get(symbol.index as usize)?;
+        if slot.generation != symbol.generation {
+            return None
+        }
+        self.storage.resolve(slot.string?)
+    }
+
+    /// Returns `true` if the storage is due to drop its evicted strings.
+    fn needs_compaction(&self) -> bool {
+        let len = self.storage.len();
+        len >= self.capacity.saturating_mul(2) || B::Symbol::try_from_usize(len).is_none()
+    }
+
+    /// Rebuilds the storage from the strings of the occupied slots.
+    fn compact(&mut self) {
+        let strings = self
+            .slots
+            .iter()
+            .map(|slot| {
+                slot.string
+                    .and_then(|symbol| self.storage.resolve(symbol))
+                    .map(ToString::to_string)
+            })
+            .collect::<Vec<Option<String>>>();
+        self.storage.clear();
+        self.slot_of.clear();
+        for (index, (slot, string)) in self.slots.iter_mut().zip(strings).enumerate() {
+            if let Some(string) = string {
+                slot.string = Some(self.storage.get_or_intern(string));
+                self.slot_of.push(index as u32);
+            }
+        }
+    }
+
+    /// Returns the index of an empty slot, evicting strings if necessary.
+    fn vacant_slot(&mut self) -> u32 {
+        loop {
+            if let Some(index) = self.vacant.pop() {
+                return index
+            }
+            if self.slots.len() == self.capacity {
+                let index = self.tail;
+                assert!(index != NIL, "all slots of the bounded interner are retired");
+                self.evict(index);
+                continue
+            }
+            self.slots.push(Slot {
+                string: None,
+                generation: NonZeroU32::MIN,
+                prev: NIL,
+                next: NIL,
+            });
+            return (self.slots.len() - 1) as u32
+        }
+    }
+
+    /// Evicts the string of the slot at the given index.
+    ///
+    /// The string stays in the storage until it is compacted. The slot becomes
+    /// vacant unless its generations are exhausted in which case it is retired.
+    fn evict(&mut self, index: u32) {
+        let symbol = self.symbol(index);
+        self.unlink(index);
+        let slot = &mut self.slots[index as usize];
+        let string = slot
+            .string
+            .take()
+            .expect("encountered vacant slot in the recency list");
+        if let Some(generation) = slot.generation.checked_add(1) {
+            slot.generation = generation;
+            self.vacant.push(index);
+        }
+        self.slot_of[string.to_usize()] = NIL;
+        self.len -= 1;
+        if let Some(on_evict) = &mut self.on_evict {
+            let evicted = self
+                .storage
+                .resolve(string)
+                .expect("encountered invalid symbol in the storage");
+            on_evict(symbol, evicted);
+        }
+    }
+
+    /// Removes the slot at the given index from the recency list.
+    fn unlink(&mut self, index: u32) {
+        let Slot { prev, next, .. } = self.slots[index as usize];
+        match prev {
+            NIL => self.head = next,
+            prev => self.slots[prev as usize].next = next,
+        }
+        match next {
+            NIL => self.tail = prev,
+            next => self.slots[next as usize].prev = prev,
+        }
+        let slot = &mut self.slots[index as usize];
+        slot.prev = NIL;
+        slot.next = NIL;
+    }
+
+    /// Inserts the slot at the given index as the most recently used slot.
+    fn push_front(&mut self, index: u32) {
+        let head = self.head;
+        {
+            let slot = &mut self.slots[index as usize];
+            slot.prev = NIL;
+            slot.next = head;
+        }
+        match head {
+            NIL => self.tail = index,
+            head => self.slots[head as usize].prev = index,
+        }
+        self.head = index;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exhausted_slots_are_retired() {
+        let mut interner = <BoundedInterner>::new(2);
+        let aa = interner.get_or_intern("aa");
+        let bb = interner.get_or_intern("bb");
+        interner.slots[aa.index()].generation = NonZeroU32::MAX;
+        let aa = interner.get("aa").unwrap();
+        // Evicting `aa` retires its slot so `cc` evicts `bb` as well.
+        let cc = interner.get_or_intern("cc");
+        assert_eq!(interner.resolve(aa), None);
+        assert_eq!(interner.resolve(bb), None);
+        assert_eq!(cc.index(), bb.index());
+        assert_eq!(interner.len(), 1);
+        // Only the retired slot is exhausted.
+        let dd = interner.get_or_intern("dd");
+        assert_eq!(interner.resolve(cc), None);
+        assert_eq!(dd.index(), bb.index());
+    }
+
+    #[test]
+    fn storage_is_compacted() {
+        let mut interner = <BoundedInterner>::new(3);
+        let aa = interner.get_or_intern("aa");
+        for i in 0..100 {
+            interner.get_or_intern(i.to_string());
+            interner.touch(aa);
+            assert!(interner.storage.len() <= 6);
+        }
+        assert_eq!(interner.resolve(aa), Some("aa"));
+        assert_eq!(interner.get("99").and_then(|s| interner.resolve(s)), Some("99"));
+        assert_eq!(interner.get("97"), None);
+        assert_eq!(interner.len(), 3);
+    }
+
+    #[test]
+    fn evicted_strings_are_reused() {
+        let mut interner = <BoundedInterner>::new(1);
+        interner.get_or_intern("aa");
+        interner.get_or_intern("bb");
+        let aa = interner.get_or_intern("aa");
+        assert_eq!(interner.resolve(aa), Some("aa"));
+        assert_eq!(interner.storage.len(), 2);
+    }
+
+    #[test]
+    #[should_panic]
+    fn all_slots_retired_panics() {
+        let mut interner = <BoundedInterner>::new(1);
+        let aa = interner.get_or_intern("aa");
+        interner.slots[aa.index()].generation = NonZeroU32::MAX;
+        interner.get_or_intern("bb");
+    }
+}
@@ -3,28 +3,48 @@
 use cfg_if::cfg_if;
 
 pub use ::hashbrown::{
-    hash_map,
-    hash_map::{
-        DefaultHashBuilder,
-        HashMap,
-    },
+    hash_map::DefaultHashBuilder,
+    hash_table,
+    HashTable,
 };
 
+cfg_if! {
+    if #[cfg(feature = "allocator-api")] {
+        pub use ::allocator_api2::{
+            alloc::{
+                Allocator,
+                Global,
+            },
+            boxed::Box as BoxIn,
+            vec::Vec as VecIn,
+        };
+        pub use ::hashbrown::HashTable as HashTableIn;
+    } else {
+        pub use crate::global_alloc::{
+            Allocator,
+            BoxIn,
+            Global,
+            HashTableIn,
+            VecIn,
+        };
+    }
+}
+
 cfg_if! {
     if #[cfg(feature = "std")] {
         pub use ::std::{
-            vec,
+            boxed::Box,
             vec::Vec,
             string::{String, ToString},
-            boxed::Box,
+            sync::{Arc, Weak},
         };
     } else {
         extern crate alloc;
         pub use self::alloc::{
-            vec,
+            boxed::Box,
             vec::Vec,
             string::{String, ToString},
-            boxed::Box,
+            sync::{Arc, Weak},
         };
     }
 }
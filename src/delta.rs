@@ -0,0 +1,258 @@
+//! Incremental replication of string interners.
+//!
+//! A [`StringInterner`](`crate::StringInterner`) only ever appends strings and
+//! all built-in backends assign symbols in append order. Therefore a replica
+//! can be kept in sync with a master interner by shipping only the strings
+//! that were interned since the replica's last known [`Watermark`].
+//!
+//! - [`StringInterner::watermark`](`crate::StringInterner::watermark`): Marks
+//!   the current state of an interner.
+//! - [`StringInterner::strings_since`](`crate::StringInterner::strings_since`):
+//!   Collects all strings interned after a watermark into a [`Delta`].
+//! - [`StringInterner::apply_delta`](`crate::StringInterner::apply_delta`):
+//!   Appends the strings of a [`Delta`] to a replica using the same symbols.
+
+use crate::{
+    compat::{
+        String,
+        Vec,
+    },
+    InternError,
+};
+use core::{
+    fmt,
+    slice,
+};
+
+/// Marks the strings interned by a string interner at some point.
+///
+/// Consists of the number of interned strings and a fingerprint of their
+/// contents so that replicas holding different strings of the same number
+/// are told apart.
+///
+/// Obtained via [`StringInterner::watermark`](`crate::StringInterner::watermark`).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Watermark {
+    len: usize,
+    fingerprint: u64,
+}
+
+impl Default for Watermark {
+    #[inline]
+    fn default() -> Self {
+        Self::EMPTY
+    }
+}
+
+impl Watermark {
+    /// The watermark of an interner without interned strings.
+    pub(crate) const EMPTY: Self = Self {
+        len: 0,
+        fingerprint: 0xcbf2_9ce4_8422_2325,
+    };
+
+    /// Creates a watermark from its number of strings and fingerprint.
+    #[cfg(feature = "serde-1")]
+    #[inline]
+    pub(crate) fn new(len: usize, fingerprint: u64) -> Self {
+        Self { len, fingerprint }
+    }
+
+    /// Returns the number of interned strings marked by `self`.
+    #[inline]
+    pub fn to_usize(self) -> usize {
+        self.len
+    }
+
+    /// Returns the fingerprint of the contents of the interned strings.
+    ///
+    /// # Note
+    ///
+    /// The fingerprint is a 64-bit FNV-1a hash of the length prefixed strings
+    /// that is stable across processes and platforms.
+    #[inline]
+    pub fn fingerprint(self) -> u64 {
+        self.fingerprint
+    }
+
+    /// Advances the watermark past the given string.
+    pub(crate) fn push(&mut self, string: &str) {
+        const PRIME: u64 = 0x0000_0100_0000_01b3;
+        let len = (string.len() as u64).to_le_bytes();
+        for &byte in len.iter().chain(string.as_bytes()) {
+            self.fingerprint = (self.fingerprint ^ u64::from(byte)).wrapping_mul(PRIME);
+        }
+        self.len += 1;
+    }
+}
+
+/// The strings interned by a string interner after a [`Watermark`].
+///
+/// Every string is recorded together with the `usize` value of its symbol
+/// so that a replica is able to verify that it assigns the same symbols.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Delta {
+    pub(crate) start: Watermark,
+    pub(crate) end: Watermark,
+    pub(crate) entries: Vec<(usize, String)>,
+}
+
+impl Delta {
+    /// Returns the watermark a replica must be at to apply the delta.
+    #[inline]
+    pub fn start(&self) -> Watermark {
+        self.start
+    }
+
+    /// Returns the watermark a replica is at after applying the delta.
+    #[inline]
+    pub fn end(&self) -> Watermark {
+        self.end
+    }
+
+    /// Returns the number of strings in the delta.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the delta contains no strings.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns an iterator over the `usize` symbol values and strings of the delta.
+    #[inline]
+    pub fn iter(&self) -> DeltaIter<'_> {
+        DeltaIter {
+            iter: self.entries.iter(),
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a Delta {
+    type Item = (usize, &'a str);
+    type IntoIter = DeltaIter<'a>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterator over the `usize` symbol values and strings of a [`Delta`].
+#[derive(Debug)]
+pub struct DeltaIter<'a> {
+    iter: slice::Iter<'a, (usize, String)>,
+}
+
+impl<'a> Iterator for DeltaIter<'a> {
+    type Item = (usize, &'a str);
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter
+            .next()
+            .map(|(symbol, string)| (*symbol, string.as_str()))
+    }
+}
+
+impl<'a> ExactSizeIterator for DeltaIter<'a> {}
+
+/// Errors that may occur when applying a [`Delta`] to a replica.
+///
+/// All of them signal that the replica has diverged from its master.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeltaError {
+    /// The replica does not hold the number of strings the delta starts at.
+    WatermarkMismatch {
+        /// The number of strings the delta expects the replica to hold.
+        expected: usize,
+        /// The number of strings the replica actually holds.
+        actual: usize,
+    },
+    /// The replica holds different strings than the interner that created
+    /// the delta before its start.
+    ///
+    /// Detected either at the start of the delta or at its end after
+    /// appending its strings to the replica's fingerprint.
+    FingerprintMismatch {
+        /// The fingerprint the delta expects the replica to be at.
+        expected: u64,
+        /// The fingerprint the replica is at.
+        actual: u64,
+    },
+    /// A string of the delta has already been interned by the replica.
+    AlreadyInterned {
+        /// The symbol value of the string in the delta.
+        symbol: usize,
+    },
+    /// The replica assigned a different symbol to a string of the delta.
+    SymbolMismatch {
+        /// The symbol value recorded by the delta.
+        expected: usize,
+        /// The symbol value assigned by the replica.
+        actual: usize,
+    },
+    /// Interning a string of the delta failed.
+    Intern(InternError),
+}
+
+impl fmt::Display for DeltaError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::WatermarkMismatch { expected, actual } => {
+                write!(
+                    f,
+                    "delta starts at {} strings but the replica holds {}",
+                    expected, actual
+                )
+            }
+            Self::FingerprintMismatch { expected, actual } => {
+                write!(
+                    f,
+                    "delta expects fingerprint {:#018x} but the replica is at {:#018x}",
+                    expected, actual
+                )
+            }
+            Self::AlreadyInterned { symbol } => {
+                write!(
+                    f,
+                    "string of symbol {} has already been interned by the replica",
+                    symbol
+                )
+            }
+            Self::SymbolMismatch { expected, actual } => {
+                write!(
+                    f,
+                    "replica assigned symbol {} instead of {}",
+                    actual, expected
+                )
+            }
+            Self::Intern(error) => error.fmt(f),
+        }
+    }
+}
+
+impl From<InternError> for DeltaError {
+    #[inline]
+    fn from(error: InternError) -> Self {
+        Self::Intern(error)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DeltaError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Intern(error) => Some(error),
+            _ => None,
+        }
+    }
+}
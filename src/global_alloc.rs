@@ -0,0 +1,337 @@
+//! Stand-ins for the `allocator-api2` types if the `allocator-api` crate feature is disabled.
+//!
+//! They only support the [`Global`] allocator and forward to the collections of the
+//! standard library so that the string interner does not depend on `allocator-api2`.
+
+use crate::{
+    compat::{
+        Box,
+        HashTable,
+        Vec,
+    },
+    TryReserveError,
+};
+use core::{
+    alloc::Layout,
+    cmp,
+    fmt,
+    hash::{
+        Hash,
+        Hasher,
+    },
+    ops::{
+        Deref,
+        DerefMut,
+    },
+};
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// Allocators that the string interner and its backends allocate their memory from.
+///
+/// Only implemented by [`Global`] unless the `allocator-api` crate feature is enabled
+/// which replaces it by the `Allocator` trait of the `allocator-api2` crate.
+pub trait Allocator: sealed::Sealed {}
+
+/// The global memory allocator.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Global;
+
+impl sealed::Sealed for Global {}
+impl Allocator for Global {}
+
+/// Returns the error for a failed allocation of `capacity` elements of type `T`.
+fn reserve_error<T>(capacity: Option<usize>) -> TryReserveError {
+    match capacity.map(Layout::array::<T>) {
+        Some(Ok(layout)) => TryReserveError::AllocError { layout },
+        _ => TryReserveError::CapacityOverflow,
+    }
+}
+
+/// A vector that stores the allocator it conceptually allocates from.
+pub struct VecIn<T, A = Global>
+where
+    A: Allocator,
+{
+    vec: Vec<T>,
+    alloc: A,
+}
+
+impl<T> VecIn<T> {
+    /// Creates a new empty vector.
+    #[inline]
+    pub fn new() -> Self {
+        Self::new_in(Global)
+    }
+}
+
+impl<T, A> VecIn<T, A>
+where
+    A: Allocator,
+{
+    /// Creates a new empty vector for the allocator.
+    #[inline]
+    pub fn new_in(alloc: A) -> Self {
+        Self {
+            vec: Vec::new(),
+            alloc,
+        }
+    }
+
+    /// Creates a new empty vector with the given capacity for the allocator.
+    #[inline]
+    pub fn with_capacity_in(capacity: usize, alloc: A) -> Self {
+        Self {
+            vec: Vec::with_capacity(capacity),
+            alloc,
+        }
+    }
+
+    /// Returns a reference to the allocator of the vector.
+    #[inline]
+    pub fn allocator(&self) -> &A {
+        &self.alloc
+    }
+
+    /// Tries to reserve capacity for at least `additional` more elements.
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        let Self { vec, .. } = self;
+        vec.try_reserve(additional).map_err(|_| {
+            // Mirrors the amortized growth strategy of the vector.
+            let doubled = vec.capacity().saturating_mul(2);
+            let required = vec.len().checked_add(additional);
+            reserve_error::<T>(required.map(|required| cmp::max(doubled, required)))
+        })
+    }
+
+    /// Tries to reserve capacity for exactly `additional` more elements.
+    pub fn try_reserve_exact(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        let Self { vec, .. } = self;
+        vec.try_reserve_exact(additional)
+            .map_err(|_| reserve_error::<T>(vec.len().checked_add(additional)))
+    }
+
+    /// Converts the vector into a boxed slice, dropping its excess capacity.
+    #[inline]
+    pub fn into_boxed_slice(self) -> BoxIn<[T], A> {
+        BoxIn {
+            boxed: self.vec.into_boxed_slice(),
+            alloc: self.alloc,
+        }
+    }
+}
+
+impl<T, A> Deref for VecIn<T, A>
+where
+    A: Allocator,
+{
+    type Target = Vec<T>;
+
+    #[inline]
+    fn deref(&self) -> &Vec<T> {
+        &self.vec
+    }
+}
+
+impl<T, A> DerefMut for VecIn<T, A>
+where
+    A: Allocator,
+{
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Vec<T> {
+        &mut self.vec
+    }
+}
+
+impl<'a, T, A> IntoIterator for &'a VecIn<T, A>
+where
+    A: Allocator,
+{
+    type Item = &'a T;
+    type IntoIter = core::slice::Iter<'a, T>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.vec.iter()
+    }
+}
+
+impl<T, A> Clone for VecIn<T, A>
+where
+    T: Clone,
+    A: Allocator + Clone,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            vec: self.vec.clone(),
+            alloc: self.alloc.clone(),
+        }
+    }
+}
+
+impl<T, A> fmt::Debug for VecIn<T, A>
+where
+    T: fmt::Debug,
+    A: Allocator,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.vec, f)
+    }
+}
+
+impl<T, U, A1, A2> PartialEq<VecIn<U, A2>> for VecIn<T, A1>
+where
+    T: PartialEq<U>,
+    A1: Allocator,
+    A2: Allocator,
+{
+    #[inline]
+    fn eq(&self, other: &VecIn<U, A2>) -> bool {
+        self.vec == other.vec
+    }
+}
+
+impl<T, U, A, const N: usize> PartialEq<[U; N]> for VecIn<T, A>
+where
+    T: PartialEq<U>,
+    A: Allocator,
+{
+    #[inline]
+    fn eq(&self, other: &[U; N]) -> bool {
+        self.vec == other
+    }
+}
+
+impl<T, A> Eq for VecIn<T, A>
+where
+    T: Eq,
+    A: Allocator,
+{
+}
+
+impl<T, A> Hash for VecIn<T, A>
+where
+    T: Hash,
+    A: Allocator,
+{
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.vec.hash(state)
+    }
+}
+
+/// A box that stores the allocator it conceptually allocates from.
+pub struct BoxIn<T, A = Global>
+where
+    T: ?Sized,
+    A: Allocator,
+{
+    boxed: Box<T>,
+    alloc: A,
+}
+
+impl<T, A> Deref for BoxIn<T, A>
+where
+    T: ?Sized,
+    A: Allocator,
+{
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        &self.boxed
+    }
+}
+
+impl<T, A> Clone for BoxIn<T, A>
+where
+    T: ?Sized,
+    Box<T>: Clone,
+    A: Allocator + Clone,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            boxed: self.boxed.clone(),
+            alloc: self.alloc.clone(),
+        }
+    }
+}
+
+/// A hash table that stores the allocator it conceptually allocates from.
+pub struct HashTableIn<T, A = Global>
+where
+    A: Allocator,
+{
+    table: HashTable<T>,
+    alloc: A,
+}
+
+impl<T, A> HashTableIn<T, A>
+where
+    A: Allocator,
+{
+    /// Creates a new empty hash table with the given capacity for the allocator.
+    #[inline]
+    pub fn with_capacity_in(capacity: usize, alloc: A) -> Self {
+        Self {
+            table: HashTable::with_capacity(capacity),
+            alloc,
+        }
+    }
+
+    /// Returns a reference to the allocator of the hash table.
+    #[inline]
+    pub fn allocator(&self) -> &A {
+        &self.alloc
+    }
+}
+
+impl<T, A> Deref for HashTableIn<T, A>
+where
+    A: Allocator,
+{
+    type Target = HashTable<T>;
+
+    #[inline]
+    fn deref(&self) -> &HashTable<T> {
+        &self.table
+    }
+}
+
+impl<T, A> DerefMut for HashTableIn<T, A>
+where
+    A: Allocator,
+{
+    #[inline]
+    fn deref_mut(&mut self) -> &mut HashTable<T> {
+        &mut self.table
+    }
+}
+
+impl<T, A> Clone for HashTableIn<T, A>
+where
+    T: Clone,
+    A: Allocator + Clone,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            table: self.table.clone(),
+            alloc: self.alloc.clone(),
+        }
+    }
+}
+
+impl<T, A> fmt::Debug for HashTableIn<T, A>
+where
+    T: fmt::Debug,
+    A: Allocator,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.table, f)
+    }
+}
@@ -0,0 +1,538 @@
+//! The [`InternMap`] that associates values with interned strings and the
+//! types of its entry API.
+
+use crate::{
+    backend::{
+        Backend,
+        ContiguousBackend,
+    },
+    compat::{
+        Allocator,
+        DefaultHashBuilder,
+        Global,
+        VecIn,
+    },
+    DefaultBackend,
+    DefaultSymbol,
+    InternError,
+    StringInterner,
+    Symbol,
+    TryReserveError,
+};
+use core::{
+    fmt,
+    fmt::{
+        Debug,
+        Formatter,
+    },
+    hash::BuildHasher,
+    iter::Enumerate,
+    mem,
+    slice,
+};
+
+/// A string interner that associates a value with every interned string.
+///
+/// The values are stored in a vector indexed by the symbols of their strings
+/// which requires a [`ContiguousBackend`]. Every interned string always has
+/// a value so that strings and values cannot get out of sync. The vector is
+/// allocated from the allocator of the string interner.
+///
+/// # Example
+///
+/// ```
+/// use string_interner::InternMap;
+///
+/// let mut map = <InternMap<usize>>::new();
+/// let (tiger, old) = map.insert("Tiger", 1);
+/// assert_eq!(old, None);
+/// *map.entry("Tiger").or_insert(0) += 1;
+/// *map.entry("Horse").or_insert(0) += 1;
+/// assert_eq!(map.get_by_symbol(tiger), Some(&2));
+/// assert_eq!(map.get_by_str("Horse"), Some(&1));
+/// assert_eq!(map.interner().resolve(tiger), Some("Tiger"));
+/// ```
+pub struct InternMap<
+    V,
+    B = DefaultBackend<DefaultSymbol>,
+    H = DefaultHashBuilder,
+    A = Global,
+> where
+    B: ContiguousBackend,
+    H: BuildHasher,
+    A: Allocator,
+{
+    interner: StringInterner<B, H, A>,
+    /// The values indexed by the symbols of their strings.
+    values: VecIn<V, A>,
+}
+
+impl<V, B, H, A> Debug for InternMap<V, B, H, A>
+where
+    V: Debug,
+    B: ContiguousBackend,
+    <B as Backend>::Symbol: Symbol + Debug,
+    H: BuildHasher,
+    A: Allocator,
+{
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_map()
+            .entries(self.iter().map(|(_, string, value)| (string, value)))
+            .finish()
+    }
+}
+
+impl<V, B, H, A> Clone for InternMap<V, B, H, A>
+where
+    V: Clone,
+    B: ContiguousBackend + Clone,
+    <B as Backend>::Symbol: Symbol,
+    H: BuildHasher + Clone,
+    A: Allocator + Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            interner: self.interner.clone(),
+            values: self.values.clone(),
+        }
+    }
+}
+
+impl<V, B, H, A> Default for InternMap<V, B, H, A>
+where
+    B: ContiguousBackend + Default,
+    <B as Backend>::Symbol: Symbol,
+    H: BuildHasher + Default,
+    A: Allocator + Clone + Default,
+{
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V, B, H, A> InternMap<V, B, H, A>
+where
+    B: ContiguousBackend + Default,
+    <B as Backend>::Symbol: Symbol,
+    H: BuildHasher + Default,
+    A: Allocator + Clone + Default,
+{
+    /// Creates a new empty `InternMap`.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn new() -> Self {
+        Self::with_hasher(H::default())
+    }
+}
+
+impl<V, B, H, A> InternMap<V, B, H, A>
+where
+    B: ContiguousBackend,
+    <B as Backend>::Symbol: Symbol,
+    H: BuildHasher,
+    A: Allocator,
+{
+    /// Creates a new empty `InternMap` with the given hasher.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn with_hasher(hash_builder: H) -> Self
+    where
+        B: Default,
+        A: Clone + Default,
+    {
+        let interner = StringInterner::<B, H, A>::with_hasher(hash_builder);
+        let values = VecIn::new_in(interner.allocator().clone());
+        Self { interner, values }
+    }
+
+    /// Returns the underlying string interner.
+    #[inline]
+    pub fn interner(&self) -> &StringInterner<B, H, A> {
+        &self.interner
+    }
+
+    /// Returns the values indexed by the symbols of their strings.
+    #[inline]
+    pub fn values(&self) -> &[V] {
+        &self.values
+    }
+
+    /// Returns the number of strings of the map.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns `true` if the map has no strings.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Interns the given string and sets its value.
+    ///
+    /// Returns the symbol of the string and its previous value if any.
+    ///
+    /// # Panics
+    ///
+    /// If interning the string fails, see [`StringInterner::get_or_intern`].
+    /// Use [`InternMap::try_insert`] to handle this case.
+    pub fn insert<T>(
+        &mut self,
+        string: T,
+        value: V,
+    ) -> (<B as Backend>::Symbol, Option<V>)
+    where
+        T: AsRef<str>,
+    {
+        let string = string.as_ref();
+        if let Some(symbol) = self.interner.get(string) {
+            let old = mem::replace(&mut self.values[symbol.to_usize()], value);
+            return (symbol, Some(old))
+        }
+        (self.push(string, value), None)
+    }
+
+    /// Tries to intern the given string and sets its value.
+    ///
+    /// Returns the symbol of the string and its previous value if any.
+    ///
+    /// # Errors
+    ///
+    /// If interning the string fails, see [`StringInterner::try_get_or_intern`].
+    /// The value is dropped in this case.
+    pub fn try_insert<T>(
+        &mut self,
+        string: T,
+        value: V,
+    ) -> Result<(<B as Backend>::Symbol, Option<V>), InternError>
+    where
+        T: AsRef<str>,
+    {
+        let string = string.as_ref();
+        if let Some(symbol) = self.interner.get(string) {
+            let old = mem::replace(&mut self.values[symbol.to_usize()], value);
+            return Ok((symbol, Some(old)))
+        }
+        Ok((self.try_push(string, value)?, None))
+    }
+
+    /// Interns a new string with its value and returns its symbol.
+    fn push(&mut self, string: &str, value: V) -> <B as Backend>::Symbol {
+        let symbol = self.interner.get_or_intern(string);
+        debug_assert_eq!(symbol.to_usize(), self.values.len());
+        self.values.push(value);
+        symbol
+    }
+
+    /// Tries to intern a new string with its value and returns its symbol.
+    fn try_push(
+        &mut self,
+        string: &str,
+        value: V,
+    ) -> Result<<B as Backend>::Symbol, InternError> {
+        // Reserve first so that the string is not interned without its value.
+        self.try_reserve_value()?;
+        let symbol = self.interner.try_get_or_intern(string)?;
+        debug_assert_eq!(symbol.to_usize(), self.values.len());
+        self.values.push(value);
+        Ok(symbol)
+    }
+
+    /// Tries to reserve capacity for the value of one more string.
+    fn try_reserve_value(&mut self) -> Result<(), TryReserveError> {
+        self.values.try_reserve(1)?;
+        Ok(())
+    }
+
+    /// Returns the symbol of the given string if any.
+    #[inline]
+    pub fn get_symbol<T>(&self, string: T) -> Option<<B as Backend>::Symbol>
+    where
+        T: AsRef<str>,
+    {
+        self.interner.get(string)
+    }
+
+    /// Returns the value of the given string if any.
+    #[inline]
+    pub fn get_by_str<T>(&self, string: T) -> Option<&V>
+    where
+        T: AsRef<str>,
+    {
+        self.get_by_symbol(self.interner.get(string)?)
+    }
+
+    /// Returns the value of the given string mutably if any.
+    #[inline]
+    pub fn get_by_str_mut<T>(&mut self, string: T) -> Option<&mut V>
+    where
+        T: AsRef<str>,
+    {
+        self.get_by_symbol_mut(self.interner.get(string)?)
+    }
+
+    /// Returns the value of the string of the given symbol if any.
+    #[inline]
+    pub fn get_by_symbol(&self, symbol: <B as Backend>::Symbol) -> Option<&V> {
+        self.values.get(symbol.to_usize())
+    }
+
+    /// Returns the value of the string of the given symbol mutably if any.
+    #[inline]
+    pub fn get_by_symbol_mut(
+        &mut self,
+        symbol: <B as Backend>::Symbol,
+    ) -> Option<&mut V> {
+        self.values.get_mut(symbol.to_usize())
+    }
+
+    /// Returns the entry of the given string for in-place manipulation.
+    pub fn entry<'a>(&'a mut self, string: &'a str) -> Entry<'a, V, B, H, A> {
+        match self.interner.get(string) {
+            Some(symbol) => {
+                Entry::Occupied(OccupiedEntry {
+                    symbol,
+                    value: &mut self.values[symbol.to_usize()],
+                })
+            }
+            None => Entry::Vacant(VacantEntry { map: self, string }),
+        }
+    }
+
+    /// Returns an iterator over the symbols, strings and values of the map.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, V, B, H, A> {
+        Iter {
+            interner: &self.interner,
+            values: self.values.iter().enumerate(),
+        }
+    }
+
+    /// Returns the underlying string interner and the values indexed by the
+    /// symbols of their strings.
+    #[inline]
+    pub fn into_parts(self) -> (StringInterner<B, H, A>, VecIn<V, A>) {
+        (self.interner, self.values)
+    }
+}
+
+/// A view into a single string of an [`InternMap`].
+///
+/// Constructed via [`InternMap::entry`].
+pub enum Entry<'a, V, B, H, A>
+where
+    B: ContiguousBackend,
+    H: BuildHasher,
+    A: Allocator,
+{
+    /// The string has been interned.
+    Occupied(OccupiedEntry<'a, V, B>),
+    /// The string has not been interned.
+    Vacant(VacantEntry<'a, V, B, H, A>),
+}
+
+impl<'a, V, B, H, A> Entry<'a, V, B, H, A>
+where
+    B: ContiguousBackend,
+    <B as Backend>::Symbol: Symbol,
+    H: BuildHasher,
+    A: Allocator,
+{
+    /// Interns the string with the given value if it is vacant.
+    ///
+    /// Returns the value of the string mutably.
+    #[inline]
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        self.or_insert_with(|| default)
+    }
+
+    /// Interns the string with the value returned by `default` if it is vacant.
+    ///
+    /// Returns the value of the string mutably.
+    #[inline]
+    pub fn or_insert_with<F>(self, default: F) -> &'a mut V
+    where
+        F: FnOnce() -> V,
+    {
+        match self {
+            Self::Occupied(entry) => entry.into_mut(),
+            Self::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    /// Interns the string with the default value if it is vacant.
+    ///
+    /// Returns the value of the string mutably.
+    #[inline]
+    pub fn or_default(self) -> &'a mut V
+    where
+        V: Default,
+    {
+        self.or_insert_with(V::default)
+    }
+
+    /// Calls `f` with the value of the string if it is occupied.
+    #[inline]
+    pub fn and_modify<F>(mut self, f: F) -> Self
+    where
+        F: FnOnce(&mut V),
+    {
+        if let Self::Occupied(entry) = &mut self {
+            f(entry.get_mut());
+        }
+        self
+    }
+}
+
+/// A view into a string of an [`InternMap`] that has been interned.
+pub struct OccupiedEntry<'a, V, B>
+where
+    B: Backend,
+{
+    symbol: <B as Backend>::Symbol,
+    value: &'a mut V,
+}
+
+impl<'a, V, B> OccupiedEntry<'a, V, B>
+where
+    B: Backend,
+{
+    /// Returns the symbol of the string.
+    #[inline]
+    pub fn symbol(&self) -> <B as Backend>::Symbol {
+        self.symbol
+    }
+
+    /// Returns the value of the string.
+    #[inline]
+    pub fn get(&self) -> &V {
+        self.value
+    }
+
+    /// Returns the value of the string mutably.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut V {
+        self.value
+    }
+
+    /// Returns the value of the string mutably for the lifetime of the map.
+    #[inline]
+    pub fn into_mut(self) -> &'a mut V {
+        self.value
+    }
+
+    /// Sets the value of the string and returns its previous value.
+    #[inline]
+    pub fn insert(&mut self, value: V) -> V {
+        mem::replace(self.value, value)
+    }
+}
+
+/// A view into a string of an [`InternMap`] that has not been interned.
+pub struct VacantEntry<'a, V, B, H, A>
+where
+    B: ContiguousBackend,
+    H: BuildHasher,
+    A: Allocator,
+{
+    map: &'a mut InternMap<V, B, H, A>,
+    string: &'a str,
+}
+
+impl<'a, V, B, H, A> VacantEntry<'a, V, B, H, A>
+where
+    B: ContiguousBackend,
+    <B as Backend>::Symbol: Symbol,
+    H: BuildHasher,
+    A: Allocator,
+{
+    /// Returns the string of the entry.
+    #[inline]
+    pub fn string(&self) -> &'a str {
+        self.string
+    }
+
+    /// Interns the string with the given value and returns the value mutably.
+    ///
+    /// # Panics
+    ///
+    /// If interning the string fails, see [`StringInterner::get_or_intern`].
+    /// Use [`VacantEntry::try_insert`] to handle this case.
+    pub fn insert(self, value: V) -> &'a mut V {
+        let symbol = self.map.push(self.string, value);
+        &mut self.map.values[symbol.to_usize()]
+    }
+
+    /// Tries to intern the string with the given value and returns the value mutably.
+    ///
+    /// # Errors
+    ///
+    /// If interning the string fails, see [`StringInterner::try_get_or_intern`].
+    pub fn try_insert(self, value: V) -> Result<&'a mut V, InternError> {
+        let symbol = self.map.try_push(self.string, value)?;
+        Ok(&mut self.map.values[symbol.to_usize()])
+    }
+}
+
+/// Iterator over the symbols, strings and values of an [`InternMap`].
+pub struct Iter<'a, V, B, H, A>
+where
+    B: ContiguousBackend,
+    H: BuildHasher,
+    A: Allocator,
+{
+    interner: &'a StringInterner<B, H, A>,
+    values: Enumerate<slice::Iter<'a, V>>,
+}
+
+impl<'a, V, B, H, A> Iterator for Iter<'a, V, B, H, A>
+where
+    B: ContiguousBackend,
+    <B as Backend>::Symbol: Symbol,
+    H: BuildHasher,
+    A: Allocator,
+{
+    type Item = (<B as Backend>::Symbol, &'a str, &'a V);
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.values.size_hint()
+    }
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let (index, value) = self.values.next()?;
+        let symbol = <B as Backend>::Symbol::try_from_usize(index)
+            .expect("encountered invalid symbol");
+        let string = self
+            .interner
+            .resolve(symbol)
+            .expect("encountered missing string for contiguous symbol");
+        Some((symbol, string, value))
+    }
+}
+
+impl<'a, V, B, H, A> ExactSizeIterator for Iter<'a, V, B, H, A>
+where
+    B: ContiguousBackend,
+    <B as Backend>::Symbol: Symbol,
+    H: BuildHasher,
+    A: Allocator,
+{
+}
+
+impl<'a, V, B, H, A> IntoIterator for &'a InternMap<V, B, H, A>
+where
+    B: ContiguousBackend,
+    <B as Backend>::Symbol: Symbol,
+    H: BuildHasher,
+    A: Allocator,
+{
+    type Item = (<B as Backend>::Symbol, &'a str, &'a V);
+    type IntoIter = Iter<'a, V, B, H, A>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
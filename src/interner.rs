@@ -1,12 +1,37 @@
+#[cfg(feature = "allocator-api")]
+use crate::backend::BackendIn;
+#[cfg(feature = "backends")]
+use crate::{
+    backend::{
+        BorrowedBackend,
+        SourceBackend,
+    },
+    compat::Arc,
+};
 use crate::{
     backend::Backend,
     compat::{
+        hash_table::Entry,
+        Allocator,
         DefaultHashBuilder,
-        HashMap,
+        Global,
+        HashTableIn,
+        ToString,
+        Vec,
+    },
+    delta::{
+        Delta,
+        DeltaError,
+        Watermark,
     },
     DefaultBackend,
     DefaultSymbol,
+    InternError,
+    InternerLimits,
+    MemoryUsage,
+    RemainingCapacity,
     Symbol,
+    TryReserveError,
 };
 use core::{
     fmt,
@@ -20,10 +45,11 @@ use core::{
         Hasher,
     },
     iter::FromIterator,
+    mem,
 };
 
 /// Creates the `u64` hash value for the given value using the given hash builder.
-fn make_hash<T>(builder: &impl BuildHasher, value: &T) -> u64
+pub(crate) fn make_hash<T>(builder: &impl BuildHasher, value: &T) -> u64
 where
     T: ?Sized + Hash,
 {
@@ -43,21 +69,35 @@ where
 ///     - This maps from `string` type to `symbol` type.
 /// - [`StringInterner::resolve`]: To resolve your already interned strings.
 ///     - This maps from `symbol` type to `string` type.
-pub struct StringInterner<B = DefaultBackend<DefaultSymbol>, H = DefaultHashBuilder>
-where
+///
+/// The deduplication table allocates from the allocator `A`. Use the
+/// `allocator-api` crate feature to provide an allocator via
+/// `StringInterner::new_in` and friends.
+pub struct StringInterner<
+    B = DefaultBackend<DefaultSymbol>,
+    H = DefaultHashBuilder,
+    A = Global,
+> where
     B: Backend,
     H: BuildHasher,
+    A: Allocator,
 {
-    dedup: HashMap<<B as Backend>::Symbol, (), ()>,
+    dedup: HashTableIn<<B as Backend>::Symbol, A>,
     hasher: H,
     backend: B,
+    limits: InternerLimits,
+    /// The sum of the lengths of all interned strings.
+    total_bytes: usize,
+    /// The watermark of all interned strings, advanced whenever a string is interned.
+    watermark: Watermark,
 }
 
-impl<B, H> Debug for StringInterner<B, H>
+impl<B, H, A> Debug for StringInterner<B, H, A>
 where
     B: Backend + Debug,
     <B as Backend>::Symbol: Symbol + Debug,
     H: BuildHasher,
+    A: Allocator,
 {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         f.debug_struct("StringInterner")
@@ -75,53 +115,63 @@ impl Default for StringInterner {
     }
 }
 
-impl<B, H> Clone for StringInterner<B, H>
+impl<B, H, A> Clone for StringInterner<B, H, A>
 where
     B: Backend + Clone,
     <B as Backend>::Symbol: Symbol,
     H: BuildHasher + Clone,
+    A: Allocator + Clone,
 {
     fn clone(&self) -> Self {
         Self {
             dedup: self.dedup.clone(),
             hasher: self.hasher.clone(),
             backend: self.backend.clone(),
+            limits: self.limits,
+            total_bytes: self.total_bytes,
+            watermark: self.watermark,
         }
     }
 }
 
-impl<B, H> PartialEq for StringInterner<B, H>
+impl<B, H, A> PartialEq for StringInterner<B, H, A>
 where
     B: Backend + PartialEq,
     <B as Backend>::Symbol: Symbol,
     H: BuildHasher,
+    A: Allocator,
 {
     fn eq(&self, rhs: &Self) -> bool {
         self.len() == rhs.len() && self.backend == rhs.backend
     }
 }
 
-impl<B, H> Eq for StringInterner<B, H>
+impl<B, H, A> Eq for StringInterner<B, H, A>
 where
     B: Backend + Eq,
     <B as Backend>::Symbol: Symbol,
     H: BuildHasher,
+    A: Allocator,
 {
 }
 
-impl<B, H> StringInterner<B, H>
+impl<B, H, A> StringInterner<B, H, A>
 where
-    B: Backend,
+    B: Backend + Default,
     <B as Backend>::Symbol: Symbol,
     H: BuildHasher + Default,
+    A: Allocator + Default,
 {
     /// Creates a new empty `StringInterner`.
     #[cfg_attr(feature = "inline-more", inline)]
     pub fn new() -> Self {
         Self {
-            dedup: HashMap::default(),
+            dedup: HashTableIn::with_capacity_in(0, A::default()),
             hasher: Default::default(),
             backend: B::default(),
+            limits: InternerLimits::UNLIMITED,
+            total_bytes: 0,
+            watermark: Watermark::EMPTY,
         }
     }
 
@@ -129,39 +179,148 @@ where
     #[cfg_attr(feature = "inline-more", inline)]
     pub fn with_capacity(cap: usize) -> Self {
         Self {
-            dedup: HashMap::with_capacity_and_hasher(cap, ()),
+            dedup: HashTableIn::with_capacity_in(cap, A::default()),
             hasher: Default::default(),
             backend: B::with_capacity(cap),
+            limits: InternerLimits::UNLIMITED,
+            total_bytes: 0,
+            watermark: Watermark::EMPTY,
         }
     }
+
+    /// Tries to create a new `StringInterner` with the given initial capacity.
+    ///
+    /// # Errors
+    ///
+    /// If the capacity overflows or the allocator reports a failure.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn try_with_capacity(cap: usize) -> Result<Self, TryReserveError> {
+        let mut interner = Self::new();
+        interner.try_reserve(cap)?;
+        Ok(interner)
+    }
+
+    /// Tries to create a new `StringInterner` from the strings of the iterator.
+    ///
+    /// This is the fallible counterpart of the [`FromIterator`] implementation.
+    ///
+    /// # Errors
+    ///
+    /// If interning a string fails, see [`StringInterner::try_get_or_intern`].
+    pub fn try_from_iter<I>(iter: I) -> Result<Self, InternError>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let iter = iter.into_iter();
+        let mut interner = Self::try_with_capacity(iter.size_hint().0)?;
+        interner.try_extend(iter)?;
+        Ok(interner)
+    }
 }
 
-impl<B, H> StringInterner<B, H>
+#[cfg(feature = "allocator-api")]
+impl<B, H, A> StringInterner<B, H, A>
+where
+    B: BackendIn<A>,
+    <B as Backend>::Symbol: Symbol,
+    H: BuildHasher,
+    A: Allocator + Clone,
+{
+    /// Creates a new empty `StringInterner` that allocates from `alloc`.
+    ///
+    /// Both the deduplication table and the backend allocate from `alloc`.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn new_in(alloc: A) -> Self
+    where
+        H: Default,
+    {
+        Self::with_capacity_and_hasher_in(0, Default::default(), alloc)
+    }
+
+    /// Creates a new `StringInterner` with the given initial capacity that
+    /// allocates from `alloc`.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn with_capacity_in(cap: usize, alloc: A) -> Self
+    where
+        H: Default,
+    {
+        Self::with_capacity_and_hasher_in(cap, Default::default(), alloc)
+    }
+
+    /// Creates a new empty `StringInterner` with the given hasher that
+    /// allocates from `alloc`.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn with_hasher_in(hash_builder: H, alloc: A) -> Self {
+        Self::with_capacity_and_hasher_in(0, hash_builder, alloc)
+    }
+
+    /// Creates a new empty `StringInterner` with the given initial capacity and the
+    /// given hasher that allocates from `alloc`.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn with_capacity_and_hasher_in(
+        cap: usize,
+        hash_builder: H,
+        alloc: A,
+    ) -> Self {
+        Self {
+            dedup: HashTableIn::with_capacity_in(cap, alloc.clone()),
+            hasher: hash_builder,
+            backend: B::with_capacity_in(cap, alloc),
+            limits: InternerLimits::UNLIMITED,
+            total_bytes: 0,
+            watermark: Watermark::EMPTY,
+        }
+    }
+}
+
+impl<B, H, A> StringInterner<B, H, A>
 where
     B: Backend,
     <B as Backend>::Symbol: Symbol,
     H: BuildHasher,
+    A: Allocator,
 {
     /// Creates a new empty `StringInterner` with the given hasher.
     #[cfg_attr(feature = "inline-more", inline)]
-    pub fn with_hasher(hash_builder: H) -> Self {
+    pub fn with_hasher(hash_builder: H) -> Self
+    where
+        B: Default,
+        A: Default,
+    {
         StringInterner {
-            dedup: HashMap::default(),
+            dedup: HashTableIn::with_capacity_in(0, A::default()),
             hasher: hash_builder,
             backend: B::default(),
+            limits: InternerLimits::UNLIMITED,
+            total_bytes: 0,
+            watermark: Watermark::EMPTY,
         }
     }
 
     /// Creates a new empty `StringInterner` with the given initial capacity and the given hasher.
     #[cfg_attr(feature = "inline-more", inline)]
-    pub fn with_capacity_and_hasher(cap: usize, hash_builder: H) -> Self {
+    pub fn with_capacity_and_hasher(cap: usize, hash_builder: H) -> Self
+    where
+        B: Default,
+        A: Default,
+    {
         StringInterner {
-            dedup: HashMap::with_capacity_and_hasher(cap, ()),
+            dedup: HashTableIn::with_capacity_in(cap, A::default()),
             hasher: hash_builder,
             backend: B::with_capacity(cap),
+            limits: InternerLimits::UNLIMITED,
+            total_bytes: 0,
+            watermark: Watermark::EMPTY,
         }
     }
 
+    /// Returns a reference to the allocator of the deduplication table.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn allocator(&self) -> &A {
+        self.dedup.allocator()
+    }
+
     /// Returns the number of strings interned by the interner.
     #[cfg_attr(feature = "inline-more", inline)]
     pub fn len(&self) -> usize {
@@ -174,6 +333,36 @@ where
         self.len() == 0
     }
 
+    /// Returns the limits on the strings the interner accepts.
+    #[inline]
+    pub fn limits(&self) -> &InternerLimits {
+        &self.limits
+    }
+
+    /// Sets the limits on the strings the interner accepts.
+    ///
+    /// # Note
+    ///
+    /// Already interned strings are kept even if they exceed the new limits.
+    #[inline]
+    pub fn set_limits(&mut self, limits: InternerLimits) {
+        self.limits = limits;
+    }
+
+    /// Returns the number of strings and bytes that may still be interned
+    /// until the limits of the interner are reached.
+    ///
+    /// # Note
+    ///
+    /// A single string may additionally not exceed [`InternerLimits::max_string_len`].
+    #[inline]
+    pub fn remaining_capacity(&self) -> RemainingCapacity {
+        RemainingCapacity {
+            strings: self.limits.max_strings.saturating_sub(self.len()),
+            bytes: self.limits.max_total_bytes.saturating_sub(self.total_bytes),
+        }
+    }
+
     /// Returns the symbol for the given string if any.
     ///
     /// Can be used to query if a string has already been interned without interning.
@@ -187,16 +376,16 @@ where
             dedup,
             hasher,
             backend,
+            ..
         } = self;
         let hash = make_hash(hasher, string);
         dedup
-            .raw_entry()
-            .from_hash(hash, |symbol| {
+            .find(hash, |symbol| {
                 // SAFETY: This is safe because we only operate on symbols that
                 //         we receive from our backend making them valid.
                 string == unsafe { backend.resolve_unchecked(*symbol) }
             })
-            .map(|(&symbol, &())| symbol)
+            .copied()
     }
 
     /// Interns the given string.
@@ -218,27 +407,40 @@ where
             dedup,
             hasher,
             backend,
+            limits,
+            total_bytes,
+            watermark,
         } = self;
+        let len_strings = dedup.len();
         let hash = make_hash(hasher, string.as_ref());
-        let entry = dedup.raw_entry_mut().from_hash(hash, |symbol| {
-            // SAFETY: This is safe because we only operate on symbols that
-            //         we receive from our backend making them valid.
-            string == unsafe { backend.resolve_unchecked(*symbol) }
-        });
-        use crate::compat::hash_map::RawEntryMut;
-        let (&mut symbol, &mut ()) = match entry {
-            RawEntryMut::Occupied(occupied) => occupied.into_key_value(),
-            RawEntryMut::Vacant(vacant) => {
+        let entry = dedup.entry(
+            hash,
+            |symbol| {
+                // SAFETY: This is safe because we only operate on symbols that
+                //         we receive from our backend making them valid.
+                string == unsafe { backend.resolve_unchecked(*symbol) }
+            },
+            |symbol| {
+                // SAFETY: This is safe because we only operate on symbols that
+                //         we receive from our backend making them valid.
+                let string = unsafe { backend.resolve_unchecked(*symbol) };
+                make_hash(hasher, string)
+            },
+        );
+        match entry {
+            Entry::Occupied(occupied) => *occupied.get(),
+            Entry::Vacant(vacant) => {
+                let len = string.as_ref().len();
+                if let Err(error) = limits.check(len_strings, *total_bytes, len) {
+                    panic!("{}", error)
+                }
                 let symbol = intern_fn(backend, string);
-                vacant.insert_with_hasher(hash, symbol, (), |symbol| {
-                    // SAFETY: This is safe because we only operate on symbols that
-                    //         we receive from our backend making them valid.
-                    let string = unsafe { backend.resolve_unchecked(*symbol) };
-                    make_hash(hasher, string)
-                })
+                *total_bytes += len;
+                watermark.push(string.as_ref());
+                vacant.insert(symbol);
+                symbol
             }
-        };
-        symbol
+        }
     }
 
     /// Interns the given string.
@@ -247,8 +449,10 @@ where
     ///
     /// # Panics
     ///
-    /// If the interner already interns the maximum number of strings possible
-    /// by the chosen symbol type.
+    /// - If the interner already interns the maximum number of strings possible
+    ///   by the chosen symbol type.
+    /// - If interning the string exceeds the [`InternerLimits`] of the interner.
+    ///   Use [`StringInterner::try_get_or_intern`] to handle this case.
     #[inline]
     pub fn get_or_intern<T>(&mut self, string: T) -> <B as Backend>::Symbol
     where
@@ -257,6 +461,112 @@ where
         self.get_or_intern_using(string.as_ref(), B::intern)
     }
 
+    /// Tries to intern the given string.
+    ///
+    /// Returns a symbol for resolution into the original string.
+    ///
+    /// # Errors
+    ///
+    /// - If interning the string exceeds the [`InternerLimits`] of the interner.
+    /// - If the interner already interns the maximum number of strings possible
+    ///   by the chosen symbol type.
+    /// - If the capacity overflows or the allocator reports a failure.
+    ///
+    /// The string interner is left unchanged in these cases.
+    ///
+    /// # Panics
+    ///
+    /// If the backend does not implement [`Backend::try_intern`] and its symbols
+    /// are exhausted.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn try_get_or_intern<T>(
+        &mut self,
+        string: T,
+    ) -> Result<<B as Backend>::Symbol, InternError>
+    where
+        T: AsRef<str>,
+    {
+        let string = string.as_ref();
+        let Self {
+            dedup,
+            hasher,
+            backend,
+            limits,
+            total_bytes,
+            watermark,
+        } = self;
+        let hash = make_hash(hasher, string);
+        let existing = dedup.find(hash, |symbol| {
+            // SAFETY: This is safe because we only operate on symbols that
+            //         we receive from our backend making them valid.
+            string == unsafe { backend.resolve_unchecked(*symbol) }
+        });
+        if let Some(&symbol) = existing {
+            return Ok(symbol)
+        }
+        limits.check(dedup.len(), *total_bytes, string.len())?;
+        // Reserving up front guarantees that the insertion below does not allocate
+        // so that the backend is never left with a string missing in `dedup`.
+        dedup
+            .try_reserve(1, |symbol| {
+                // SAFETY: This is safe because we only operate on symbols that
+                //         we receive from our backend making them valid.
+                let string = unsafe { backend.resolve_unchecked(*symbol) };
+                make_hash(hasher, string)
+            })
+            .map_err(TryReserveError::from)?;
+        let symbol = backend.try_intern(string)?;
+        // Does not allocate since there is enough capacity reserved above.
+        dedup.insert_unique(hash, symbol, |symbol| {
+            // SAFETY: This is safe because we only operate on symbols that
+            //         we receive from our backend making them valid.
+            let string = unsafe { backend.resolve_unchecked(*symbol) };
+            make_hash(hasher, string)
+        });
+        *total_bytes += string.len();
+        watermark.push(string);
+        Ok(symbol)
+    }
+
+    /// Tries to intern all strings of the iterator.
+    ///
+    /// This is the fallible counterpart of the [`Extend`] implementation.
+    /// Stops at the first string that cannot be interned in which case the
+    /// strings of the iterator preceding it stay interned.
+    ///
+    /// # Errors
+    ///
+    /// If interning a string fails, see [`StringInterner::try_get_or_intern`].
+    pub fn try_extend<I>(&mut self, iter: I) -> Result<(), InternError>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        iter.into_iter()
+            .try_for_each(|string| self.try_get_or_intern(string).map(|_| ()))
+    }
+
+    /// Tries to reserve capacity for at least `additional` more strings.
+    ///
+    /// # Errors
+    ///
+    /// If the capacity overflows or the allocator reports a failure.
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        let Self {
+            dedup,
+            hasher,
+            backend,
+            ..
+        } = self;
+        dedup.try_reserve(additional, |symbol| {
+            // SAFETY: This is safe because we only operate on symbols that
+            //         we receive from our backend making them valid.
+            let string = unsafe { backend.resolve_unchecked(*symbol) };
+            make_hash(hasher, string)
+        })?;
+        backend.try_reserve(additional)
+    }
+
     /// Interns the given `'static` string.
     ///
     /// Returns a symbol for resolution into the original string.
@@ -268,8 +578,9 @@ where
     ///
     /// # Panics
     ///
-    /// If the interner already interns the maximum number of strings possible
-    /// by the chosen symbol type.
+    /// - If the interner already interns the maximum number of strings possible
+    ///   by the chosen symbol type.
+    /// - If interning the string exceeds the [`InternerLimits`] of the interner.
     #[inline]
     pub fn get_or_intern_static(
         &mut self,
@@ -283,20 +594,332 @@ where
         self.backend.shrink_to_fit()
     }
 
+    /// Returns a shared reference to the backend of the string interner.
+    #[inline]
+    pub(crate) fn backend(&self) -> &B {
+        &self.backend
+    }
+
+    /// Returns a shared reference to the hasher of the string interner.
+    #[inline]
+    pub(crate) fn hasher(&self) -> &H {
+        &self.hasher
+    }
+
+    /// Removes all strings from the interner while keeping its hasher and limits.
+    ///
+    /// Invalidates all symbols handed out by the interner. The backend is cleared
+    /// in place so that it keeps its allocator unless it does not support
+    /// [`Backend::truncate`].
+    pub(crate) fn clear(&mut self)
+    where
+        B: Default,
+    {
+        self.dedup.clear();
+        if !self.backend.truncate(0) {
+            self.backend = B::default();
+        }
+        self.total_bytes = 0;
+        self.watermark = Watermark::EMPTY;
+    }
+
+    /// Returns the estimated heap memory used by the deduplication table.
+    ///
+    /// Counts a symbol and a control byte for every entry the table has capacity
+    /// for. This is a lower bound since the table allocates spare buckets.
+    pub(crate) fn dedup_memory_usage(&self) -> MemoryUsage {
+        let entry_size = mem::size_of::<<B as Backend>::Symbol>() + 1;
+        MemoryUsage {
+            heap_bytes: self.dedup.capacity() * entry_size,
+            used_bytes: self.len() * entry_size,
+        }
+    }
+
     /// Returns the string for the given symbol if any.
     #[inline]
     pub fn resolve(&self, symbol: <B as Backend>::Symbol) -> Option<&str> {
         self.backend.resolve(symbol)
     }
+
+    /// Returns a watermark marking the strings interned so far.
+    ///
+    /// Use it with [`StringInterner::strings_since`] to query all strings
+    /// that are interned after this point.
+    #[inline]
+    pub fn watermark(&self) -> Watermark {
+        self.watermark
+    }
 }
 
-impl<B, H, T> FromIterator<T> for StringInterner<B, H>
+impl<B, H, A> StringInterner<B, H, A>
 where
     B: Backend,
     <B as Backend>::Symbol: Symbol,
+    for<'a> &'a B: IntoIterator<Item = (<B as Backend>::Symbol, &'a str)>,
+    H: BuildHasher,
+    A: Allocator,
+{
+    /// Creates a new `StringInterner` from the given backend and hasher whose
+    /// deduplication table allocates from `alloc`.
+    ///
+    /// Rebuilds the deduplication table from the strings of the backend, for
+    /// example of a deserialized [`BorrowedBackend`] that borrows its strings
+    /// from the input.
+    ///
+    /// Returns the backend back if it contains a string more than once.
+    ///
+    /// [`BorrowedBackend`]: crate::backend::BorrowedBackend
+    pub fn try_from_backend(backend: B, hasher: H, alloc: A) -> Result<Self, B> {
+        let mut dedup =
+            HashTableIn::with_capacity_in(backend.into_iter().size_hint().0, alloc);
+        let mut duplicate = false;
+        let mut total_bytes = 0;
+        let mut watermark = Watermark::EMPTY;
+        for (symbol, string) in &backend {
+            total_bytes += string.len();
+            watermark.push(string);
+            let hash = make_hash(&hasher, string);
+            let entry = dedup.entry(
+                hash,
+                |symbol| {
+                    // SAFETY: This is safe because we only operate on symbols that
+                    //         we receive from our backend making them valid.
+                    string == unsafe { backend.resolve_unchecked(*symbol) }
+                },
+                |symbol| {
+                    // SAFETY: This is safe because we only operate on symbols that
+                    //         we receive from our backend making them valid.
+                    let string = unsafe { backend.resolve_unchecked(*symbol) };
+                    make_hash(&hasher, string)
+                },
+            );
+            match entry {
+                Entry::Occupied(_) => {
+                    duplicate = true;
+                    break
+                }
+                Entry::Vacant(vacant) => {
+                    vacant.insert(symbol);
+                }
+            }
+        }
+        if duplicate {
+            return Err(backend)
+        }
+        Ok(Self {
+            dedup,
+            hasher,
+            backend,
+            limits: InternerLimits::UNLIMITED,
+            total_bytes,
+            watermark,
+        })
+    }
+
+    /// Returns all strings interned after the given watermark.
+    ///
+    /// # Note
+    ///
+    /// Only the number of strings of the watermark is taken into account. The
+    /// returned delta starts at the given watermark and ends at the watermark
+    /// of `self` so that replicas holding other strings fail to apply it.
+    ///
+    /// If the watermark is ahead of `self` the returned delta is empty and
+    /// starts at the current watermark of `self`.
+    pub fn strings_since(&self, watermark: Watermark) -> Delta {
+        let end = self.watermark;
+        if watermark.to_usize() > end.to_usize() {
+            return Delta {
+                start: end,
+                end,
+                entries: Vec::new(),
+            }
+        }
+        let entries = self
+            .backend
+            .into_iter()
+            .skip(watermark.to_usize())
+            .map(|(symbol, string)| (symbol.to_usize(), string.to_string()))
+            .collect();
+        Delta {
+            start: watermark,
+            end,
+            entries,
+        }
+    }
+
+    /// Appends the strings of the delta using the same symbols as its source.
+    ///
+    /// Either all strings of the delta are appended or `self` is left unchanged.
+    ///
+    /// # Errors
+    ///
+    /// If `self` has diverged from the interner that created the delta:
+    ///
+    /// - If `self` does not hold the number of strings the delta starts at.
+    /// - If `self` holds other strings than the delta starts at.
+    /// - If `self` already interned one of the strings of the delta.
+    /// - If `self` assigns a different symbol to one of the strings of the delta.
+    ///
+    /// Also if interning a string of the delta exceeds the [`InternerLimits`]
+    /// of `self` or the allocator reports a failure.
+    ///
+    /// # Note
+    ///
+    /// If the backend does not support [`Backend::truncate`] the strings of the
+    /// delta preceding a symbol mismatch or interning failure stay appended.
+    pub fn apply_delta(&mut self, delta: &Delta) -> Result<(), DeltaError> {
+        let start = delta.start();
+        let actual = self.watermark();
+        if actual.to_usize() != start.to_usize() {
+            return Err(DeltaError::WatermarkMismatch {
+                expected: start.to_usize(),
+                actual: actual.to_usize(),
+            })
+        }
+        if actual.fingerprint() != start.fingerprint() {
+            return Err(DeltaError::FingerprintMismatch {
+                expected: start.fingerprint(),
+                actual: actual.fingerprint(),
+            })
+        }
+        // The end of the delta tells apart replicas that hold other strings
+        // than the interner that created the delta before its start.
+        let mut end = actual;
+        for (_symbol, string) in delta {
+            end.push(string);
+        }
+        if end != delta.end() {
+            return Err(DeltaError::FingerprintMismatch {
+                expected: delta.end().fingerprint(),
+                actual: end.fingerprint(),
+            })
+        }
+        for (symbol, string) in delta {
+            if self.get(string).is_some() {
+                return Err(DeltaError::AlreadyInterned { symbol })
+            }
+        }
+        let total_bytes = self.total_bytes;
+        let mut appended = 0;
+        let result = delta.iter().try_for_each(|(expected, string)| {
+            let len = self.len();
+            let actual = self.try_get_or_intern(string)?.to_usize();
+            if self.len() == len {
+                return Err(DeltaError::AlreadyInterned { symbol: expected })
+            }
+            appended += 1;
+            if actual != expected {
+                return Err(DeltaError::SymbolMismatch { expected, actual })
+            }
+            Ok(())
+        });
+        if result.is_err() {
+            let appended = delta.iter().take(appended).map(|(_symbol, string)| string);
+            self.remove_appended(start, total_bytes, appended);
+        }
+        result
+    }
+
+    /// Removes the given strings appended after the strings of the watermark.
+    ///
+    /// Leaves `self` unchanged if the backend does not support truncation.
+    fn remove_appended<'a, I>(
+        &mut self,
+        watermark: Watermark,
+        total_bytes: usize,
+        appended: I,
+    ) where
+        I: IntoIterator<Item = &'a str>,
+    {
+        let removed = appended
+            .into_iter()
+            .filter_map(|string| {
+                let symbol = self.get(string)?;
+                Some((make_hash(&self.hasher, string), symbol))
+            })
+            .collect::<Vec<_>>();
+        if !self.backend.truncate(watermark.to_usize()) {
+            return
+        }
+        for (hash, symbol) in removed {
+            let entry = self.dedup.find_entry(hash, |&candidate| candidate == symbol);
+            if let Ok(occupied) = entry {
+                occupied.remove();
+            }
+        }
+        self.total_bytes = total_bytes;
+        self.watermark = watermark;
+    }
+}
+
+#[cfg(feature = "backends")]
+impl<'a, S, H, A> StringInterner<BorrowedBackend<'a, S, A>, H, A>
+where
+    S: Symbol,
+    H: BuildHasher,
+    A: Allocator + Clone,
+{
+    /// Interns the given borrowed string without copying it.
+    ///
+    /// Returns a symbol that resolves to the borrowed string via
+    /// [`StringInterner::resolve_borrowed`] even if the string has been
+    /// interned via [`StringInterner::get_or_intern`] before.
+    ///
+    /// # Panics
+    ///
+    /// If the interner already interns the maximum number of strings possible
+    /// by the chosen symbol type.
+    #[inline]
+    pub fn get_or_intern_borrowed(&mut self, string: &'a str) -> S {
+        let symbol = self.get_or_intern_using(string, BorrowedBackend::intern_borrowed);
+        self.backend.borrow(symbol, string);
+        symbol
+    }
+
+    /// Returns the borrowed string for the given symbol if any.
+    ///
+    /// Unlike [`StringInterner::resolve`] the returned string is not tied to
+    /// the borrow of the string interner.
+    ///
+    /// Returns `None` for strings that have only been interned via
+    /// [`StringInterner::get_or_intern`] since those are copied.
+    #[inline]
+    pub fn resolve_borrowed(&self, symbol: S) -> Option<&'a str> {
+        self.backend.resolve_borrowed(symbol)
+    }
+}
+
+#[cfg(feature = "backends")]
+impl<S, H, A> StringInterner<SourceBackend<S, A>, H, A>
+where
+    S: Symbol,
+    H: BuildHasher,
+    A: Allocator,
+{
+    /// Registers the source buffer so that interned slices of it are not copied.
+    ///
+    /// See [`SourceBackend`] for more information.
+    #[inline]
+    pub fn register_source(&mut self, source: Arc<str>) {
+        self.backend.register_source(source)
+    }
+}
+
+impl<B, H, A, T> FromIterator<T> for StringInterner<B, H, A>
+where
+    B: Backend + Default,
+    <B as Backend>::Symbol: Symbol,
     H: BuildHasher + Default,
+    A: Allocator + Default,
     T: AsRef<str>,
 {
+    /// Creates a new `StringInterner` from the strings of the iterator.
+    ///
+    /// # Panics
+    ///
+    /// If interning a string fails, see [`StringInterner::get_or_intern`].
+    /// Use [`StringInterner::try_from_iter`] to handle this case.
     fn from_iter<I>(iter: I) -> Self
     where
         I: IntoIterator<Item = T>,
@@ -309,13 +932,20 @@ where
     }
 }
 
-impl<B, H, T> Extend<T> for StringInterner<B, H>
+impl<B, H, A, T> Extend<T> for StringInterner<B, H, A>
 where
     B: Backend,
     <B as Backend>::Symbol: Symbol,
     H: BuildHasher,
+    A: Allocator,
     T: AsRef<str>,
 {
+    /// Interns all strings of the iterator.
+    ///
+    /// # Panics
+    ///
+    /// If interning a string fails, see [`StringInterner::get_or_intern`].
+    /// Use [`StringInterner::try_extend`] to handle this case.
     fn extend<I>(&mut self, iter: I)
     where
         I: IntoIterator<Item = T>,
@@ -326,12 +956,13 @@ where
     }
 }
 
-impl<'a, B, H> IntoIterator for &'a StringInterner<B, H>
+impl<'a, B, H, A> IntoIterator for &'a StringInterner<B, H, A>
 where
     B: Backend,
     <B as Backend>::Symbol: Symbol,
     &'a B: IntoIterator<Item = (<B as Backend>::Symbol, &'a str)>,
     H: BuildHasher,
+    A: Allocator,
 {
     type Item = (<B as Backend>::Symbol, &'a str);
     type IntoIter = <&'a B as IntoIterator>::IntoIter;
@@ -0,0 +1,384 @@
+//! Reference-counted interned strings that are released automatically.
+//!
+//! The [`IStrInterner`] hands out [`IStr`] handles instead of symbols. A handle
+//! keeps its string alive and resolves it without access to the interner.
+//! Once the last handle of a string is dropped the string is removed from the
+//! table of its interner and freed.
+
+use crate::{
+    compat::{
+        Arc,
+        Box,
+        DefaultHashBuilder,
+        HashTable,
+    },
+    interner::make_hash,
+};
+use core::{
+    cmp::Ordering,
+    fmt,
+    hash::{
+        BuildHasher,
+        Hash,
+        Hasher,
+    },
+    ops::Deref,
+    ptr,
+    ptr::NonNull,
+};
+use std::sync::{
+    Mutex,
+    MutexGuard,
+    PoisonError,
+    Weak,
+};
+
+/// An entry of the table of an [`IStrInterner`].
+struct Entry {
+    /// The cached hash of the string so that the table can be rehashed
+    /// without accessing strings that are about to be released.
+    hash: u64,
+    /// The string owned by [`Inner`].
+    string: NonNull<str>,
+    inner: Weak<Inner>,
+}
+
+// SAFETY: The string is immutable and only read while the table is locked.
+unsafe impl Send for Entry {}
+// SAFETY: The string is immutable and only read while the table is locked.
+unsafe impl Sync for Entry {}
+
+impl Entry {
+    /// Returns the string of the entry.
+    ///
+    /// # Safety
+    ///
+    /// The table of the entry must be locked.
+    unsafe fn as_str(&self) -> &str {
+        // SAFETY: Every entry in the table refers to a string that is alive since
+        //         strings are freed in `Inner::drop` only after removing their
+        //         entry while holding the lock.
+        unsafe { self.string.as_ref() }
+    }
+}
+
+/// The table of the strings of an [`IStrInterner`] shared with its handles.
+#[derive(Default)]
+struct Table {
+    entries: Mutex<HashTable<Entry>>,
+}
+
+impl Table {
+    /// Locks the table ignoring poisoning since the table is always consistent.
+    fn lock(&self) -> MutexGuard<'_, HashTable<Entry>> {
+        self.entries.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// The shared contents of the handles of an interned string.
+struct Inner {
+    hash: u64,
+    /// The string allocated via [`Box::into_raw`].
+    ///
+    /// Owned via a raw pointer so that the table may read it while
+    /// the last handle of the string is being dropped.
+    string: NonNull<str>,
+    table: Arc<Table>,
+}
+
+// SAFETY: The string is immutable and owned by `Inner`.
+unsafe impl Send for Inner {}
+// SAFETY: The string is immutable and owned by `Inner`.
+unsafe impl Sync for Inner {}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        let this: *const Inner = self;
+        let mut entries = self.table.lock();
+        // The entry might have been replaced by a new string with the same
+        // contents already if this string was interned again meanwhile.
+        let entry = entries.find_entry(self.hash, |entry| {
+            ptr::eq(entry.inner.as_ptr(), this)
+        });
+        if let Ok(entry) = entry {
+            entry.remove();
+        }
+        drop(entries);
+        // SAFETY: The string has been allocated via `Box::into_raw` and is no
+        //         longer referred to by the table.
+        drop(unsafe { Box::from_raw(self.string.as_ptr()) });
+    }
+}
+
+/// A reference-counted handle to a string interned by an [`IStrInterner`].
+///
+/// Dereferences to its string and resolves it without access to the interner.
+/// Comparing and hashing handles only considers the identity of their strings
+/// and thus takes constant time. Since the interner stores every string only
+/// once, handles of the same interner are equal if and only if their strings are.
+///
+/// # Note
+///
+/// Handles of different interners are never equal, even for equal strings.
+/// Ordering handles compares their strings first to be usable as sorted keys.
+#[derive(Clone)]
+pub struct IStr(Arc<Inner>);
+
+impl IStr {
+    /// Returns the string of the handle.
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        // SAFETY: The string is alive as long as its handles are.
+        unsafe { self.0.string.as_ref() }
+    }
+
+    /// Returns `true` if both handles refer to the same interned string.
+    #[inline]
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        Arc::ptr_eq(&this.0, &other.0)
+    }
+
+    /// Returns the number of handles of the string.
+    #[inline]
+    pub fn strong_count(this: &Self) -> usize {
+        Arc::strong_count(&this.0)
+    }
+}
+
+impl Deref for IStr {
+    type Target = str;
+
+    #[inline]
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<str> for IStr {
+    #[inline]
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl fmt::Debug for IStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl fmt::Display for IStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.as_str(), f)
+    }
+}
+
+impl PartialEq for IStr {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        Self::ptr_eq(self, other)
+    }
+}
+
+impl Eq for IStr {}
+
+impl PartialEq<str> for IStr {
+    #[inline]
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl<'a> PartialEq<&'a str> for IStr {
+    #[inline]
+    fn eq(&self, other: &&'a str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl Hash for IStr {
+    #[inline]
+    fn hash<T: Hasher>(&self, state: &mut T) {
+        ptr::hash(Arc::as_ptr(&self.0), state)
+    }
+}
+
+impl PartialOrd for IStr {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for IStr {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_str()
+            .cmp(other.as_str())
+            .then_with(|| Arc::as_ptr(&self.0).cmp(&Arc::as_ptr(&other.0)))
+    }
+}
+
+/// Serializes the handle as its string.
+#[cfg(feature = "serde-1")]
+impl serde::Serialize for IStr {
+    fn serialize<T>(&self, serializer: T) -> Result<T::Ok, T::Error>
+    where
+        T: serde::Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+/// Interns strings into reference-counted [`IStr`] handles.
+///
+/// Strings are removed from the interner once their last handle is dropped.
+/// Interning only requires a shared reference so that the interner can be
+/// shared between threads. Clones of the interner share the same strings.
+///
+/// # Example
+///
+/// ```
+/// use string_interner::{IStr, IStrInterner};
+///
+/// let interner = <IStrInterner>::new();
+/// let tiger = interner.intern("Tiger");
+/// let other = interner.intern(String::from("Tiger"));
+/// assert_eq!(tiger, other);
+/// assert_eq!(&*tiger, "Tiger");
+/// assert_eq!(interner.len(), 1);
+/// drop(tiger);
+/// drop(other);
+/// assert!(interner.is_empty());
+/// ```
+#[derive(Clone)]
+pub struct IStrInterner<H = DefaultHashBuilder>
+where
+    H: BuildHasher,
+{
+    table: Arc<Table>,
+    hasher: H,
+}
+
+impl<H> fmt::Debug for IStrInterner<H>
+where
+    H: BuildHasher,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("IStrInterner")
+            .field("len", &self.len())
+            .finish()
+    }
+}
+
+impl<H> Default for IStrInterner<H>
+where
+    H: BuildHasher + Default,
+{
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<H> IStrInterner<H>
+where
+    H: BuildHasher + Default,
+{
+    /// Creates a new empty `IStrInterner`.
+    #[inline]
+    pub fn new() -> Self {
+        Self::with_hasher(H::default())
+    }
+}
+
+impl<H> IStrInterner<H>
+where
+    H: BuildHasher,
+{
+    /// Creates a new empty `IStrInterner` with the given hasher.
+    #[inline]
+    pub fn with_hasher(hash_builder: H) -> Self {
+        Self {
+            table: Arc::default(),
+            hasher: hash_builder,
+        }
+    }
+
+    /// Returns the number of strings interned by the interner.
+    ///
+    /// # Note
+    ///
+    /// This includes strings whose last handle is being dropped concurrently.
+    pub fn len(&self) -> usize {
+        self.table.lock().len()
+    }
+
+    /// Returns `true` if the interner has no interned strings.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns a handle to the given string if it is interned.
+    pub fn get<T>(&self, string: T) -> Option<IStr>
+    where
+        T: AsRef<str>,
+    {
+        let string = string.as_ref();
+        let hash = make_hash(&self.hasher, string);
+        let entries = self.table.lock();
+        entries
+            // SAFETY: The table is locked.
+            .find(hash, |entry| unsafe { entry.as_str() } == string)
+            .and_then(|entry| entry.inner.upgrade())
+            .map(IStr)
+    }
+
+    /// Interns the given string and returns a handle to it.
+    pub fn intern<T>(&self, string: T) -> IStr
+    where
+        T: AsRef<str> + Into<Box<str>>,
+    {
+        let hash = make_hash(&self.hasher, string.as_ref());
+        let mut entries = self.table.lock();
+        let entry = entries
+            // SAFETY: The table is locked.
+            .find_mut(hash, |entry| unsafe { entry.as_str() } == string.as_ref());
+        if let Some(entry) = entry {
+            if let Some(inner) = entry.inner.upgrade() {
+                return IStr(inner)
+            }
+            // The last handle of the string is being dropped concurrently and
+            // will not find its replaced entry.
+            let inner = self.new_inner(hash, string);
+            *entry = Entry {
+                hash,
+                string: inner.string,
+                inner: Arc::downgrade(&inner),
+            };
+            return IStr(inner)
+        }
+        let inner = self.new_inner(hash, string);
+        let entry = Entry {
+            hash,
+            string: inner.string,
+            inner: Arc::downgrade(&inner),
+        };
+        entries.insert_unique(hash, entry, |entry| entry.hash);
+        IStr(inner)
+    }
+
+    /// Creates the shared contents of the handles of a new string.
+    fn new_inner<T>(&self, hash: u64, string: T) -> Arc<Inner>
+    where
+        T: Into<Box<str>>,
+    {
+        let string = Box::into_raw(string.into());
+        Arc::new(Inner {
+            hash,
+            // SAFETY: `Box::into_raw` never returns a null pointer.
+            string: unsafe { NonNull::new_unchecked(string) },
+            table: self.table.clone(),
+        })
+    }
+}
@@ -0,0 +1,273 @@
+//! String interner that layers a local interner on top of a shared base interner.
+
+use crate::{
+    backend::{
+        Backend,
+        ContiguousBackend,
+    },
+    compat::{
+        Allocator,
+        DefaultHashBuilder,
+        Global,
+    },
+    DefaultBackend,
+    DefaultSymbol,
+    InternError,
+    StringInterner,
+    Symbol,
+    TryReserveError,
+};
+use core::{
+    fmt,
+    fmt::{
+        Debug,
+        Formatter,
+    },
+    hash::BuildHasher,
+};
+
+/// String interner that layers a local interner on top of a shared base interner.
+///
+/// Strings are looked up in the base first and only strings unknown to the base
+/// are interned into the local layer. The symbols of both layers are used
+/// interchangeably: base symbols are the symbols of the base interner and local
+/// symbols are numbered after the strings of the base.
+///
+/// # Note
+///
+/// The base is borrowed immutably for the lifetime of the layered interner so
+/// that its symbols stay the same. Many short-lived layered interners may share
+/// the same large base without copying it.
+///
+/// Local symbols are numbered after the number of strings of the base which
+/// requires a [`ContiguousBackend`]. Symbols of other backends such as the
+/// [`BufferBackend`](crate::backend::BufferBackend) are byte offsets that would
+/// collide with the symbols of the base.
+///
+/// # Example
+///
+/// ```
+/// use string_interner::{LayeredInterner, StringInterner, Symbol};
+///
+/// let mut base = <StringInterner>::new();
+/// let vec = base.get_or_intern("Vec");
+/// let mut layered = LayeredInterner::new(&base);
+/// assert_eq!(layered.get_or_intern("Vec"), vec);
+/// let tiger = layered.get_or_intern("Tiger");
+/// assert_eq!(tiger.to_usize(), base.len());
+/// assert_eq!(layered.resolve(vec), Some("Vec"));
+/// assert_eq!(layered.resolve(tiger), Some("Tiger"));
+/// assert_eq!(base.get("Tiger"), None);
+/// ```
+///
+/// Bases whose backends are not contiguous cannot be layered:
+///
+/// ```compile_fail
+/// use string_interner::{backend::BufferBackend, LayeredInterner, StringInterner};
+///
+/// let base = StringInterner::<BufferBackend>::new();
+/// LayeredInterner::new(&base);
+/// ```
+pub struct LayeredInterner<
+    'base,
+    B = DefaultBackend<DefaultSymbol>,
+    H = DefaultHashBuilder,
+    A = Global,
+> where
+    B: ContiguousBackend,
+    H: BuildHasher,
+    A: Allocator,
+{
+    base: &'base StringInterner<B, H, A>,
+    /// The number of strings of the base that local symbols are numbered after.
+    base_len: usize,
+    local: StringInterner<B, H, A>,
+}
+
+impl<'base, B, H, A> Debug for LayeredInterner<'base, B, H, A>
+where
+    B: ContiguousBackend + Debug,
+    <B as Backend>::Symbol: Symbol + Debug,
+    H: BuildHasher,
+    A: Allocator,
+{
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("LayeredInterner")
+            .field("base_len", &self.base_len)
+            .field("local", &self.local)
+            .finish()
+    }
+}
+
+impl<'base, B, H, A> LayeredInterner<'base, B, H, A>
+where
+    B: ContiguousBackend + Default,
+    <B as Backend>::Symbol: Symbol,
+    H: BuildHasher + Default,
+    A: Allocator + Default,
+{
+    /// Creates a new layered interner with an empty local layer on top of `base`.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn new(base: &'base StringInterner<B, H, A>) -> Self {
+        Self::with_local(base, StringInterner::with_hasher(H::default()))
+    }
+}
+
+impl<'base, B, H, A> LayeredInterner<'base, B, H, A>
+where
+    B: ContiguousBackend,
+    <B as Backend>::Symbol: Symbol,
+    H: BuildHasher,
+    A: Allocator,
+{
+    /// Creates a new layered interner with the given local layer on top of `base`.
+    ///
+    /// # Note
+    ///
+    /// Strings of `local` that are also interned by `base` are still resolved
+    /// but never returned by [`LayeredInterner::get`] since the base takes
+    /// precedence. Use an empty local layer to avoid this.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn with_local(
+        base: &'base StringInterner<B, H, A>,
+        local: StringInterner<B, H, A>,
+    ) -> Self {
+        Self {
+            base,
+            base_len: base.len(),
+            local,
+        }
+    }
+
+    /// Returns the base interner.
+    #[inline]
+    pub fn base(&self) -> &'base StringInterner<B, H, A> {
+        self.base
+    }
+
+    /// Returns the local layer of the interner.
+    ///
+    /// # Note
+    ///
+    /// The symbols of the local layer are not offset by the length of the base.
+    #[inline]
+    pub fn local(&self) -> &StringInterner<B, H, A> {
+        &self.local
+    }
+
+    /// Consumes the layered interner and returns its local layer.
+    ///
+    /// # Note
+    ///
+    /// The symbols of the local layer are not offset by the length of the base.
+    #[inline]
+    pub fn into_local(self) -> StringInterner<B, H, A> {
+        self.local
+    }
+
+    /// Returns the number of strings of the base and the local layer.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.base_len + self.local.len()
+    }
+
+    /// Returns `true` if neither the base nor the local layer has strings.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns `true` if the symbol refers to a string of the local layer.
+    #[inline]
+    pub fn is_local(&self, symbol: <B as Backend>::Symbol) -> bool {
+        symbol.to_usize() >= self.base_len
+    }
+
+    /// Converts a symbol of the local layer into a layered symbol.
+    #[inline]
+    fn layer_symbol(&self, symbol: <B as Backend>::Symbol) -> <B as Backend>::Symbol {
+        <B as Backend>::Symbol::try_from_usize(self.base_len + symbol.to_usize())
+            .expect("encountered invalid symbol")
+    }
+
+    /// Returns the symbol for the given string if any.
+    ///
+    /// Looks up the base before the local layer.
+    #[inline]
+    pub fn get<T>(&self, string: T) -> Option<<B as Backend>::Symbol>
+    where
+        T: AsRef<str>,
+    {
+        let string = string.as_ref();
+        self.base.get(string).or_else(|| {
+            self.local
+                .get(string)
+                .map(|symbol| self.layer_symbol(symbol))
+        })
+    }
+
+    /// Interns the given string into the local layer unless the base has it.
+    ///
+    /// Returns the symbol of the string in either layer.
+    ///
+    /// # Panics
+    ///
+    /// If the layered symbol of a new local string exceeds the maximum symbol
+    /// of the chosen symbol type or if interning into the local layer fails,
+    /// see [`StringInterner::get_or_intern`]. Use
+    /// [`LayeredInterner::try_get_or_intern`] to handle these cases.
+    #[inline]
+    pub fn get_or_intern<T>(&mut self, string: T) -> <B as Backend>::Symbol
+    where
+        T: AsRef<str>,
+    {
+        let string = string.as_ref();
+        if let Some(symbol) = self.base.get(string) {
+            return symbol
+        }
+        let symbol = self.local.get_or_intern(string);
+        self.layer_symbol(symbol)
+    }
+
+    /// Tries to intern the given string into the local layer unless the base has it.
+    ///
+    /// Returns the symbol of the string in either layer.
+    ///
+    /// # Errors
+    ///
+    /// - If the layered symbol of a new local string exceeds the maximum symbol
+    ///   of the chosen symbol type.
+    /// - If interning into the local layer fails, see
+    ///   [`StringInterner::try_get_or_intern`].
+    ///
+    /// The layered interner is left unchanged in these cases.
+    pub fn try_get_or_intern<T>(
+        &mut self,
+        string: T,
+    ) -> Result<<B as Backend>::Symbol, InternError>
+    where
+        T: AsRef<str>,
+    {
+        let string = string.as_ref();
+        if let Some(symbol) = self.get(string) {
+            return Ok(symbol)
+        }
+        // Checked up front so that the local layer is left unchanged.
+        if <B as Backend>::Symbol::try_from_usize(self.len()).is_none() {
+            return Err(TryReserveError::CapacityOverflow.into())
+        }
+        let symbol = self.local.try_get_or_intern(string)?;
+        Ok(self.layer_symbol(symbol))
+    }
+
+    /// Returns the string for the given symbol of either layer if any.
+    #[inline]
+    pub fn resolve(&self, symbol: <B as Backend>::Symbol) -> Option<&str> {
+        let index = symbol.to_usize();
+        if index < self.base_len {
+            return self.base.resolve(symbol)
+        }
+        let local = <B as Backend>::Symbol::try_from_usize(index - self.base_len)?;
+        self.local.resolve(local)
+    }
+}
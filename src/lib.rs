@@ -117,6 +117,19 @@
 //! However, all this is at the costs of a less efficient resolution of symbols.
 //! Note that the symbols generated by the `BufferBackend` are not contiguous.
 //!
+//! ### Borrowed Backend
+//!
+//! The `BorrowedBackend` does not copy strings that outlive the string interner,
+//! e.g. identifiers of a source file that is kept alive anyways. Instead it
+//! references them and resolves symbols to strings that are not tied to the
+//! borrow of the string interner.
+//!
+//! ### Source Backend
+//!
+//! The `SourceBackend` is similar to the `BorrowedBackend` for strings that are
+//! slices of reference-counted `Arc<str>` source buffers. It stores spans into
+//! registered source buffers and copies all other strings into an owned buffer.
+//!
 //! ### Simple Backend
 //!
 //! Never use this one for real use cases!
@@ -127,18 +140,107 @@ extern crate alloc;
 #[cfg(feature = "serde-1")]
 mod serde_impl;
 
+#[cfg(feature = "std")]
+mod append;
 pub mod backend;
+mod bounded;
 mod compat;
+mod delta;
+#[cfg(not(feature = "allocator-api"))]
+mod global_alloc;
+pub mod intern_map;
 mod interner;
+#[cfg(feature = "std")]
+mod istr;
+mod layered;
+mod limits;
+#[cfg(feature = "backends")]
+mod local;
+mod lines;
+mod memory;
+mod namespaced;
+mod persistent;
+mod reserve;
+#[cfg(feature = "serde-1")]
+pub mod serde;
+#[cfg(all(feature = "shared-memory", target_os = "linux"))]
+pub mod shared;
 pub mod symbol;
+#[cfg(feature = "std")]
+mod tls;
+mod translate;
+mod weak;
 
 #[doc(inline)]
 pub use self::{
     backend::DefaultBackend,
-    compat::DefaultHashBuilder,
+    bounded::{
+        BoundedInterner,
+        BoundedSymbol,
+    },
+    compat::{
+        Allocator,
+        DefaultHashBuilder,
+        Global,
+    },
+    delta::{
+        Delta,
+        DeltaError,
+        DeltaIter,
+        Watermark,
+    },
+    intern_map::InternMap,
     interner::StringInterner,
+    layered::LayeredInterner,
+    limits::{
+        InternError,
+        InternerLimits,
+        RemainingCapacity,
+    },
+    memory::{
+        MemoryStats,
+        MemoryUsage,
+    },
+    namespaced::{
+        Namespace,
+        NamespacedInterner,
+        NamespacedSymbol,
+    },
+    persistent::PersistentInterner,
+    reserve::TryReserveError,
     symbol::{
         DefaultSymbol,
         Symbol,
     },
+    translate::SymbolTranslator,
+    weak::WeakInterner,
+};
+
+#[cfg(feature = "std")]
+#[doc(inline)]
+pub use self::append::{
+    AppendInterner,
+    AppendReader,
+};
+
+#[cfg(feature = "std")]
+#[doc(inline)]
+pub use self::istr::{
+    IStr,
+    IStrInterner,
+};
+
+#[cfg(feature = "backends")]
+#[doc(inline)]
+pub use self::local::LocalInterner;
+
+#[cfg(feature = "std")]
+#[doc(inline)]
+pub use self::tls::ThreadLocalInterner;
+
+#[cfg(feature = "std")]
+#[doc(inline)]
+pub use self::lines::{
+    LineFormat,
+    ReadLinesError,
 };
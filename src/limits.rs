@@ -0,0 +1,181 @@
+use crate::TryReserveError;
+use core::fmt;
+
+/// Limits on the strings a [`StringInterner`] accepts.
+///
+/// Protects string interners that intern untrusted input from exhausting memory.
+/// All limits are unlimited by default.
+///
+/// Only the fallible methods such as [`StringInterner::try_get_or_intern`] and
+/// [`StringInterner::try_extend`] report exceeded limits as errors. The methods
+/// that intern strings infallibly, such as [`StringInterner::get_or_intern`],
+/// [`StringInterner::get_or_intern_static`] and the [`Extend`] implementation,
+/// panic instead.
+///
+/// # Example
+///
+/// ```
+/// use string_interner::{InternError, InternerLimits, StringInterner};
+///
+/// let mut interner = <StringInterner>::new();
+/// interner.set_limits(InternerLimits {
+///     max_strings: 2,
+///     max_string_len: 5,
+///     ..InternerLimits::default()
+/// });
+/// assert!(interner.try_get_or_intern("hello").is_ok());
+/// assert!(matches!(
+///     interner.try_get_or_intern("hello world"),
+///     Err(InternError::StringTooLong { .. })
+/// ));
+/// assert!(interner.try_get_or_intern("world").is_ok());
+/// assert_eq!(interner.remaining_capacity().strings, 0);
+/// assert!(matches!(
+///     interner.try_get_or_intern("tiger"),
+///     Err(InternError::StringLimitExceeded { .. })
+/// ));
+/// ```
+///
+/// [`StringInterner`]: crate::StringInterner
+/// [`StringInterner::try_get_or_intern`]: crate::StringInterner::try_get_or_intern
+/// [`StringInterner::try_extend`]: crate::StringInterner::try_extend
+/// [`StringInterner::get_or_intern`]: crate::StringInterner::get_or_intern
+/// [`StringInterner::get_or_intern_static`]: crate::StringInterner::get_or_intern_static
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct InternerLimits {
+    /// The maximum number of interned strings.
+    pub max_strings: usize,
+    /// The maximum sum of the lengths in bytes of all interned strings.
+    pub max_total_bytes: usize,
+    /// The maximum length in bytes of a single interned string.
+    pub max_string_len: usize,
+}
+
+impl InternerLimits {
+    /// Limits that accept any string.
+    pub const UNLIMITED: Self = Self {
+        max_strings: usize::MAX,
+        max_total_bytes: usize::MAX,
+        max_string_len: usize::MAX,
+    };
+
+    /// Returns `Ok` if a string of `len` bytes may be interned in addition to
+    /// `len_strings` strings with a total of `total_bytes` bytes.
+    pub(crate) fn check(
+        &self,
+        len_strings: usize,
+        total_bytes: usize,
+        len: usize,
+    ) -> Result<(), InternError> {
+        if len > self.max_string_len {
+            return Err(InternError::StringTooLong {
+                len,
+                max_string_len: self.max_string_len,
+            })
+        }
+        if len_strings >= self.max_strings {
+            return Err(InternError::StringLimitExceeded {
+                max_strings: self.max_strings,
+            })
+        }
+        if len > self.max_total_bytes.saturating_sub(total_bytes) {
+            return Err(InternError::ByteLimitExceeded {
+                max_total_bytes: self.max_total_bytes,
+            })
+        }
+        Ok(())
+    }
+}
+
+impl Default for InternerLimits {
+    #[inline]
+    fn default() -> Self {
+        Self::UNLIMITED
+    }
+}
+
+/// The number of strings and bytes that may still be interned until the
+/// [`InternerLimits`] of a [`StringInterner`] are reached.
+///
+/// [`StringInterner`]: crate::StringInterner
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct RemainingCapacity {
+    /// The number of strings that may still be interned.
+    pub strings: usize,
+    /// The number of bytes that may still be interned in total.
+    pub bytes: usize,
+}
+
+/// Errors that may occur when interning a string via
+/// [`StringInterner::try_get_or_intern`].
+///
+/// The string interner is left unchanged when this error is returned.
+///
+/// [`StringInterner::try_get_or_intern`]: crate::StringInterner::try_get_or_intern
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InternError {
+    /// Interning the string would exceed [`InternerLimits::max_strings`].
+    StringLimitExceeded {
+        /// The maximum number of interned strings.
+        max_strings: usize,
+    },
+    /// Interning the string would exceed [`InternerLimits::max_total_bytes`].
+    ByteLimitExceeded {
+        /// The maximum sum of the lengths of all interned strings.
+        max_total_bytes: usize,
+    },
+    /// The string is longer than [`InternerLimits::max_string_len`].
+    StringTooLong {
+        /// The length of the rejected string.
+        len: usize,
+        /// The maximum length of a single interned string.
+        max_string_len: usize,
+    },
+    /// The memory required to intern the string could not be allocated.
+    Alloc(TryReserveError),
+}
+
+impl From<TryReserveError> for InternError {
+    #[inline]
+    fn from(error: TryReserveError) -> Self {
+        Self::Alloc(error)
+    }
+}
+
+impl fmt::Display for InternError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::StringLimitExceeded { max_strings } => {
+                write!(f, "exceeded the limit of {} interned strings", max_strings)
+            }
+            Self::ByteLimitExceeded { max_total_bytes } => {
+                write!(
+                    f,
+                    "exceeded the limit of {} interned bytes",
+                    max_total_bytes
+                )
+            }
+            Self::StringTooLong {
+                len,
+                max_string_len,
+            } => {
+                write!(
+                    f,
+                    "string of {} bytes exceeds the limit of {} bytes",
+                    len, max_string_len
+                )
+            }
+            Self::Alloc(error) => error.fmt(f),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for InternError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Alloc(error) => Some(error),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,351 @@
+//! Plain-text import and export of string interners.
+//!
+//! Every interned string is written on its own line so that symbol tables
+//! can be inspected and edited with ordinary line-based tools. Characters
+//! that would break the line structure are escaped:
+//!
+//! | Character        | Escape     |
+//! |:-----------------|:-----------|
+//! | `\`              | `\\`       |
+//! | line feed        | `\n`       |
+//! | carriage return  | `\r`       |
+//! | tab              | `\t`       |
+//! | other control    | `\u{7f}`   |
+
+#![cfg(feature = "std")]
+
+use crate::{
+    backend::Backend,
+    compat::Allocator,
+    InternError,
+    InternerLimits,
+    StringInterner,
+    Symbol,
+};
+use core::{
+    fmt,
+    hash::BuildHasher,
+};
+use std::io;
+
+/// The format of the lines written by [`StringInterner::write_lines`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum LineFormat {
+    /// One escaped string per line in symbol order.
+    Strings,
+    /// One `symbol<TAB>string` pair per line in symbol order.
+    ///
+    /// The symbol is written as its decimal `usize` value.
+    Symbols,
+}
+
+/// Errors that may occur when reading the lines of a string interner.
+#[derive(Debug)]
+pub enum ReadLinesError {
+    /// Reading from the underlying reader failed.
+    Io(io::Error),
+    /// The line contains an invalid escape sequence.
+    InvalidEscape {
+        /// The 1-based number of the offending line.
+        line: usize,
+    },
+    /// The line does not start with a valid symbol followed by a tab.
+    InvalidSymbol {
+        /// The 1-based number of the offending line.
+        line: usize,
+    },
+    /// The symbol or string of the line has already been recorded by another line.
+    Conflict {
+        /// The 1-based number of the offending line.
+        line: usize,
+    },
+    /// The recorded symbol differs from the one the interner assigns to the string.
+    OutOfOrder {
+        /// The 1-based number of the offending line.
+        line: usize,
+        /// The symbol recorded on the line.
+        expected: usize,
+        /// The symbol assigned by the interner.
+        actual: usize,
+    },
+    /// The string of the line cannot be interned.
+    Intern {
+        /// The 1-based number of the offending line.
+        line: usize,
+        /// The reason why the string cannot be interned.
+        error: InternError,
+    },
+}
+
+impl fmt::Display for ReadLinesError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Io(error) => error.fmt(f),
+            Self::InvalidEscape { line } => {
+                write!(f, "invalid escape sequence on line {}", line)
+            }
+            Self::InvalidSymbol { line } => write!(f, "invalid symbol on line {}", line),
+            Self::Conflict { line } => {
+                write!(f, "conflicting symbol or string on line {}", line)
+            }
+            Self::OutOfOrder {
+                line,
+                expected,
+                actual,
+            } => {
+                write!(
+                    f,
+                    "symbol {} on line {} is out of order, the interner assigned {}",
+                    expected, line, actual
+                )
+            }
+            Self::Intern { line, error } => {
+                write!(f, "cannot intern the string on line {}: {}", line, error)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ReadLinesError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(error) => Some(error),
+            Self::Intern { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for ReadLinesError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+impl<B, H, A> StringInterner<B, H, A>
+where
+    B: Backend,
+    <B as Backend>::Symbol: Symbol,
+    for<'a> &'a B: IntoIterator<Item = (<B as Backend>::Symbol, &'a str)>,
+    H: BuildHasher,
+    A: Allocator,
+{
+    /// Writes all interned strings line by line in the given format.
+    ///
+    /// See the [`LineFormat`] docs for the available formats.
+    ///
+    /// # Errors
+    ///
+    /// If writing to the writer fails.
+    pub fn write_lines<W>(&self, mut writer: W, format: LineFormat) -> io::Result<()>
+    where
+        W: io::Write,
+    {
+        let mut line = String::new();
+        for (symbol, string) in self {
+            line.clear();
+            if let LineFormat::Symbols = format {
+                line.push_str(&symbol.to_usize().to_string());
+                line.push('\t');
+            }
+            escape_into(string, &mut line);
+            line.push('\n');
+            writer.write_all(line.as_bytes())?;
+        }
+        writer.flush()
+    }
+}
+
+impl<B, H, A> StringInterner<B, H, A>
+where
+    B: Backend + Default,
+    <B as Backend>::Symbol: Symbol,
+    H: BuildHasher + Default,
+    A: Allocator + Default,
+{
+    /// Reads a string interner from lines written by [`StringInterner::write_lines`].
+    ///
+    /// - With [`LineFormat::Strings`] the strings are interned in order and
+    ///   duplicate strings are collapsed.
+    /// - With [`LineFormat::Symbols`] every string is guaranteed to be
+    ///   resolved by exactly its recorded symbol. Exact duplicate lines are
+    ///   accepted.
+    ///
+    /// # Errors
+    ///
+    /// - If reading from the reader fails.
+    /// - If a line contains an invalid escape sequence or symbol.
+    /// - If a symbol or string is recorded with different counterparts.
+    /// - If a recorded symbol differs from the symbol the interner assigns.
+    /// - If a string cannot be interned, e.g. since the symbols are exhausted.
+    #[inline]
+    pub fn read_lines<R>(reader: R, format: LineFormat) -> Result<Self, ReadLinesError>
+    where
+        R: io::BufRead,
+    {
+        Self::read_lines_with_limits(reader, format, InternerLimits::UNLIMITED)
+    }
+
+    /// Reads a string interner with the given limits from lines written by
+    /// [`StringInterner::write_lines`].
+    ///
+    /// Use this to read lines of untrusted origin. See [`StringInterner::read_lines`]
+    /// for more information.
+    ///
+    /// # Errors
+    ///
+    /// - If [`StringInterner::read_lines`] fails.
+    /// - If interning the strings exceeds the limits.
+    pub fn read_lines_with_limits<R>(
+        reader: R,
+        format: LineFormat,
+        limits: InternerLimits,
+    ) -> Result<Self, ReadLinesError>
+    where
+        R: io::BufRead,
+    {
+        let mut interner = Self::new();
+        interner.set_limits(limits);
+        let mut string = String::new();
+        for (index, line) in reader.lines().enumerate() {
+            let line_number = index + 1;
+            let line = line?;
+            let (recorded, escaped) = match format {
+                LineFormat::Strings => (None, line.as_str()),
+                LineFormat::Symbols => {
+                    let (symbol, escaped) = line
+                        .split_once('\t')
+                        .and_then(|(symbol, escaped)| {
+                            let symbol = symbol.parse::<usize>().ok()?;
+                            let symbol = <B as Backend>::Symbol::try_from_usize(symbol)?;
+                            Some((symbol, escaped))
+                        })
+                        .ok_or(ReadLinesError::InvalidSymbol { line: line_number })?;
+                    (Some(symbol), escaped)
+                }
+            };
+            string.clear();
+            unescape_into(escaped, &mut string)
+                .ok_or(ReadLinesError::InvalidEscape { line: line_number })?;
+            let intern_error = |error| {
+                ReadLinesError::Intern {
+                    line: line_number,
+                    error,
+                }
+            };
+            let expected = match recorded {
+                Some(expected) => expected,
+                None => {
+                    interner.try_get_or_intern(&string).map_err(intern_error)?;
+                    continue
+                }
+            };
+            if let Some(existing) = interner.get(&string) {
+                if existing != expected {
+                    return Err(ReadLinesError::Conflict { line: line_number })
+                }
+                continue
+            }
+            if interner.resolve(expected).is_some() {
+                return Err(ReadLinesError::Conflict { line: line_number })
+            }
+            let actual = interner.try_get_or_intern(&string).map_err(intern_error)?;
+            if actual != expected {
+                return Err(ReadLinesError::OutOfOrder {
+                    line: line_number,
+                    expected: expected.to_usize(),
+                    actual: actual.to_usize(),
+                })
+            }
+        }
+        Ok(interner)
+    }
+}
+
+/// Appends the escaped string to the buffer.
+fn escape_into(string: &str, buffer: &mut String) {
+    for c in string.chars() {
+        match c {
+            '\\' => buffer.push_str("\\\\"),
+            '\n' => buffer.push_str("\\n"),
+            '\r' => buffer.push_str("\\r"),
+            '\t' => buffer.push_str("\\t"),
+            c if c.is_control() => buffer.push_str(&format!("\\u{{{:x}}}", c as u32)),
+            c => buffer.push(c),
+        }
+    }
+}
+
+/// Appends the unescaped string to the buffer.
+///
+/// Returns `None` if the string contains an invalid escape sequence.
+fn unescape_into(escaped: &str, buffer: &mut String) -> Option<()> {
+    let mut chars = escaped.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            buffer.push(c);
+            continue
+        }
+        let unescaped = match chars.next()? {
+            '\\' => '\\',
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            'u' => {
+                let rest = chars.as_str().strip_prefix('{')?;
+                let (hex, rest) = rest.split_once('}')?;
+                let code = u32::from_str_radix(hex, 16).ok()?;
+                chars = rest.chars();
+                char::from_u32(code)?
+            }
+            _ => return None,
+        };
+        buffer.push(unescaped);
+    }
+    Some(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        escape_into,
+        unescape_into,
+    };
+
+    fn escape(string: &str) -> String {
+        let mut buffer = String::new();
+        escape_into(string, &mut buffer);
+        buffer
+    }
+
+    fn unescape(escaped: &str) -> Option<String> {
+        let mut buffer = String::new();
+        unescape_into(escaped, &mut buffer).map(|()| buffer)
+    }
+
+    #[test]
+    fn escape_works() {
+        assert_eq!(escape("plain"), "plain");
+        assert_eq!(escape("a\\b"), "a\\\\b");
+        assert_eq!(escape("a\nb\r\tc"), "a\\nb\\r\\tc");
+        assert_eq!(escape("\0\u{7f}"), "\\u{0}\\u{7f}");
+        assert_eq!(escape("ünï"), "ünï");
+    }
+
+    #[test]
+    fn unescape_works() {
+        for string in ["", "plain", "a\\b", "a\nb\r\tc", "\0\u{7f}\u{85}", "ünï\\n"] {
+            assert_eq!(unescape(&escape(string)).as_deref(), Some(string));
+        }
+    }
+
+    #[test]
+    fn unescape_invalid_fails() {
+        assert_eq!(unescape("\\"), None);
+        assert_eq!(unescape("\\x"), None);
+        assert_eq!(unescape("\\u41"), None);
+        assert_eq!(unescape("\\u{41"), None);
+        assert_eq!(unescape("\\u{zz}"), None);
+        assert_eq!(unescape("\\u{d800}"), None);
+    }
+}
@@ -0,0 +1,189 @@
+//! String interner that interns through shared references within a single thread.
+//!
+//! The [`LocalInterner`] hands out `&str` references to its interned strings
+//! that remain valid while it keeps interning more strings.
+
+use crate::{
+    backend::BucketBackend,
+    compat::DefaultHashBuilder,
+    DefaultSymbol,
+    StringInterner,
+    Symbol,
+};
+use core::{
+    cell::RefCell,
+    fmt,
+    hash::BuildHasher,
+};
+
+/// String interner that interns through shared references.
+///
+/// Interning and resolving only require a shared reference and return
+/// references to the interned strings that live as long as the interner.
+/// This allows to keep using interned strings while interning more strings.
+///
+/// # Note
+///
+/// The interner is based on the [`BucketBackend`] that never moves its
+/// interned strings in memory. The interner uses interior mutability and
+/// thus is not `Sync`.
+///
+/// # Example
+///
+/// ```
+/// use string_interner::LocalInterner;
+///
+/// let interner = <LocalInterner>::new();
+/// let tiger = interner.intern("Tiger");
+/// let horse = interner.intern("Horse");
+/// let symbol = interner.get("Tiger").unwrap();
+/// assert_eq!(tiger, "Tiger");
+/// assert_eq!(horse, "Horse");
+/// assert_eq!(interner.resolve(symbol), Some("Tiger"));
+/// assert_eq!(interner.len(), 2);
+/// ```
+pub struct LocalInterner<S = DefaultSymbol, H = DefaultHashBuilder>
+where
+    S: Symbol,
+    H: BuildHasher,
+{
+    interner: RefCell<StringInterner<BucketBackend<S>, H>>,
+}
+
+impl<S, H> fmt::Debug for LocalInterner<S, H>
+where
+    S: Symbol + fmt::Debug,
+    H: BuildHasher,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LocalInterner")
+            .field("interner", &self.interner)
+            .finish()
+    }
+}
+
+impl<S, H> Default for LocalInterner<S, H>
+where
+    S: Symbol,
+    H: BuildHasher + Default,
+{
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S, H> LocalInterner<S, H>
+where
+    S: Symbol,
+    H: BuildHasher + Default,
+{
+    /// Creates a new empty `LocalInterner`.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn new() -> Self {
+        Self::with_hasher(H::default())
+    }
+
+    /// Creates a new `LocalInterner` with the given initial capacity.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn with_capacity(cap: usize) -> Self {
+        Self {
+            interner: RefCell::new(StringInterner::with_capacity(cap)),
+        }
+    }
+}
+
+impl<S, H> LocalInterner<S, H>
+where
+    S: Symbol,
+    H: BuildHasher,
+{
+    /// Creates a new empty `LocalInterner` with the given hasher.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn with_hasher(hash_builder: H) -> Self {
+        Self {
+            interner: RefCell::new(StringInterner::with_hasher(hash_builder)),
+        }
+    }
+
+    /// Returns the number of strings interned by the interner.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.interner.borrow().len()
+    }
+
+    /// Returns `true` if the interner has no interned strings.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the symbol for the given string if any.
+    #[inline]
+    pub fn get<T>(&self, string: T) -> Option<S>
+    where
+        T: AsRef<str>,
+    {
+        self.interner.borrow().get(string)
+    }
+
+    /// Interns the given string and returns its symbol.
+    ///
+    /// # Panics
+    ///
+    /// If the interner already interns the maximum number of strings possible
+    /// by the chosen symbol type.
+    #[inline]
+    pub fn get_or_intern<T>(&self, string: T) -> S
+    where
+        T: AsRef<str>,
+    {
+        self.interner.borrow_mut().get_or_intern(string)
+    }
+
+    /// Interns the given `'static` string and returns its symbol.
+    ///
+    /// Does not copy the string into the interner.
+    ///
+    /// # Panics
+    ///
+    /// If the interner already interns the maximum number of strings possible
+    /// by the chosen symbol type.
+    #[inline]
+    pub fn get_or_intern_static(&self, string: &'static str) -> S {
+        self.interner.borrow_mut().get_or_intern_static(string)
+    }
+
+    /// Interns the given string and returns a reference to the interned string.
+    ///
+    /// # Panics
+    ///
+    /// If the interner already interns the maximum number of strings possible
+    /// by the chosen symbol type.
+    pub fn intern<T>(&self, string: T) -> &str
+    where
+        T: AsRef<str>,
+    {
+        let symbol = self.get_or_intern(string);
+        self.resolve(symbol)
+            .expect("encountered missing string for interned symbol")
+    }
+
+    /// Returns the string for the given symbol if any.
+    #[inline]
+    pub fn resolve(&self, symbol: S) -> Option<&str> {
+        let interner = self.interner.borrow();
+        let string: *const str = interner.resolve(symbol)?;
+        // SAFETY: The bucket backend never moves or frees its interned strings
+        //         before it is dropped since its buckets are never reallocated.
+        //         The interner never shrinks or clears the backend, so the
+        //         string lives as long as the interner.
+        Some(unsafe { &*string })
+    }
+
+    /// Returns the underlying string interner.
+    #[inline]
+    pub fn into_inner(self) -> StringInterner<BucketBackend<S>, H> {
+        self.interner.into_inner()
+    }
+}
@@ -0,0 +1,177 @@
+//! Memory usage statistics of string interners.
+
+use crate::{
+    backend::Backend,
+    compat::{
+        Allocator,
+        String,
+        Vec,
+        VecIn,
+    },
+    StringInterner,
+    Symbol,
+};
+use core::{
+    hash::BuildHasher,
+    mem,
+    ops::{
+        Add,
+        AddAssign,
+    },
+};
+
+/// The heap memory used by a part of a string interner.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct MemoryUsage {
+    /// The number of allocated heap bytes, i.e. the capacity in bytes.
+    pub heap_bytes: usize,
+    /// The number of allocated heap bytes that are in use.
+    pub used_bytes: usize,
+}
+
+impl MemoryUsage {
+    /// Returns the heap memory used by the buffer of the vector.
+    ///
+    /// Does not include the heap memory owned by the elements.
+    #[inline]
+    pub fn of_vec<T>(vec: &Vec<T>) -> Self {
+        Self {
+            heap_bytes: vec.capacity() * mem::size_of::<T>(),
+            used_bytes: vec.len() * mem::size_of::<T>(),
+        }
+    }
+
+    /// Returns the heap memory used by the buffer of the allocator aware vector.
+    ///
+    /// Does not include the heap memory owned by the elements.
+    #[cfg(feature = "allocator-api")]
+    #[inline]
+    pub fn of_vec_in<T, A>(vec: &VecIn<T, A>) -> Self
+    where
+        A: Allocator,
+    {
+        Self {
+            heap_bytes: vec.capacity() * mem::size_of::<T>(),
+            used_bytes: vec.len() * mem::size_of::<T>(),
+        }
+    }
+
+    /// Returns the heap memory used by the buffer of the vector standing in for
+    /// the allocator aware vector.
+    #[cfg(not(feature = "allocator-api"))]
+    #[inline]
+    pub(crate) fn of_vec_in<T, A>(vec: &VecIn<T, A>) -> Self
+    where
+        A: Allocator,
+    {
+        Self::of_vec(vec)
+    }
+
+    /// Returns the heap memory used by the buffer of the string.
+    #[inline]
+    pub fn of_string(string: &String) -> Self {
+        Self {
+            heap_bytes: string.capacity(),
+            used_bytes: string.len(),
+        }
+    }
+
+    /// Returns the number of allocated heap bytes that are not in use.
+    #[inline]
+    pub fn slack_bytes(&self) -> usize {
+        self.heap_bytes - self.used_bytes
+    }
+}
+
+impl Add for MemoryUsage {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, rhs: Self) -> Self {
+        Self {
+            heap_bytes: self.heap_bytes + rhs.heap_bytes,
+            used_bytes: self.used_bytes + rhs.used_bytes,
+        }
+    }
+}
+
+impl AddAssign for MemoryUsage {
+    #[inline]
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+/// Memory usage statistics of a string interner.
+///
+/// Returned by [`StringInterner::memory_stats`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct MemoryStats {
+    /// The heap memory used by the backend as reported by [`Backend::memory_usage`].
+    ///
+    /// `None` if the backend does not report its heap memory.
+    pub backend: Option<MemoryUsage>,
+    /// The estimated heap memory used by the deduplication table.
+    ///
+    /// Estimated from the capacity of the table and the size of its entries.
+    /// The allocation of the table is larger since it keeps spare buckets and
+    /// control bytes for every bucket.
+    pub dedup: MemoryUsage,
+    /// The number of interned strings.
+    pub len_strings: usize,
+    /// The total number of bytes of all interned strings.
+    pub string_bytes: usize,
+    /// Histogram of the lengths of the interned strings.
+    ///
+    /// `length_histogram[0]` counts the empty strings and `length_histogram[i]`
+    /// counts the strings with a length within `2^(i-1)..2^i` bytes.
+    pub length_histogram: Vec<usize>,
+}
+
+impl MemoryStats {
+    /// Returns the heap memory used by the backend and the deduplication table.
+    ///
+    /// Returns `None` if the backend does not report its heap memory.
+    #[inline]
+    pub fn total(&self) -> Option<MemoryUsage> {
+        Some(self.backend? + self.dedup)
+    }
+}
+
+impl<B, H, A> StringInterner<B, H, A>
+where
+    B: Backend,
+    <B as Backend>::Symbol: Symbol,
+    for<'a> &'a B: IntoIterator<Item = (<B as Backend>::Symbol, &'a str)>,
+    H: BuildHasher,
+    A: Allocator,
+{
+    /// Returns memory usage statistics of the string interner.
+    ///
+    /// # Note
+    ///
+    /// This iterates over all interned strings.
+    pub fn memory_stats(&self) -> MemoryStats {
+        let mut string_bytes = 0;
+        let mut length_histogram = Vec::new();
+        for (_symbol, string) in self {
+            string_bytes += string.len();
+            let bucket = match string.len() {
+                0 => 0,
+                len => len.ilog2() as usize + 1,
+            };
+            if length_histogram.len() <= bucket {
+                length_histogram.resize(bucket + 1, 0);
+            }
+            length_histogram[bucket] += 1;
+        }
+        MemoryStats {
+            backend: self.backend().memory_usage(),
+            dedup: self.dedup_memory_usage(),
+            len_strings: self.len(),
+            string_bytes,
+            length_histogram,
+        }
+    }
+}
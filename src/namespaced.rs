@@ -0,0 +1,447 @@
+//! String interner whose symbols are typed by the namespace they were interned in.
+
+use crate::{
+    backend::Backend,
+    compat::{
+        Allocator,
+        DefaultHashBuilder,
+        Global,
+        HashTableIn,
+    },
+    interner::make_hash,
+    DefaultBackend,
+    DefaultSymbol,
+    InternError,
+    StringInterner,
+    Symbol,
+    TryReserveError,
+};
+use core::{
+    cmp::Ordering,
+    fmt,
+    fmt::{
+        Debug,
+        Formatter,
+    },
+    hash::{
+        BuildHasher,
+        Hash,
+        Hasher,
+    },
+    marker::PhantomData,
+};
+
+/// Marker types of the namespaces of a [`NamespacedInterner`] with namespaces `N`.
+///
+/// Every namespace is a type so that symbols of different namespaces have
+/// different types and cannot be mixed up.
+///
+/// # Example
+///
+/// ```
+/// use string_interner::Namespace;
+///
+/// #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+/// enum Kind {
+///     Ident,
+///     Label,
+/// }
+///
+/// struct Ident;
+///
+/// impl Namespace<Kind> for Ident {
+///     const NAMESPACE: Kind = Kind::Ident;
+/// }
+/// ```
+pub trait Namespace<N> {
+    /// The namespace the marker type stands for.
+    const NAMESPACE: N;
+}
+
+/// The symbol of a string interned into the namespace `M` of a [`NamespacedInterner`].
+///
+/// Symbols of the same string in different namespaces have different types
+/// and only resolve within their own namespace.
+pub struct NamespacedSymbol<M, S = DefaultSymbol> {
+    symbol: S,
+    marker: PhantomData<fn() -> M>,
+}
+
+impl<M, S> NamespacedSymbol<M, S>
+where
+    S: Symbol,
+{
+    /// Returns the namespace of the symbol.
+    #[inline]
+    pub fn namespace<N>(self) -> N
+    where
+        M: Namespace<N>,
+    {
+        M::NAMESPACE
+    }
+
+    /// Returns the symbol of the string in the shared storage.
+    ///
+    /// # Note
+    ///
+    /// Storage symbols of the same string are equal across namespaces.
+    #[inline]
+    pub fn storage_symbol(self) -> S {
+        self.symbol
+    }
+}
+
+// The trait implementations are written by hand since derives would require
+// the marker types of the namespaces to implement the traits as well.
+
+impl<M, S> Debug for NamespacedSymbol<M, S>
+where
+    S: Debug,
+{
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("NamespacedSymbol")
+            .field("namespace", &core::any::type_name::<M>())
+            .field("symbol", &self.symbol)
+            .finish()
+    }
+}
+
+impl<M, S> Copy for NamespacedSymbol<M, S> where S: Copy {}
+
+impl<M, S> Clone for NamespacedSymbol<M, S>
+where
+    S: Copy,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<M, S> PartialEq for NamespacedSymbol<M, S>
+where
+    S: PartialEq,
+{
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.symbol == other.symbol
+    }
+}
+
+impl<M, S> Eq for NamespacedSymbol<M, S> where S: Eq {}
+
+impl<M, S> PartialOrd for NamespacedSymbol<M, S>
+where
+    S: Ord,
+{
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<M, S> Ord for NamespacedSymbol<M, S>
+where
+    S: Ord,
+{
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.symbol.cmp(&other.symbol)
+    }
+}
+
+impl<M, S> Hash for NamespacedSymbol<M, S>
+where
+    S: Hash,
+{
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.symbol.hash(state)
+    }
+}
+
+/// String interner with distinct symbol spaces that share one storage.
+///
+/// Every string is interned into a namespace of `N`, such as a user enum of the
+/// kinds of interned strings or constants, and yields a [`NamespacedSymbol`]
+/// of that namespace. Namespaces are denoted by marker types implementing
+/// [`Namespace`] so that symbols of different namespaces cannot be mixed up.
+/// Strings are deduplicated by the shared storage so that identical strings of
+/// different namespaces are stored only once.
+///
+/// # Example
+///
+/// ```
+/// use string_interner::{Namespace, NamespacedInterner};
+///
+/// #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+/// enum Kind {
+///     Ident,
+///     Label,
+/// }
+///
+/// struct Ident;
+/// struct Label;
+///
+/// impl Namespace<Kind> for Ident {
+///     const NAMESPACE: Kind = Kind::Ident;
+/// }
+///
+/// impl Namespace<Kind> for Label {
+///     const NAMESPACE: Kind = Kind::Label;
+/// }
+///
+/// let mut interner = <NamespacedInterner<Kind>>::new();
+/// let ident = interner.get_or_intern(Ident, "exit");
+/// let label = interner.get_or_intern(Label, "exit");
+/// assert_eq!(ident.storage_symbol(), label.storage_symbol());
+/// assert_eq!(interner.resolve(label), Some("exit"));
+/// assert_eq!(interner.get(Label, "loop"), None);
+/// assert_eq!(interner.storage().len(), 1);
+/// ```
+///
+/// Symbols of different namespaces cannot be compared:
+///
+/// ```compile_fail
+/// # use string_interner::{Namespace, NamespacedInterner};
+/// # struct Ident;
+/// # struct Label;
+/// # impl Namespace<u8> for Ident {
+/// #     const NAMESPACE: u8 = 0;
+/// # }
+/// # impl Namespace<u8> for Label {
+/// #     const NAMESPACE: u8 = 1;
+/// # }
+/// let mut interner = <NamespacedInterner<u8>>::new();
+/// let ident = interner.get_or_intern(Ident, "exit");
+/// let label = interner.get_or_intern(Label, "exit");
+/// assert_ne!(ident, label);
+/// ```
+pub struct NamespacedInterner<
+    N,
+    B = DefaultBackend<DefaultSymbol>,
+    H = DefaultHashBuilder,
+    A = Global,
+> where
+    N: Copy + Eq + Hash,
+    B: Backend,
+    H: BuildHasher,
+    A: Allocator,
+{
+    storage: StringInterner<B, H, A>,
+    /// The set of namespaces and storage symbol indices of all interned strings.
+    ///
+    /// Allocated from the allocator of the storage and hashed by its hasher.
+    members: HashTableIn<(N, usize), A>,
+}
+
+impl<N, B, H, A> Debug for NamespacedInterner<N, B, H, A>
+where
+    N: Copy + Eq + Hash + Debug,
+    B: Backend + Debug,
+    <B as Backend>::Symbol: Symbol + Debug,
+    H: BuildHasher,
+    A: Allocator,
+{
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("NamespacedInterner")
+            .field("storage", &self.storage)
+            .field("members", &self.members)
+            .finish()
+    }
+}
+
+impl<N, B, H, A> Default for NamespacedInterner<N, B, H, A>
+where
+    N: Copy + Eq + Hash,
+    B: Backend + Default,
+    <B as Backend>::Symbol: Symbol,
+    H: BuildHasher + Default,
+    A: Allocator + Clone + Default,
+{
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N, B, H, A> NamespacedInterner<N, B, H, A>
+where
+    N: Copy + Eq + Hash,
+    B: Backend + Default,
+    <B as Backend>::Symbol: Symbol,
+    H: BuildHasher + Default,
+    A: Allocator + Clone + Default,
+{
+    /// Creates a new empty `NamespacedInterner`.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn new() -> Self {
+        Self::with_hasher(H::default())
+    }
+}
+
+impl<N, B, H, A> NamespacedInterner<N, B, H, A>
+where
+    N: Copy + Eq + Hash,
+    B: Backend,
+    <B as Backend>::Symbol: Symbol,
+    H: BuildHasher,
+    A: Allocator,
+{
+    /// Creates a new empty `NamespacedInterner` with the given hasher.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn with_hasher(hash_builder: H) -> Self
+    where
+        B: Default,
+        A: Clone + Default,
+    {
+        Self::with_storage(StringInterner::with_hasher(hash_builder))
+    }
+
+    /// Creates a new empty `NamespacedInterner` that stores its strings in `storage`.
+    ///
+    /// # Note
+    ///
+    /// The strings of `storage` are not interned into any namespace. Use this to
+    /// provide a storage with a custom allocator which is used by the namespaces
+    /// as well.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn with_storage(storage: StringInterner<B, H, A>) -> Self
+    where
+        A: Clone,
+    {
+        let members = HashTableIn::with_capacity_in(0, storage.allocator().clone());
+        Self { storage, members }
+    }
+
+    /// Returns the shared storage of the strings of all namespaces.
+    #[inline]
+    pub fn storage(&self) -> &StringInterner<B, H, A> {
+        &self.storage
+    }
+
+    /// Returns the number of strings interned into all namespaces.
+    ///
+    /// # Note
+    ///
+    /// Strings interned into several namespaces are counted for each of them.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+
+    /// Returns `true` if no strings have been interned into any namespace.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns `true` if the string of the symbol is interned into its namespace.
+    #[inline]
+    pub fn contains<M>(&self, symbol: NamespacedSymbol<M, <B as Backend>::Symbol>) -> bool
+    where
+        M: Namespace<N>,
+    {
+        let member = (M::NAMESPACE, symbol.symbol.to_usize());
+        let hash = make_hash(self.storage.hasher(), &member);
+        self.members.find(hash, |&other| other == member).is_some()
+    }
+
+    /// Returns the symbol for the given string in the namespace if any.
+    #[inline]
+    pub fn get<M, T>(
+        &self,
+        _namespace: M,
+        string: T,
+    ) -> Option<NamespacedSymbol<M, <B as Backend>::Symbol>>
+    where
+        M: Namespace<N>,
+        T: AsRef<str>,
+    {
+        let symbol = NamespacedSymbol {
+            symbol: self.storage.get(string)?,
+            marker: PhantomData,
+        };
+        self.contains(symbol).then_some(symbol)
+    }
+
+    /// Interns the given string into the namespace and returns its symbol.
+    ///
+    /// Stores the string only if it has not been interned into any namespace.
+    ///
+    /// # Panics
+    ///
+    /// If interning into the storage fails, see [`StringInterner::get_or_intern`].
+    /// Use [`NamespacedInterner::try_get_or_intern`] to handle this case.
+    #[inline]
+    pub fn get_or_intern<M, T>(
+        &mut self,
+        _namespace: M,
+        string: T,
+    ) -> NamespacedSymbol<M, <B as Backend>::Symbol>
+    where
+        M: Namespace<N>,
+        T: AsRef<str>,
+    {
+        let symbol = self.storage.get_or_intern(string);
+        self.insert_member(M::NAMESPACE, symbol);
+        NamespacedSymbol {
+            symbol,
+            marker: PhantomData,
+        }
+    }
+
+    /// Tries to intern the given string into the namespace and returns its symbol.
+    ///
+    /// Stores the string only if it has not been interned into any namespace.
+    ///
+    /// # Errors
+    ///
+    /// If interning into the storage fails, see [`StringInterner::try_get_or_intern`].
+    pub fn try_get_or_intern<M, T>(
+        &mut self,
+        _namespace: M,
+        string: T,
+    ) -> Result<NamespacedSymbol<M, <B as Backend>::Symbol>, InternError>
+    where
+        M: Namespace<N>,
+        T: AsRef<str>,
+    {
+        let hasher = self.storage.hasher();
+        self.members
+            .try_reserve(1, |member| make_hash(hasher, member))
+            .map_err(TryReserveError::from)?;
+        let symbol = self.storage.try_get_or_intern(string)?;
+        self.insert_member(M::NAMESPACE, symbol);
+        Ok(NamespacedSymbol {
+            symbol,
+            marker: PhantomData,
+        })
+    }
+
+    /// Records that the string of `symbol` is interned into `namespace`.
+    fn insert_member(&mut self, namespace: N, symbol: <B as Backend>::Symbol) {
+        let member = (namespace, symbol.to_usize());
+        let hasher = self.storage.hasher();
+        let hash = make_hash(hasher, &member);
+        if self.members.find(hash, |&other| other == member).is_none() {
+            self.members
+                .insert_unique(hash, member, |member| make_hash(hasher, member));
+        }
+    }
+
+    /// Returns the string for the given symbol if it is interned into its namespace.
+    #[inline]
+    pub fn resolve<M>(
+        &self,
+        symbol: NamespacedSymbol<M, <B as Backend>::Symbol>,
+    ) -> Option<&str>
+    where
+        M: Namespace<N>,
+    {
+        if !self.contains(symbol) {
+            return None
+        }
+        self.storage.resolve(symbol.symbol)
+    }
+}
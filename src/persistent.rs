@@ -0,0 +1,366 @@
+//! Persistent string interner whose versions are cheap to clone.
+//!
+//! # Design
+//!
+//! The strings are stored in the leaves of a trie with a branching factor of 32.
+//! Interning copies only the nodes on the path to the last leaf that are shared
+//! with other versions, so that versions structurally share all other nodes.
+//!
+//! The deduplication index consists of hash table levels of decreasing sizes.
+//! New symbols are inserted into the last level if no other version shares it
+//! and into a new level otherwise. Levels of similar sizes are merged so that
+//! there are only logarithmically many levels to query.
+
+use crate::{
+    compat::{
+        Arc,
+        DefaultHashBuilder,
+        HashTable,
+        String,
+        Vec,
+    },
+    interner::make_hash,
+    DefaultSymbol,
+    Symbol,
+};
+use core::{
+    fmt,
+    hash::BuildHasher,
+};
+
+/// The base 2 logarithm of the number of children of a branch and of the
+/// number of strings of a leaf.
+const BITS: u32 = 5;
+
+/// The number of children of a branch and of the number of strings of a leaf.
+const WIDTH: usize = 1 << BITS;
+
+/// The maximum number of entries of a level that is extended in place.
+const TAIL_LEN: usize = 64;
+
+/// A leaf of the trie holding up to [`WIDTH`] strings.
+#[derive(Debug, Clone, Default)]
+struct Chunk {
+    buffer: String,
+    ends: Vec<usize>,
+}
+
+impl Chunk {
+    /// Returns the string at the given offset.
+    #[inline]
+    fn get(&self, offset: usize) -> Option<&str> {
+        let end = *self.ends.get(offset)?;
+        let start = offset.checked_sub(1).map_or(0, |prev| self.ends[prev]);
+        Some(&self.buffer[start..end])
+    }
+
+    fn push(&mut self, string: &str) {
+        self.buffer.push_str(string);
+        self.ends.push(self.buffer.len());
+    }
+}
+
+/// A node of the trie of strings.
+#[derive(Debug, Clone)]
+enum Node {
+    Branch(Vec<Arc<Node>>),
+    Leaf(Chunk),
+}
+
+/// The persistent trie of the interned strings.
+#[derive(Debug, Clone)]
+struct Strings {
+    root: Arc<Node>,
+    /// The number of branch levels above the leaves.
+    depth: u32,
+    len: usize,
+}
+
+impl Strings {
+    fn new() -> Self {
+        Self {
+            root: Arc::new(Node::Leaf(Chunk::default())),
+            depth: 0,
+            len: 0,
+        }
+    }
+
+    /// Returns the string at the given index if any.
+    fn get(&self, index: usize) -> Option<&str> {
+        if index >= self.len {
+            return None
+        }
+        let mut node = &*self.root;
+        for level in (1..=self.depth).rev() {
+            match node {
+                Node::Branch(children) => {
+                    node = &children[(index >> (BITS * level)) & (WIDTH - 1)];
+                }
+                Node::Leaf(_) => unreachable!("encountered leaf above the leaf level"),
+            }
+        }
+        match node {
+            Node::Leaf(chunk) => chunk.get(index & (WIDTH - 1)),
+            Node::Branch(_) => unreachable!("encountered branch at the leaf level"),
+        }
+    }
+
+    /// Pushes the string copying all shared nodes on the path to its leaf.
+    fn push(&mut self, string: &str) {
+        let index = self.len;
+        let capacity = 1_usize
+            .checked_shl(BITS * (self.depth + 1))
+            .unwrap_or(usize::MAX);
+        if index == capacity {
+            let root = self.root.clone();
+            self.root = Arc::new(Node::Branch(Vec::from([root])));
+            self.depth += 1;
+        }
+        let mut node = Arc::make_mut(&mut self.root);
+        for level in (1..=self.depth).rev() {
+            let Node::Branch(children) = node else {
+                unreachable!("encountered leaf above the leaf level")
+            };
+            let digit = (index >> (BITS * level)) & (WIDTH - 1);
+            if digit == children.len() {
+                let child = match level {
+                    1 => Node::Leaf(Chunk::default()),
+                    _ => Node::Branch(Vec::new()),
+                };
+                children.push(Arc::new(child));
+            }
+            node = Arc::make_mut(&mut children[digit]);
+        }
+        let Node::Leaf(chunk) = node else {
+            unreachable!("encountered branch at the leaf level")
+        };
+        chunk.push(string);
+        self.len += 1;
+    }
+}
+
+/// An entry of a level of the deduplication index.
+#[derive(Debug, Copy, Clone)]
+struct Entry<S> {
+    /// The cached hash of the string so that levels are merged without
+    /// resolving their strings.
+    hash: u64,
+    symbol: S,
+}
+
+/// A level of the deduplication index.
+type Level<S> = HashTable<Entry<S>>;
+
+/// Persistent string interner whose clones share their strings and symbols.
+///
+/// Cloning the interner takes constant time. Every clone interns strings
+/// independently: symbols interned before a clone are identical across the
+/// clones, while strings interned afterwards are only visible to the clone
+/// that interned them.
+///
+/// # Note
+///
+/// Interning into a clone copies only the few shared nodes that lead to its
+/// newest strings. Looking up a string queries logarithmically many hash table
+/// levels, so lookups are slower than with the [`StringInterner`].
+///
+/// # Example
+///
+/// ```
+/// use string_interner::PersistentInterner;
+///
+/// let mut before = <PersistentInterner>::new();
+/// let tiger = before.get_or_intern("Tiger");
+/// let mut after = before.clone();
+/// let horse = after.get_or_intern("Horse");
+/// assert_eq!(after.get("Tiger"), Some(tiger));
+/// assert_eq!(after.resolve(horse), Some("Horse"));
+/// assert_eq!(before.get("Horse"), None);
+/// ```
+///
+/// [`StringInterner`]: crate::StringInterner
+pub struct PersistentInterner<S = DefaultSymbol, H = DefaultHashBuilder>
+where
+    S: Symbol,
+    H: BuildHasher,
+{
+    strings: Strings,
+    levels: Arc<Vec<Arc<Level<S>>>>,
+    hasher: H,
+}
+
+impl<S, H> Clone for PersistentInterner<S, H>
+where
+    S: Symbol,
+    H: BuildHasher + Clone,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            strings: self.strings.clone(),
+            levels: self.levels.clone(),
+            hasher: self.hasher.clone(),
+        }
+    }
+}
+
+impl<S, H> fmt::Debug for PersistentInterner<S, H>
+where
+    S: Symbol,
+    H: BuildHasher,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PersistentInterner")
+            .field("len", &self.len())
+            .field("levels", &self.levels.len())
+            .finish()
+    }
+}
+
+impl<S, H> Default for PersistentInterner<S, H>
+where
+    S: Symbol,
+    H: BuildHasher + Default,
+{
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S, H> PersistentInterner<S, H>
+where
+    S: Symbol,
+    H: BuildHasher + Default,
+{
+    /// Creates a new empty `PersistentInterner`.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn new() -> Self {
+        Self::with_hasher(H::default())
+    }
+}
+
+impl<S, H> PersistentInterner<S, H>
+where
+    S: Symbol,
+    H: BuildHasher,
+{
+    /// Creates a new empty `PersistentInterner` with the given hasher.
+    pub fn with_hasher(hash_builder: H) -> Self {
+        Self {
+            strings: Strings::new(),
+            levels: Arc::default(),
+            hasher: hash_builder,
+        }
+    }
+
+    /// Returns the number of strings interned by the interner.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.strings.len
+    }
+
+    /// Returns `true` if the interner has no interned strings.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the symbol of the string with the given hash if any.
+    fn find(&self, hash: u64, string: &str) -> Option<S> {
+        let strings = &self.strings;
+        self.levels.iter().find_map(|level| {
+            level
+                .find(hash, |entry| {
+                    entry.hash == hash
+                        && strings.get(entry.symbol.to_usize()) == Some(string)
+                })
+                .map(|entry| entry.symbol)
+        })
+    }
+
+    /// Returns the symbol for the given string if any.
+    #[inline]
+    pub fn get<T>(&self, string: T) -> Option<S>
+    where
+        T: AsRef<str>,
+    {
+        let string = string.as_ref();
+        self.find(make_hash(&self.hasher, string), string)
+    }
+
+    /// Interns the given string and returns its symbol.
+    ///
+    /// # Panics
+    ///
+    /// If the interner already interns the maximum number of strings possible
+    /// by the chosen symbol type.
+    pub fn get_or_intern<T>(&mut self, string: T) -> S
+    where
+        T: AsRef<str>,
+    {
+        let string = string.as_ref();
+        let hash = make_hash(&self.hasher, string);
+        if let Some(symbol) = self.find(hash, string) {
+            return symbol
+        }
+        let symbol = S::try_from_usize(self.len()).expect("encountered invalid symbol");
+        self.strings.push(string);
+        self.insert_entry(Entry { hash, symbol });
+        symbol
+    }
+
+    /// Returns the string for the given symbol if any.
+    #[inline]
+    pub fn resolve(&self, symbol: S) -> Option<&str> {
+        self.strings.get(symbol.to_usize())
+    }
+
+    /// Inserts the entry into the deduplication index.
+    fn insert_entry(&mut self, entry: Entry<S>) {
+        let rehash = |entry: &Entry<S>| entry.hash;
+        let levels = Arc::make_mut(&mut self.levels);
+        match levels.last_mut().and_then(Arc::get_mut) {
+            Some(tail) if tail.len() < TAIL_LEN => {
+                tail.insert_unique(entry.hash, entry, rehash);
+            }
+            _ => {
+                let mut level = Level::default();
+                level.insert_unique(entry.hash, entry, rehash);
+                levels.push(Arc::new(level));
+            }
+        }
+        while let [.., prev, last] = levels.as_slice() {
+            if prev.len() > last.len() {
+                break
+            }
+            let last = levels.pop().expect("encountered missing last level");
+            let prev = levels.pop().expect("encountered missing previous level");
+            let mut merged = Arc::try_unwrap(prev).unwrap_or_else(|prev| (*prev).clone());
+            merged.reserve(last.len(), rehash);
+            for entry in last.iter() {
+                merged.insert_unique(entry.hash, *entry, rehash);
+            }
+            levels.push(Arc::new(merged));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn levels_are_logarithmic() {
+        let mut interner = <PersistentInterner>::new();
+        let mut clones = Vec::new();
+        for i in 0..10_000 {
+            interner.get_or_intern(i.to_string());
+            if i % 100 == 0 {
+                clones.push(interner.clone());
+            }
+        }
+        assert!(interner.levels.len() <= 16);
+        assert_eq!(interner.strings.depth, 2);
+    }
+}
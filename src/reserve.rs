@@ -0,0 +1,65 @@
+use core::{
+    alloc::Layout,
+    fmt,
+};
+
+/// The error returned by the fallible allocation methods of the string interner
+/// and its backends such as [`StringInterner::try_reserve`].
+///
+/// The string interner and its backend are left unchanged when this error is returned.
+///
+/// [`StringInterner::try_reserve`]: crate::StringInterner::try_reserve
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TryReserveError {
+    /// The required capacity exceeds the maximum capacity of a collection.
+    CapacityOverflow,
+    /// The allocator failed to provide the requested memory.
+    AllocError {
+        /// The layout of the allocation request that failed.
+        layout: Layout,
+    },
+}
+
+impl fmt::Display for TryReserveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::CapacityOverflow => {
+                write!(f, "required capacity exceeds the maximum capacity")
+            }
+            Self::AllocError { layout } => {
+                write!(
+                    f,
+                    "memory allocation of {} bytes failed",
+                    layout.size()
+                )
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for TryReserveError {}
+
+impl From<hashbrown::TryReserveError> for TryReserveError {
+    fn from(error: hashbrown::TryReserveError) -> Self {
+        match error {
+            hashbrown::TryReserveError::CapacityOverflow => Self::CapacityOverflow,
+            hashbrown::TryReserveError::AllocError { layout } => {
+                Self::AllocError { layout }
+            }
+        }
+    }
+}
+
+#[cfg(feature = "allocator-api")]
+impl From<allocator_api2::collections::TryReserveError> for TryReserveError {
+    fn from(error: allocator_api2::collections::TryReserveError) -> Self {
+        use allocator_api2::collections::TryReserveErrorKind;
+        match error.kind() {
+            TryReserveErrorKind::CapacityOverflow => Self::CapacityOverflow,
+            TryReserveErrorKind::AllocError { layout, .. } => {
+                Self::AllocError { layout }
+            }
+        }
+    }
+}
@@ -0,0 +1,881 @@
+//! Helpers to serialize and deserialize symbols with `serde`.
+//!
+//! The built-in symbols implement `Serialize` and `Deserialize` as their
+//! `usize` value. User-defined [`Symbol`] types can use the helpers of this
+//! module to get the same support.
+//!
+//! With the `std` crate feature symbols can also be serialized as their
+//! strings using [`as_str`] together with [`with_interner`] or [`with_interner_mut`].
+//!
+//! String interners can be serialized together with their symbols using [`with_symbols`]
+//! or as the raw storage of their backend using [`with_storage`].
+//!
+//! Strings can be interned while deserializing them using the [`InternSeed`]
+//! and maps with interned keys using the [`InternKeysSeed`].
+
+use crate::{
+    backend::Backend,
+    compat::{
+        Allocator,
+        Global,
+    },
+    DefaultHashBuilder,
+    StringInterner,
+    Symbol,
+};
+#[cfg(feature = "std")]
+use crate::{
+    serde_impl::cautious_capacity,
+    InternError,
+};
+use ::serde::de::{
+    DeserializeSeed,
+    Deserializer,
+    Unexpected,
+    Visitor,
+};
+use core::{
+    fmt,
+    hash::BuildHasher,
+};
+#[cfg(feature = "std")]
+use core::{
+    any::TypeId,
+    cell::Cell,
+    hash::Hash,
+    marker::PhantomData,
+};
+#[cfg(feature = "std")]
+use std::collections::{
+    hash_map::RandomState,
+    HashMap,
+};
+
+/// Serializes symbols as their `usize` value.
+///
+/// Use it for fields via `#[serde(with = "string_interner::serde::as_usize")]`
+/// or forward to it from hand-written `Serialize` and `Deserialize` impls.
+///
+/// # Example
+///
+/// ```
+/// use serde::{Deserialize, Deserializer, Serialize, Serializer};
+/// use string_interner::{serde::as_usize, Symbol};
+///
+/// #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+/// struct MySymbol(u8);
+///
+/// impl Symbol for MySymbol {
+///     fn try_from_usize(index: usize) -> Option<Self> {
+///         u8::try_from(index).ok().map(MySymbol)
+///     }
+///
+///     fn to_usize(self) -> usize {
+///         self.0 as usize
+///     }
+/// }
+///
+/// impl Serialize for MySymbol {
+///     fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+///         as_usize::serialize(self, serializer)
+///     }
+/// }
+///
+/// impl<'de> Deserialize<'de> for MySymbol {
+///     fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+///         as_usize::deserialize(deserializer)
+///     }
+/// }
+///
+/// assert_eq!(serde_json::to_string(&MySymbol(42)).unwrap(), "42");
+/// assert_eq!(serde_json::from_str::<MySymbol>("42").unwrap(), MySymbol(42));
+/// assert!(serde_json::from_str::<MySymbol>("256").is_err());
+/// ```
+pub mod as_usize {
+    use super::Symbol;
+    use ::serde::{
+        de::{
+            Error as _,
+            Unexpected,
+        },
+        Deserialize,
+        Deserializer,
+        Serializer,
+    };
+
+    /// Serializes the symbol as its `usize` value.
+    pub fn serialize<S, T>(symbol: &S, serializer: T) -> Result<T::Ok, T::Error>
+    where
+        S: Symbol,
+        T: Serializer,
+    {
+        serializer.serialize_u64(symbol.to_usize() as u64)
+    }
+
+    /// Deserializes the symbol from its `usize` value.
+    ///
+    /// # Errors
+    ///
+    /// If the value is not a valid value for the symbol type.
+    pub fn deserialize<'de, S, D>(deserializer: D) -> Result<S, D::Error>
+    where
+        S: Symbol,
+        D: Deserializer<'de>,
+    {
+        let value = u64::deserialize(deserializer)?;
+        usize::try_from(value)
+            .ok()
+            .and_then(S::try_from_usize)
+            .ok_or_else(|| {
+                D::Error::invalid_value(Unexpected::Unsigned(value), &"a valid symbol value")
+            })
+    }
+}
+
+/// Type erased access to the string interner of a [`with_interner`] scope.
+#[cfg(feature = "std")]
+trait ErasedInterner {
+    /// Resolves the symbol with the given `usize` value.
+    fn resolve_usize(&self, symbol: usize) -> Option<&str>;
+
+    /// Interns the string and returns the `usize` value of its symbol.
+    fn try_get_or_intern_usize(&mut self, string: &str) -> Result<usize, InternError>;
+}
+
+#[cfg(feature = "std")]
+impl<B, H, A> ErasedInterner for StringInterner<B, H, A>
+where
+    B: Backend,
+    <B as Backend>::Symbol: Symbol,
+    H: BuildHasher,
+    A: Allocator,
+{
+    fn resolve_usize(&self, symbol: usize) -> Option<&str> {
+        <B as Backend>::Symbol::try_from_usize(symbol).and_then(|symbol| self.resolve(symbol))
+    }
+
+    fn try_get_or_intern_usize(&mut self, string: &str) -> Result<usize, InternError> {
+        self.try_get_or_intern(string).map(Symbol::to_usize)
+    }
+}
+
+/// The string interner of the innermost [`with_interner`] scope of a thread.
+#[cfg(feature = "std")]
+#[derive(Copy, Clone)]
+struct Context {
+    /// Points to the string interner borrowed by the scope.
+    ///
+    /// The lifetime is erased. The pointer is only dereferenced while the scope is active.
+    interner: *mut (dyn ErasedInterner + 'static),
+    /// `true` if the scope borrows the string interner mutably.
+    mutable: bool,
+    /// The type of the symbols of the string interner.
+    symbol: TypeId,
+}
+
+#[cfg(feature = "std")]
+std::thread_local! {
+    static CONTEXT: Cell<Option<Context>> = const { Cell::new(None) };
+}
+
+/// Restores the previous context of the thread when dropped.
+#[cfg(feature = "std")]
+struct ContextGuard {
+    previous: Option<Context>,
+}
+
+#[cfg(feature = "std")]
+impl Drop for ContextGuard {
+    fn drop(&mut self) {
+        CONTEXT.with(|context| context.set(self.previous));
+    }
+}
+
+/// Runs `f` with `context` as the context of the current thread.
+#[cfg(feature = "std")]
+fn with_context<F, R>(context: Context, f: F) -> R
+where
+    F: FnOnce() -> R,
+{
+    let _guard = ContextGuard {
+        previous: CONTEXT.with(|current| current.replace(Some(context))),
+    };
+    f()
+}
+
+/// Erases the lifetime of the string interner trait object.
+///
+/// # Safety
+///
+/// The returned pointer must not be dereferenced after `'a` ends.
+#[cfg(feature = "std")]
+unsafe fn erase<'a>(
+    interner: *mut (dyn ErasedInterner + 'a),
+) -> *mut (dyn ErasedInterner + 'static) {
+    // SAFETY: Both are raw fat pointers with identical layout and the caller
+    //         guarantees that the pointer is not used after `'a` ends.
+    unsafe { core::mem::transmute(interner) }
+}
+
+/// Runs `f` with `interner` as the string interner used to serialize symbols via [`as_str`].
+///
+/// Scopes may be nested in which case the innermost scope is used.
+///
+/// # Example
+///
+/// ```
+/// use serde::Serialize;
+/// use string_interner::{serde::{as_str, with_interner}, DefaultSymbol, StringInterner};
+///
+/// #[derive(Serialize)]
+/// struct Animal {
+///     #[serde(with = "as_str")]
+///     name: DefaultSymbol,
+/// }
+///
+/// let mut interner = <StringInterner>::new();
+/// let animal = Animal { name: interner.get_or_intern("Tiger") };
+/// let json = with_interner(&interner, || serde_json::to_string(&animal)).unwrap();
+/// assert_eq!(json, r#"{"name":"Tiger"}"#);
+/// ```
+#[cfg(feature = "std")]
+pub fn with_interner<B, H, A, F, R>(interner: &StringInterner<B, H, A>, f: F) -> R
+where
+    B: Backend,
+    <B as Backend>::Symbol: Symbol + 'static,
+    H: BuildHasher,
+    A: Allocator,
+    F: FnOnce() -> R,
+{
+    let interner = interner as *const (dyn ErasedInterner + '_) as *mut (dyn ErasedInterner + '_);
+    let context = Context {
+        // SAFETY: The context is removed by the guard of `with_context` before
+        //         the borrow of the interner ends.
+        interner: unsafe { erase(interner) },
+        mutable: false,
+        symbol: TypeId::of::<<B as Backend>::Symbol>(),
+    };
+    with_context(context, f)
+}
+
+/// Runs `f` with `interner` as the string interner used to serialize and
+/// deserialize symbols via [`as_str`].
+///
+/// Deserialization interns the strings into `interner`.
+/// Scopes may be nested in which case the innermost scope is used.
+///
+/// # Example
+///
+/// ```
+/// use serde::Deserialize;
+/// use string_interner::{serde::{as_str, with_interner_mut}, DefaultSymbol, StringInterner};
+///
+/// #[derive(Deserialize)]
+/// struct Animal {
+///     #[serde(with = "as_str")]
+///     name: DefaultSymbol,
+/// }
+///
+/// let mut interner = <StringInterner>::new();
+/// let animal: Animal = with_interner_mut(&mut interner, || {
+///     serde_json::from_str(r#"{"name":"Tiger"}"#)
+/// }).unwrap();
+/// assert_eq!(interner.resolve(animal.name), Some("Tiger"));
+/// ```
+#[cfg(feature = "std")]
+pub fn with_interner_mut<B, H, A, F, R>(interner: &mut StringInterner<B, H, A>, f: F) -> R
+where
+    B: Backend,
+    <B as Backend>::Symbol: Symbol + 'static,
+    H: BuildHasher,
+    A: Allocator,
+    F: FnOnce() -> R,
+{
+    let interner = interner as *mut (dyn ErasedInterner + '_);
+    let context = Context {
+        // SAFETY: The context is removed by the guard of `with_context` before
+        //         the borrow of the interner ends.
+        interner: unsafe { erase(interner) },
+        mutable: true,
+        symbol: TypeId::of::<<B as Backend>::Symbol>(),
+    };
+    with_context(context, f)
+}
+
+/// Returns the context of the current thread if it uses symbols of type `S`.
+#[cfg(feature = "std")]
+fn current_context<S>() -> Result<Context, &'static str>
+where
+    S: Symbol + 'static,
+{
+    let context = CONTEXT
+        .with(Cell::get)
+        .ok_or("no string interner in scope, use string_interner::serde::with_interner")?;
+    if context.symbol != TypeId::of::<S>() {
+        return Err("the string interner in scope uses a different symbol type")
+    }
+    Ok(context)
+}
+
+/// Serializes symbols as their strings and deserializes them by interning the strings.
+///
+/// Use it for fields via `#[serde(with = "string_interner::serde::as_str")]`.
+/// The string interner is provided by the innermost [`with_interner`] or
+/// [`with_interner_mut`] scope of the current thread.
+#[cfg(feature = "std")]
+pub mod as_str {
+    use super::{
+        current_context,
+        Symbol,
+    };
+    use crate::compat::String;
+    use ::serde::{
+        de::Error as _,
+        ser::Error as _,
+        Deserialize,
+        Deserializer,
+        Serializer,
+    };
+
+    /// Serializes the symbol as its string.
+    ///
+    /// # Errors
+    ///
+    /// - If there is no string interner in scope.
+    /// - If the string interner in scope uses another symbol type.
+    /// - If the symbol cannot be resolved by the string interner in scope.
+    pub fn serialize<S, T>(symbol: &S, serializer: T) -> Result<T::Ok, T::Error>
+    where
+        S: Symbol + 'static,
+        T: Serializer,
+    {
+        let context = current_context::<S>().map_err(T::Error::custom)?;
+        // SAFETY: The context is only set while its string interner is borrowed.
+        //         While a shared reference to the interner exists here no other
+        //         code is able to mutate it through the context.
+        let interner = unsafe { &*context.interner };
+        let string = interner.resolve_usize(symbol.to_usize()).ok_or_else(|| {
+            T::Error::custom("symbol cannot be resolved by the string interner in scope")
+        })?;
+        serializer.serialize_str(string)
+    }
+
+    /// Deserializes the symbol by interning its string.
+    ///
+    /// # Errors
+    ///
+    /// - If there is no mutable string interner in scope.
+    /// - If the string interner in scope uses another symbol type.
+    /// - If interning the string exceeds the limits of the string interner in scope.
+    pub fn deserialize<'de, S, D>(deserializer: D) -> Result<S, D::Error>
+    where
+        S: Symbol + 'static,
+        D: Deserializer<'de>,
+    {
+        let context = current_context::<S>().map_err(D::Error::custom)?;
+        if !context.mutable {
+            return Err(D::Error::custom(
+                "the string interner in scope is not mutable, \
+                 use string_interner::serde::with_interner_mut",
+            ))
+        }
+        let string = String::deserialize(deserializer)?;
+        // SAFETY: The context is only set while its string interner is mutably
+        //         borrowed. The reference does not escape this function and
+        //         no other reference to the interner is alive meanwhile.
+        let interner = unsafe { &mut *context.interner };
+        let symbol = interner
+            .try_get_or_intern_usize(&string)
+            .map_err(D::Error::custom)?;
+        S::try_from_usize(symbol)
+            .ok_or_else(|| D::Error::custom("encountered invalid symbol"))
+    }
+}
+
+/// Serializes string interners as the raw storage of their backend.
+///
+/// The [`StringBackend`] and [`BufferBackend`] write their buffers as byte blobs
+/// for formats that are not human-readable such as `bincode`, which are validated
+/// and loaded without re-interning every string. Other backends and
+/// human-readable formats use the sequence of strings of the default
+/// `Serialize` impl of the `StringInterner`. Backends opt into a representation
+/// of their own via [`Backend::serialize_storage`].
+///
+/// Use it for fields via `#[serde(with = "string_interner::serde::with_storage")]`.
+///
+/// # Note
+///
+/// The raw storage is not compatible with the default representation, so
+/// data written by one can only be read by the other if the format is
+/// human-readable.
+///
+/// # Example
+///
+/// ```
+/// use serde::{Deserialize, Serialize};
+/// use string_interner::StringInterner;
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Table {
+///     #[serde(with = "string_interner::serde::with_storage")]
+///     interner: StringInterner,
+/// }
+///
+/// let table = Table { interner: ["Tiger", "Horse"].into_iter().collect() };
+/// let binary = bincode::serialize(&table).unwrap();
+/// let restored: Table = bincode::deserialize(&binary).unwrap();
+/// assert!(restored.interner == table.interner);
+/// ```
+///
+/// [`StringBackend`]: crate::backend::StringBackend
+/// [`BufferBackend`]: crate::backend::BufferBackend
+pub mod with_storage {
+    use crate::{
+        backend::Backend,
+        compat::Allocator,
+        StringInterner,
+        Symbol,
+    };
+    use ::serde::{
+        de::Error as _,
+        Deserialize,
+        Deserializer,
+        Serialize,
+        Serializer,
+    };
+    use core::hash::BuildHasher;
+
+    /// Serializes the string interner as the raw storage of its backend.
+    ///
+    /// Serializes its strings as a sequence if the backend has no representation
+    /// of its own for the format.
+    pub fn serialize<I, T>(interner: &I, serializer: T) -> Result<T::Ok, T::Error>
+    where
+        I: ?Sized + StorageTable,
+        T: Serializer,
+    {
+        interner.serialize_storage(serializer)
+    }
+
+    /// Deserializes the string interner from the raw storage of its backend.
+    ///
+    /// # Errors
+    ///
+    /// - If the storage is invalid for the backend.
+    /// - If the storage contains a string more than once.
+    pub fn deserialize<'de, I, D>(deserializer: D) -> Result<I, D::Error>
+    where
+        I: StorageTable,
+        D: Deserializer<'de>,
+    {
+        I::deserialize_storage(deserializer)
+    }
+
+    mod sealed {
+        use ::serde::{
+            Deserializer,
+            Serializer,
+        };
+
+        /// Implemented by string interners serializable via [`with_storage`](super).
+        ///
+        /// # Note
+        ///
+        /// Bounding the functions by this trait instead of by an iterable backend
+        /// allows callers to infer the string interner type.
+        pub trait StorageTable {
+            /// Serializes `self` as the raw storage of its backend.
+            fn serialize_storage<T>(&self, serializer: T) -> Result<T::Ok, T::Error>
+            where
+                T: Serializer;
+
+            /// Deserializes `Self` from the raw storage of its backend.
+            fn deserialize_storage<'de, D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                Self: Sized,
+                D: Deserializer<'de>;
+        }
+    }
+    use self::sealed::StorageTable;
+
+    impl<B, H, A> StorageTable for StringInterner<B, H, A>
+    where
+        B: Backend + Default,
+        <B as Backend>::Symbol: Symbol,
+        for<'a> &'a B: IntoIterator<Item = (<B as Backend>::Symbol, &'a str)>,
+        H: BuildHasher + Default,
+        A: Allocator + Default,
+    {
+        fn serialize_storage<T>(&self, serializer: T) -> Result<T::Ok, T::Error>
+        where
+            T: Serializer,
+        {
+            match self.backend().serialize_storage(serializer) {
+                Ok(result) => result,
+                Err(serializer) => self.serialize(serializer),
+            }
+        }
+
+        fn deserialize_storage<'de, D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            let mut backend = B::default();
+            match backend.deserialize_storage(deserializer) {
+                Ok(result) => {
+                    result?;
+                    let interner =
+                        Self::try_from_backend(backend, H::default(), A::default());
+                    interner.map_err(|_| D::Error::custom("encountered duplicate string"))
+                }
+                Err(deserializer) => Self::deserialize(deserializer),
+            }
+        }
+    }
+}
+
+/// Serializes string interners as `(symbol, string)` pairs and restores them exactly.
+///
+/// Unlike the default `Deserialize` impl of the `StringInterner`
+/// that re-interns the strings in order, deserialization fails if the
+/// backend does not assign the recorded symbols, e.g. when restoring into a
+/// different backend, or if the input contains duplicate strings.
+///
+/// Use it for fields via `#[serde(with = "string_interner::serde::with_symbols")]`.
+///
+/// # Example
+///
+/// ```
+/// use serde::{Deserialize, Serialize};
+/// use string_interner::{backend::BufferBackend, StringInterner};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Table {
+///     #[serde(with = "string_interner::serde::with_symbols")]
+///     interner: StringInterner<BufferBackend>,
+/// }
+///
+/// let table = Table { interner: ["Tiger", "Horse"].into_iter().collect() };
+/// let json = serde_json::to_string(&table).unwrap();
+/// assert_eq!(json, r#"{"interner":[[0,"Tiger"],[6,"Horse"]]}"#);
+/// let restored: Table = serde_json::from_str(&json).unwrap();
+/// assert!(restored.interner == table.interner);
+/// ```
+pub mod with_symbols {
+    use crate::{
+        backend::Backend,
+        compat::{
+            Allocator,
+            String,
+        },
+        serde_impl::cautious_capacity,
+        StringInterner,
+        Symbol,
+    };
+    use ::serde::{
+        de::{
+            Error as _,
+            SeqAccess,
+            Visitor,
+        },
+        ser::SerializeSeq,
+        Deserializer,
+        Serializer,
+    };
+    use core::{
+        fmt,
+        hash::BuildHasher,
+        marker::PhantomData,
+    };
+
+    /// Serializes the string interner as a sequence of `(symbol, string)` pairs.
+    pub fn serialize<I, T>(interner: &I, serializer: T) -> Result<T::Ok, T::Error>
+    where
+        I: ?Sized + SymbolTable,
+        T: Serializer,
+    {
+        interner.serialize_with_symbols(serializer)
+    }
+
+    /// Deserializes the string interner from a sequence of `(symbol, string)` pairs.
+    ///
+    /// # Errors
+    ///
+    /// - If the input contains a string more than once.
+    /// - If the backend assigns a different symbol than the recorded one to a string.
+    pub fn deserialize<'de, B, H, A, D>(
+        deserializer: D,
+    ) -> Result<StringInterner<B, H, A>, D::Error>
+    where
+        B: Backend + Default,
+        <B as Backend>::Symbol: Symbol,
+        H: BuildHasher + Default,
+        A: Allocator + Default,
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(WithSymbolsVisitor {
+            marker: PhantomData,
+            alloc_marker: PhantomData,
+        })
+    }
+
+    mod sealed {
+        use ::serde::Serializer;
+
+        /// Implemented by string interners that can be serialized via [`with_symbols`](super).
+        ///
+        /// # Note
+        ///
+        /// Bounding `serialize` by this trait instead of by an iterable backend
+        /// allows callers to infer the string interner type from the argument.
+        pub trait SymbolTable {
+            /// Serializes `self` as a sequence of `(symbol, string)` pairs.
+            fn serialize_with_symbols<T>(&self, serializer: T) -> Result<T::Ok, T::Error>
+            where
+                T: Serializer;
+        }
+    }
+    use self::sealed::SymbolTable;
+
+    impl<B, H, A> SymbolTable for StringInterner<B, H, A>
+    where
+        B: Backend,
+        <B as Backend>::Symbol: Symbol,
+        for<'a> &'a B: IntoIterator<Item = (<B as Backend>::Symbol, &'a str)>,
+        H: BuildHasher,
+        A: Allocator,
+    {
+        fn serialize_with_symbols<T>(&self, serializer: T) -> Result<T::Ok, T::Error>
+        where
+            T: Serializer,
+        {
+            let mut seq = serializer.serialize_seq(Some(self.len()))?;
+            for (symbol, string) in self {
+                seq.serialize_element(&(symbol.to_usize() as u64, string))?
+            }
+            seq.end()
+        }
+    }
+
+    struct WithSymbolsVisitor<B, H, A> {
+        marker: PhantomData<fn() -> (B, H)>,
+        alloc_marker: PhantomData<fn() -> A>,
+    }
+
+    impl<'de, B, H, A> Visitor<'de> for WithSymbolsVisitor<B, H, A>
+    where
+        B: Backend + Default,
+        <B as Backend>::Symbol: Symbol,
+        H: BuildHasher + Default,
+        A: Allocator + Default,
+    {
+        type Value = StringInterner<B, H, A>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("Expected a sequence of unique symbols and strings.")
+        }
+
+        fn visit_seq<V>(self, mut seq: V) -> Result<Self::Value, V::Error>
+        where
+            V: SeqAccess<'de>,
+        {
+            let mut interner: StringInterner<B, H, A> =
+                StringInterner::with_capacity_and_hasher(
+                    cautious_capacity::<(u64, String)>(seq.size_hint()),
+                    H::default(),
+                );
+            while let Some((expected, string)) = seq.next_element::<(u64, String)>()? {
+                if interner.get(&string).is_some() {
+                    return Err(V::Error::custom(format_args!(
+                        "encountered duplicate string {:?}",
+                        string
+                    )))
+                }
+                let actual = interner
+                    .try_get_or_intern(&string)
+                    .map_err(V::Error::custom)?
+                    .to_usize();
+                if actual as u64 != expected {
+                    return Err(V::Error::custom(format_args!(
+                        "cannot restore symbol {} of string {:?}, the backend assigned {}",
+                        expected, string, actual
+                    )))
+                }
+            }
+            Ok(interner)
+        }
+    }
+}
+
+/// Deserializes a string into its symbol by interning it into the wrapped string interner.
+///
+/// The string is interned directly from the input so no intermediate `String`
+/// is allocated if the deserializer provides borrowed or transient strings.
+///
+/// Fails if interning the string exceeds the [`InternerLimits`] of the string interner.
+///
+/// [`InternerLimits`]: crate::InternerLimits
+///
+/// # Example
+///
+/// ```
+/// use serde::de::DeserializeSeed;
+/// use string_interner::{serde::InternSeed, StringInterner};
+///
+/// let mut interner = <StringInterner>::new();
+/// let mut deserializer = serde_json::Deserializer::from_str(r#""Tiger""#);
+/// let symbol = InternSeed(&mut interner).deserialize(&mut deserializer).unwrap();
+/// assert_eq!(interner.resolve(symbol), Some("Tiger"));
+/// ```
+pub struct InternSeed<'a, B, H = DefaultHashBuilder, A = Global>(
+    pub &'a mut StringInterner<B, H, A>,
+)
+where
+    B: Backend,
+    H: BuildHasher,
+    A: Allocator;
+
+impl<'a, 'de, B, H, A> DeserializeSeed<'de> for InternSeed<'a, B, H, A>
+where
+    B: Backend,
+    <B as Backend>::Symbol: Symbol,
+    H: BuildHasher,
+    A: Allocator,
+{
+    type Value = <B as Backend>::Symbol;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_str(self)
+    }
+}
+
+impl<'a, 'de, B, H, A> Visitor<'de> for InternSeed<'a, B, H, A>
+where
+    B: Backend,
+    <B as Backend>::Symbol: Symbol,
+    H: BuildHasher,
+    A: Allocator,
+{
+    type Value = <B as Backend>::Symbol;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a string")
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+    where
+        E: ::serde::de::Error,
+    {
+        self.0.try_get_or_intern(value).map_err(E::custom)
+    }
+
+    fn visit_bytes<E>(self, value: &[u8]) -> Result<Self::Value, E>
+    where
+        E: ::serde::de::Error,
+    {
+        let value = core::str::from_utf8(value)
+            .map_err(|_| E::invalid_value(Unexpected::Bytes(value), &self))?;
+        self.visit_str(value)
+    }
+}
+
+/// Deserializes a map into a `HashMap` with its keys interned into the wrapped string interner.
+///
+/// Like the `Deserialize` impl of `HashMap` later entries replace earlier
+/// entries with the same key.
+///
+/// # Example
+///
+/// ```
+/// use serde::de::DeserializeSeed;
+/// use std::collections::HashMap;
+/// use string_interner::{serde::InternKeysSeed, DefaultSymbol, StringInterner};
+///
+/// let mut interner = <StringInterner>::new();
+/// let mut deserializer = serde_json::Deserializer::from_str(r#"{"Tiger":4,"Bird":2}"#);
+/// let legs: HashMap<DefaultSymbol, u32> = InternKeysSeed::new(&mut interner)
+///     .deserialize(&mut deserializer)
+///     .unwrap();
+/// assert_eq!(legs[&interner.get("Bird").unwrap()], 2);
+/// ```
+#[cfg(feature = "std")]
+pub struct InternKeysSeed<'a, V, B, H = DefaultHashBuilder, S = RandomState, A = Global>
+where
+    B: Backend,
+    H: BuildHasher,
+    A: Allocator,
+{
+    interner: &'a mut StringInterner<B, H, A>,
+    marker: PhantomData<fn() -> (V, S)>,
+}
+
+#[cfg(feature = "std")]
+impl<'a, V, B, H, S, A> InternKeysSeed<'a, V, B, H, S, A>
+where
+    B: Backend,
+    H: BuildHasher,
+    A: Allocator,
+{
+    /// Creates a new seed that interns the keys into `interner`.
+    pub fn new(interner: &'a mut StringInterner<B, H, A>) -> Self {
+        Self {
+            interner,
+            marker: PhantomData,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a, 'de, V, B, H, S, A> DeserializeSeed<'de> for InternKeysSeed<'a, V, B, H, S, A>
+where
+    V: ::serde::Deserialize<'de>,
+    B: Backend,
+    <B as Backend>::Symbol: Symbol + Hash,
+    H: BuildHasher,
+    S: BuildHasher + Default,
+    A: Allocator,
+{
+    type Value = HashMap<<B as Backend>::Symbol, V, S>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(self)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a, 'de, V, B, H, S, A> Visitor<'de> for InternKeysSeed<'a, V, B, H, S, A>
+where
+    V: ::serde::Deserialize<'de>,
+    B: Backend,
+    <B as Backend>::Symbol: Symbol + Hash,
+    H: BuildHasher,
+    S: BuildHasher + Default,
+    A: Allocator,
+{
+    type Value = HashMap<<B as Backend>::Symbol, V, S>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a map with string keys")
+    }
+
+    fn visit_map<M>(self, mut access: M) -> Result<Self::Value, M::Error>
+    where
+        M: ::serde::de::MapAccess<'de>,
+    {
+        let mut map = HashMap::with_capacity_and_hasher(
+            cautious_capacity::<(<B as Backend>::Symbol, V)>(access.size_hint()),
+            S::default(),
+        );
+        while let Some(key) = access.next_key_seed(InternSeed(&mut *self.interner))? {
+            let value = access.next_value()?;
+            map.insert(key, value);
+        }
+        Ok(map)
+    }
+}
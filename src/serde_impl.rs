@@ -1,36 +1,57 @@
 use crate::{
     backend::Backend,
+    compat::{
+        Allocator,
+        String,
+        Vec,
+    },
+    serde::InternSeed,
+    symbol::{
+        SymbolU16,
+        SymbolU32,
+        SymbolUsize,
+    },
+    Delta,
     StringInterner,
     Symbol,
+    Watermark,
 };
-#[cfg(not(feature = "std"))]
-use alloc::boxed::Box;
 use core::{
     default::Default,
     fmt,
     hash::BuildHasher,
     marker,
+    mem,
 };
 use serde::{
     de::{
         Deserialize,
+        DeserializeSeed,
         Deserializer,
+        Error as _,
         SeqAccess,
+        Unexpected,
         Visitor,
     },
     ser::{
         Serialize,
         SerializeSeq,
+        SerializeTuple,
         Serializer,
     },
 };
 
-impl<B, H> Serialize for StringInterner<B, H>
+/// Serializes string interners as a sequence of their strings.
+///
+/// Use [`serde::with_storage`](crate::serde::with_storage) to serialize them as
+/// the raw storage of their backend instead.
+impl<B, H, A> Serialize for StringInterner<B, H, A>
 where
     B: Backend,
     <B as Backend>::Symbol: Symbol,
     for<'a> &'a B: IntoIterator<Item = (<B as Backend>::Symbol, &'a str)>,
     H: BuildHasher,
+    A: Allocator,
 {
     fn serialize<T>(&self, serializer: T) -> Result<T::Ok, T::Error>
     where
@@ -44,65 +65,344 @@ where
     }
 }
 
-impl<'de, B, H> Deserialize<'de> for StringInterner<B, H>
+/// Deserializes string interners by interning a sequence of strings.
+///
+/// # Note
+///
+/// Fails instead of panicking if the symbols or limits of the interner are exceeded.
+impl<'de, B, H, A> Deserialize<'de> for StringInterner<B, H, A>
 where
-    B: Backend,
+    B: Backend + Default,
     <B as Backend>::Symbol: Symbol,
     H: BuildHasher + Default,
+    A: Allocator + Default,
 {
-    fn deserialize<D>(deserializer: D) -> Result<StringInterner<B, H>, D::Error>
+    fn deserialize<D>(deserializer: D) -> Result<StringInterner<B, H, A>, D::Error>
     where
         D: Deserializer<'de>,
     {
-        deserializer.deserialize_seq(StringInternerVisitor::default())
+        deserializer.deserialize_seq(StringInternerVisitor(marker::PhantomData))
     }
 }
 
-struct StringInternerVisitor<B, H>
+struct StringInternerVisitor<B, H, A>(marker::PhantomData<(B, H, A)>)
 where
     B: Backend,
-    <B as Backend>::Symbol: Symbol,
     H: BuildHasher,
-{
-    mark: marker::PhantomData<(<B as Backend>::Symbol, B, H)>,
-}
+    A: Allocator;
 
-impl<B, H> Default for StringInternerVisitor<B, H>
+impl<'de, B, H, A> Visitor<'de> for StringInternerVisitor<B, H, A>
 where
-    B: Backend,
+    B: Backend + Default,
     <B as Backend>::Symbol: Symbol,
-    H: BuildHasher,
+    H: BuildHasher + Default,
+    A: Allocator + Default,
 {
-    fn default() -> Self {
-        StringInternerVisitor {
-            mark: marker::PhantomData,
+    type Value = StringInterner<B, H, A>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("Expected a contiguous sequence of strings.")
+    }
+
+    fn visit_seq<V>(self, mut seq: V) -> Result<Self::Value, V::Error>
+    where
+        V: SeqAccess<'de>,
+    {
+        let mut interner = StringInterner::with_capacity_and_hasher(
+            cautious_capacity::<String>(seq.size_hint()),
+            H::default(),
+        );
+        while seq.next_element_seed(InternSeed(&mut interner))?.is_some() {}
+        Ok(interner)
+    }
+}
+
+impl Serialize for Watermark {
+    fn serialize<T>(&self, serializer: T) -> Result<T::Ok, T::Error>
+    where
+        T: Serializer,
+    {
+        (self.to_usize() as u64, self.fingerprint()).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Watermark {
+    fn deserialize<D>(deserializer: D) -> Result<Watermark, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let (len, fingerprint) = <(u64, u64)>::deserialize(deserializer)?;
+        usize::try_from(len)
+            .map(|len| Watermark::new(len, fingerprint))
+            .map_err(|_| serde::de::Error::custom("watermark out of bounds for usize"))
+    }
+}
+
+impl Serialize for Delta {
+    fn serialize<T>(&self, serializer: T) -> Result<T::Ok, T::Error>
+    where
+        T: Serializer,
+    {
+        let mut tuple = serializer.serialize_tuple(3)?;
+        tuple.serialize_element(&self.start())?;
+        tuple.serialize_element(&self.end())?;
+        tuple.serialize_element(&DeltaEntries(self))?;
+        tuple.end()
+    }
+}
+
+/// Serializes the `(symbol, string)` entries of a [`Delta`] as a sequence.
+struct DeltaEntries<'a>(&'a Delta);
+
+impl<'a> Serialize for DeltaEntries<'a> {
+    fn serialize<T>(&self, serializer: T) -> Result<T::Ok, T::Error>
+    where
+        T: Serializer,
+    {
+        let mut seq = serializer.serialize_seq(Some(self.0.len()))?;
+        for (symbol, string) in self.0 {
+            seq.serialize_element(&(symbol as u64, string))?
         }
+        seq.end()
     }
 }
 
-impl<'de, B, H> Visitor<'de> for StringInternerVisitor<B, H>
-where
-    B: Backend,
-    <B as Backend>::Symbol: Symbol,
-    H: BuildHasher + Default,
-{
-    type Value = StringInterner<B, H>;
+impl<'de> Deserialize<'de> for Delta {
+    fn deserialize<D>(deserializer: D) -> Result<Delta, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_tuple(3, DeltaVisitor)
+    }
+}
+
+struct DeltaVisitor;
+
+impl<'de> Visitor<'de> for DeltaVisitor {
+    type Value = Delta;
 
     fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-        formatter.write_str("Expected a contiguous sequence of strings.")
+        formatter.write_str(
+            "Expected two watermarks followed by a sequence of symbols and strings.",
+        )
     }
 
     fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
     where
         A: SeqAccess<'de>,
     {
-        let mut interner: StringInterner<B, H> = StringInterner::with_capacity_and_hasher(
-            seq.size_hint().unwrap_or(0),
-            H::default(),
-        );
-        while let Some(s) = seq.next_element::<Box<str>>()? {
-            interner.get_or_intern(s);
+        let start = seq
+            .next_element::<Watermark>()?
+            .ok_or_else(|| A::Error::invalid_length(0, &self))?;
+        let end = seq
+            .next_element::<Watermark>()?
+            .ok_or_else(|| A::Error::invalid_length(1, &self))?;
+        let entries = seq
+            .next_element::<Vec<(u64, String)>>()?
+            .ok_or_else(|| A::Error::invalid_length(2, &self))?
+            .into_iter()
+            .map(|(symbol, string)| {
+                usize::try_from(symbol)
+                    .map(|symbol| (symbol, string))
+                    .map_err(|_| A::Error::custom("symbol out of bounds for usize"))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Delta {
+            start,
+            end,
+            entries,
+        })
+    }
+}
+
+macro_rules! impl_serde_for_symbol {
+    ( $( $name:ident($base_ty:ty) ),* $(,)? ) => {
+        $(
+            impl Serialize for $name {
+                fn serialize<T>(&self, serializer: T) -> Result<T::Ok, T::Error>
+                where
+                    T: Serializer,
+                {
+                    // Symbol values always fit into their base type.
+                    (self.to_usize() as $base_ty).serialize(serializer)
+                }
+            }
+
+            impl<'de> Deserialize<'de> for $name {
+                fn deserialize<D>(deserializer: D) -> Result<$name, D::Error>
+                where
+                    D: Deserializer<'de>,
+                {
+                    let value = <$base_ty>::deserialize(deserializer)?;
+                    usize::try_from(value)
+                        .ok()
+                        .and_then(<$name>::try_from_usize)
+                        .ok_or_else(|| {
+                            D::Error::invalid_value(
+                                Unexpected::Unsigned(value as u64),
+                                &concat!("a valid ", stringify!($name), " value"),
+                            )
+                        })
+                }
+            }
+        )*
+    };
+}
+impl_serde_for_symbol!(SymbolU16(u16), SymbolU32(u32), SymbolUsize(u64));
+
+/// Serializes a byte slice as a byte blob.
+pub(crate) struct Bytes<'a>(pub &'a [u8]);
+
+impl<'a> Serialize for Bytes<'a> {
+    fn serialize<T>(&self, serializer: T) -> Result<T::Ok, T::Error>
+    where
+        T: Serializer,
+    {
+        serializer.serialize_bytes(self.0)
+    }
+}
+
+/// Deserializes a byte blob into an owned byte buffer.
+pub(crate) struct ByteBuf(pub Vec<u8>);
+
+impl<'de> Deserialize<'de> for ByteBuf {
+    fn deserialize<D>(deserializer: D) -> Result<ByteBuf, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_byte_buf(ByteBufVisitor)
+    }
+}
+
+struct ByteBufVisitor;
+
+impl<'de> Visitor<'de> for ByteBufVisitor {
+    type Value = ByteBuf;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("Expected a byte blob.")
+    }
+
+    fn visit_bytes<E>(self, bytes: &[u8]) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(ByteBuf(bytes.to_vec()))
+    }
+
+    fn visit_byte_buf<E>(self, bytes: Vec<u8>) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(ByteBuf(bytes))
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut bytes = Vec::with_capacity(cautious_capacity::<u8>(seq.size_hint()));
+        while let Some(byte) = seq.next_element::<u8>()? {
+            bytes.push(byte);
         }
-        Ok(interner)
+        Ok(ByteBuf(bytes))
     }
 }
+
+/// Returns the capacity to preallocate for a sequence of `T` with the given size hint.
+///
+/// Caps the untrusted size hint to a megabyte of elements like `serde` does so
+/// that inputs cannot cause huge allocations by announcing long sequences.
+pub(crate) fn cautious_capacity<T>(hint: Option<usize>) -> usize {
+    const MAX_PREALLOC_BYTES: usize = 1024 * 1024;
+    hint.unwrap_or(0)
+        .min(MAX_PREALLOC_BYTES / mem::size_of::<T>().max(1))
+}
+
+/// Serializes the strings of a backend as a sequence.
+#[cfg(feature = "backends")]
+pub(crate) fn serialize_strings<'a, B, T>(backend: &'a B, serializer: T) -> Result<T::Ok, T::Error>
+where
+    B: Backend,
+    &'a B: IntoIterator<Item = (<B as Backend>::Symbol, &'a str)>,
+    T: Serializer,
+{
+    let iter = backend.into_iter();
+    let mut seq = serializer.serialize_seq(Some(iter.size_hint().0))?;
+    for (_symbol, string) in iter {
+        seq.serialize_element(string)?
+    }
+    seq.end()
+}
+
+/// Deserializes a backend by interning a sequence of strings.
+///
+/// # Note
+///
+/// Duplicate strings are interned multiple times.
+#[cfg(feature = "backends")]
+pub(crate) fn deserialize_strings<'de, B, D>(deserializer: D) -> Result<B, D::Error>
+where
+    B: Backend + Default,
+    D: Deserializer<'de>,
+{
+    struct StringsVisitor<B>(marker::PhantomData<fn() -> B>);
+
+    impl<'de, B> Visitor<'de> for StringsVisitor<B>
+    where
+        B: Backend + Default,
+    {
+        type Value = B;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("Expected a sequence of strings.")
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where
+            A: SeqAccess<'de>,
+        {
+            let mut backend = B::with_capacity(cautious_capacity::<String>(seq.size_hint()));
+            while seq.next_element_seed(StringSeed(&mut backend))?.is_some() {}
+            Ok(backend)
+        }
+    }
+
+    /// Interns a string into the backend without allocating an intermediate `String`.
+    struct StringSeed<'a, B>(&'a mut B);
+
+    impl<'a, 'de, B> DeserializeSeed<'de> for StringSeed<'a, B>
+    where
+        B: Backend,
+    {
+        type Value = ();
+
+        fn deserialize<D>(self, deserializer: D) -> Result<(), D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            deserializer.deserialize_str(self)
+        }
+    }
+
+    impl<'a, 'de, B> Visitor<'de> for StringSeed<'a, B>
+    where
+        B: Backend,
+    {
+        type Value = ();
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("Expected a string.")
+        }
+
+        fn visit_str<E>(self, string: &str) -> Result<(), E>
+        where
+            E: serde::de::Error,
+        {
+            self.0.try_intern(string).map_err(E::custom)?;
+            Ok(())
+        }
+    }
+
+    deserializer.deserialize_seq(StringsVisitor(marker::PhantomData))
+}
@@ -0,0 +1,516 @@
+//! A string interner that lives in a memory-mapped file shared between processes.
+//!
+//! All processes that open the same file agree on the symbols of the interned
+//! strings and are able to resolve strings interned by any other process.
+//!
+//! # File Layout
+//!
+//! The file has a fixed size that is determined upon creation:
+//!
+//! | Region  | Contents |
+//! |:--------|:---------|
+//! | Header  | Magic number, capacities and the published string and byte counts. |
+//! | Ends    | The end offset of every interned string within the bytes region. |
+//! | Table   | Open addressing hash index mapping string hashes to `symbol + 1`. |
+//! | Bytes   | The concatenated contents of all interned strings. |
+//!
+//! # Synchronization
+//!
+//! Appends are serialized across processes by an exclusive `flock` on the
+//! file. Readers never take the lock: a writer first writes the string bytes
+//! and end offset, then publishes the new string count and finally its hash
+//! index slot, each with `Release` ordering. Readers observe them through
+//! `Acquire` loads.
+//!
+//! # Note
+//!
+//! All processes sharing the file must be trusted. The contents are validated
+//! upon resolution so that a corrupted file cannot cause out of bounds accesses,
+//! however, a malicious process may still concurrently modify published strings.
+
+#![cfg(all(feature = "shared-memory", target_os = "linux"))]
+
+use crate::{
+    DefaultSymbol,
+    Symbol,
+};
+use core::{
+    marker::PhantomData,
+    mem,
+    ptr::NonNull,
+    slice,
+    str,
+    sync::atomic::{
+        AtomicU64,
+        Ordering,
+    },
+};
+use std::{
+    fs::{
+        File,
+        OpenOptions,
+    },
+    io,
+    os::unix::io::AsRawFd,
+    path::Path,
+};
+
+/// Identifies files created by [`SharedInterner::create`].
+const MAGIC: u64 = u64::from_le_bytes(*b"STRINTRN");
+
+/// The version of the file layout.
+const VERSION: u64 = 1;
+
+/// The header at the start of every shared interner file.
+#[repr(C)]
+struct Header {
+    /// Written last upon creation to signal an initialized file.
+    magic: AtomicU64,
+    version: AtomicU64,
+    /// The maximum number of strings the file can hold.
+    max_strings: AtomicU64,
+    /// The number of hash index slots, always a power of two.
+    table_len: AtomicU64,
+    /// The maximum number of string bytes the file can hold.
+    max_bytes: AtomicU64,
+    /// The number of published strings.
+    len_strings: AtomicU64,
+    /// The number of used string bytes.
+    len_bytes: AtomicU64,
+    _reserved: AtomicU64,
+}
+
+/// The byte offsets of the regions of a shared interner file.
+#[derive(Debug, Copy, Clone)]
+struct Layout {
+    max_strings: usize,
+    table_len: usize,
+    max_bytes: usize,
+    ends: usize,
+    table: usize,
+    bytes: usize,
+    file_len: usize,
+}
+
+impl Layout {
+    /// Computes the layout for the given capacities if it fits into memory.
+    fn new(max_strings: usize, table_len: usize, max_bytes: usize) -> Option<Self> {
+        const WORD: usize = mem::size_of::<AtomicU64>();
+        let ends = mem::size_of::<Header>();
+        let table = ends.checked_add(max_strings.checked_mul(WORD)?)?;
+        let bytes = table.checked_add(table_len.checked_mul(WORD)?)?;
+        let file_len = bytes.checked_add(max_bytes)?;
+        Some(Self {
+            max_strings,
+            table_len,
+            max_bytes,
+            ends,
+            table,
+            bytes,
+            file_len,
+        })
+    }
+
+    /// Computes the layout for a new file with the given capacities.
+    fn for_capacity(max_strings: usize, max_bytes: usize) -> Option<Self> {
+        // Keeps the load factor of the hash index at or below 50%.
+        let table_len = max_strings.checked_mul(2)?.checked_next_power_of_two()?;
+        Self::new(max_strings, table_len.max(2), max_bytes)
+    }
+}
+
+/// Guard holding an exclusive `flock` on a file until dropped.
+struct FileLock<'a> {
+    file: &'a File,
+}
+
+impl<'a> FileLock<'a> {
+    /// Blocks until the exclusive lock on the file is acquired.
+    fn exclusive(file: &'a File) -> io::Result<Self> {
+        loop {
+            // SAFETY: `flock` has no memory safety preconditions.
+            if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX) } == 0 {
+                return Ok(Self { file })
+            }
+            let error = io::Error::last_os_error();
+            if error.kind() != io::ErrorKind::Interrupted {
+                return Err(error)
+            }
+        }
+    }
+}
+
+impl<'a> Drop for FileLock<'a> {
+    fn drop(&mut self) {
+        // SAFETY: `flock` has no memory safety preconditions.
+        unsafe { libc::flock(self.file.as_raw_fd(), libc::LOCK_UN) };
+    }
+}
+
+/// A shared `MAP_SHARED` mapping of a whole file.
+struct Mapping {
+    ptr: NonNull<u8>,
+    len: usize,
+}
+
+impl Mapping {
+    /// Maps the first `len` bytes of the file into memory.
+    fn new(file: &File, len: usize) -> io::Result<Self> {
+        // SAFETY: We request a fresh mapping and let the kernel pick its address.
+        let ptr = unsafe {
+            libc::mmap(
+                core::ptr::null_mut(),
+                len,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_SHARED,
+                file.as_raw_fd(),
+                0,
+            )
+        };
+        if ptr == libc::MAP_FAILED {
+            return Err(io::Error::last_os_error())
+        }
+        let ptr = NonNull::new(ptr.cast::<u8>())
+            .ok_or_else(|| io::Error::other("encountered null memory mapping"))?;
+        Ok(Self { ptr, len })
+    }
+}
+
+impl Drop for Mapping {
+    fn drop(&mut self) {
+        // SAFETY: The mapping was created by `mmap` with the same address and length.
+        unsafe { libc::munmap(self.ptr.as_ptr().cast(), self.len) };
+    }
+}
+
+/// A string interner whose strings and hash index live in a shared memory-mapped file.
+///
+/// Every process opening the same file observes the same symbols for the same
+/// strings. Interning is coordinated across processes via a file lock whereas
+/// look-ups and resolution are lock-free.
+///
+/// # Note
+///
+/// Symbols are contiguous and the capacity of the file is fixed upon creation.
+///
+/// # Example
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use string_interner::shared::SharedInterner;
+///
+/// let mut writer = <SharedInterner>::create("/tmp/symbols", 1024, 64 * 1024)?;
+/// let reader = <SharedInterner>::open("/tmp/symbols")?;
+/// let symbol = writer.get_or_intern("Tiger")?;
+/// assert_eq!(reader.resolve(symbol), Some("Tiger"));
+/// # Ok(())
+/// # }
+/// ```
+pub struct SharedInterner<S = DefaultSymbol> {
+    file: File,
+    mapping: Mapping,
+    layout: Layout,
+    marker: PhantomData<fn() -> S>,
+}
+
+/// # Safety
+///
+/// The shared interner only holds a file and a memory mapping that both may
+/// be used from any thread.
+unsafe impl<S> Send for SharedInterner<S> {}
+
+/// # Safety
+///
+/// All `&self` methods of the shared interner only perform atomic loads or
+/// read published bytes that are never written again. Mutations require
+/// `&mut self`.
+unsafe impl<S> Sync for SharedInterner<S> {}
+
+impl<S> core::fmt::Debug for SharedInterner<S>
+where
+    S: Symbol,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("SharedInterner")
+            .field("len", &self.len())
+            .field("max_strings", &self.layout.max_strings)
+            .field("max_bytes", &self.layout.max_bytes)
+            .finish()
+    }
+}
+
+impl<S> SharedInterner<S>
+where
+    S: Symbol,
+{
+    /// Creates a new shared interner file at `path`.
+    ///
+    /// The file is able to hold up to `max_strings` strings with a total of
+    /// up to `max_bytes` bytes.
+    ///
+    /// # Errors
+    ///
+    /// - If a file already exists at `path`.
+    /// - If the capacities exceed the address space.
+    /// - If creating or mapping the file fails.
+    pub fn create<P>(path: P, max_strings: usize, max_bytes: usize) -> io::Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let layout = Layout::for_capacity(max_strings, max_bytes).ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, "capacity out of bounds")
+        })?;
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create_new(true)
+            .open(path)?;
+        file.set_len(layout.file_len as u64)?;
+        let mapping = Mapping::new(&file, layout.file_len)?;
+        let interner = Self {
+            file,
+            mapping,
+            layout,
+            marker: PhantomData,
+        };
+        let header = interner.header();
+        header.version.store(VERSION, Ordering::Relaxed);
+        header.max_strings.store(max_strings as u64, Ordering::Relaxed);
+        header.table_len.store(layout.table_len as u64, Ordering::Relaxed);
+        header.max_bytes.store(max_bytes as u64, Ordering::Relaxed);
+        header.magic.store(MAGIC, Ordering::Release);
+        Ok(interner)
+    }
+
+    /// Opens the existing shared interner file at `path`.
+    ///
+    /// # Errors
+    ///
+    /// - If the file is not a fully initialized shared interner file.
+    /// - If opening or mapping the file fails.
+    pub fn open<P>(path: P) -> io::Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let invalid = |message| io::Error::new(io::ErrorKind::InvalidData, message);
+        let file = OpenOptions::new().read(true).write(true).open(path)?;
+        let file_len = usize::try_from(file.metadata()?.len())
+            .map_err(|_| invalid("file length out of bounds"))?;
+        if file_len < mem::size_of::<Header>() {
+            return Err(invalid("missing shared interner header"))
+        }
+        let mapping = Mapping::new(&file, file_len)?;
+        // SAFETY: The mapping is page aligned and large enough to hold the header.
+        let header = unsafe { &*mapping.ptr.as_ptr().cast::<Header>() };
+        if header.magic.load(Ordering::Acquire) != MAGIC {
+            return Err(invalid("missing shared interner magic number"))
+        }
+        if header.version.load(Ordering::Relaxed) != VERSION {
+            return Err(invalid("unsupported shared interner version"))
+        }
+        let field = |value: &AtomicU64| usize::try_from(value.load(Ordering::Relaxed));
+        let layout = match (
+            field(&header.max_strings),
+            field(&header.table_len),
+            field(&header.max_bytes),
+        ) {
+            (Ok(max_strings), Ok(table_len), Ok(max_bytes))
+                if table_len.is_power_of_two() && table_len > max_strings =>
+            {
+                Layout::new(max_strings, table_len, max_bytes)
+            }
+            _ => None,
+        };
+        match layout {
+            Some(layout) if layout.file_len == file_len => {
+                Ok(Self {
+                    file,
+                    mapping,
+                    layout,
+                    marker: PhantomData,
+                })
+            }
+            _ => Err(invalid("inconsistent shared interner header")),
+        }
+    }
+
+    /// Returns the header of the file.
+    fn header(&self) -> &Header {
+        // SAFETY: The mapping is page aligned and large enough to hold the header.
+        unsafe { &*self.mapping.ptr.as_ptr().cast::<Header>() }
+    }
+
+    /// Returns the atomic words of the region starting at `offset`.
+    fn words(&self, offset: usize, len: usize) -> &[AtomicU64] {
+        // SAFETY: The layout guarantees that the region is within the mapping
+        //         and 8-byte aligned since the header and all words are.
+        unsafe {
+            slice::from_raw_parts(
+                self.mapping.ptr.as_ptr().add(offset).cast::<AtomicU64>(),
+                len,
+            )
+        }
+    }
+
+    /// Returns the end offsets of all strings.
+    fn ends(&self) -> &[AtomicU64] {
+        self.words(self.layout.ends, self.layout.max_strings)
+    }
+
+    /// Returns the slots of the hash index.
+    fn table(&self) -> &[AtomicU64] {
+        self.words(self.layout.table, self.layout.table_len)
+    }
+
+    /// Returns the number of strings interned by all processes so far.
+    pub fn len(&self) -> usize {
+        self.header().len_strings.load(Ordering::Acquire) as usize
+    }
+
+    /// Returns `true` if no process has interned a string so far.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Resolves the string at the given index if it has been published.
+    fn resolve_index(&self, index: usize) -> Option<&str> {
+        if index >= self.len().min(self.layout.max_strings) {
+            return None
+        }
+        let ends = self.ends();
+        let to = ends[index].load(Ordering::Relaxed);
+        let from = match index.checked_sub(1) {
+            Some(prev) => ends[prev].load(Ordering::Relaxed),
+            None => 0,
+        };
+        if from > to || to > self.layout.max_bytes as u64 {
+            return None
+        }
+        // SAFETY: The bytes in `from..to` are within the bytes region and have
+        //         been published before the string count we observed above.
+        //         Published bytes are never written again.
+        let bytes = unsafe {
+            slice::from_raw_parts(
+                self.mapping
+                    .ptr
+                    .as_ptr()
+                    .add(self.layout.bytes + from as usize),
+                (to - from) as usize,
+            )
+        };
+        str::from_utf8(bytes).ok()
+    }
+
+    /// Returns the index of the given string if it has been published.
+    ///
+    /// Also returns the position of the hash index slot where the search ended.
+    fn find(&self, string: &str, hash: u64) -> (Option<usize>, usize) {
+        let table = self.table();
+        let mask = self.layout.table_len - 1;
+        let mut pos = hash as usize & mask;
+        for _ in 0..self.layout.table_len {
+            let slot = table[pos].load(Ordering::Acquire);
+            if slot == 0 {
+                return (None, pos)
+            }
+            let index = (slot - 1) as usize;
+            if self.resolve_index(index) == Some(string) {
+                return (Some(index), pos)
+            }
+            pos = (pos + 1) & mask;
+        }
+        (None, pos)
+    }
+
+    /// Returns the symbol for the given string if any process interned it.
+    pub fn get<T>(&self, string: T) -> Option<S>
+    where
+        T: AsRef<str>,
+    {
+        let string = string.as_ref();
+        self.find(string, hash_str(string))
+            .0
+            .and_then(S::try_from_usize)
+    }
+
+    /// Returns the string for the given symbol if any process interned it.
+    pub fn resolve(&self, symbol: S) -> Option<&str> {
+        self.resolve_index(symbol.to_usize())
+    }
+
+    /// Interns the given string and returns its symbol.
+    ///
+    /// # Errors
+    ///
+    /// - If the file cannot hold another string or the string's bytes.
+    /// - If the symbol type cannot represent the symbol of the string.
+    /// - If locking the file fails.
+    pub fn get_or_intern<T>(&mut self, string: T) -> io::Result<S>
+    where
+        T: AsRef<str>,
+    {
+        let string = string.as_ref();
+        let hash = hash_str(string);
+        if let (Some(index), _) = self.find(string, hash) {
+            return to_symbol(index)
+        }
+        let _lock = FileLock::exclusive(&self.file)?;
+        // Another process might have interned the string before we got the lock.
+        let (found, pos) = self.find(string, hash);
+        if let Some(index) = found {
+            return to_symbol(index)
+        }
+        let header = self.header();
+        let index = header.len_strings.load(Ordering::Relaxed) as usize;
+        if index >= self.layout.max_strings {
+            return Err(io::Error::other("shared interner ran out of strings"))
+        }
+        if self.table()[pos].load(Ordering::Relaxed) != 0 {
+            return Err(io::Error::other("shared interner hash index is full"))
+        }
+        let from = header.len_bytes.load(Ordering::Relaxed) as usize;
+        let to = from
+            .checked_add(string.len())
+            .filter(|&to| to <= self.layout.max_bytes)
+            .ok_or_else(|| io::Error::other("shared interner ran out of bytes"))?;
+        let symbol = to_symbol(index)?;
+        // SAFETY: `from..to` is within the bytes region and not yet published,
+        //         we hold the exclusive lock so no other writer accesses it.
+        unsafe {
+            core::ptr::copy_nonoverlapping(
+                string.as_ptr(),
+                self.mapping.ptr.as_ptr().add(self.layout.bytes + from),
+                string.len(),
+            );
+        }
+        self.ends()[index].store(to as u64, Ordering::Relaxed);
+        header.len_bytes.store(to as u64, Ordering::Relaxed);
+        header
+            .len_strings
+            .store(index as u64 + 1, Ordering::Release);
+        self.table()[pos].store(index as u64 + 1, Ordering::Release);
+        Ok(symbol)
+    }
+}
+
+/// Converts the index into a symbol or returns an error.
+fn to_symbol<S>(index: usize) -> io::Result<S>
+where
+    S: Symbol,
+{
+    S::try_from_usize(index).ok_or_else(|| io::Error::other("encountered invalid symbol"))
+}
+
+/// Hashes the string with 64-bit FNV-1a.
+///
+/// # Note
+///
+/// The hash must be identical in all processes so randomly seeded
+/// hashers cannot be used here.
+fn hash_str(string: &str) -> u64 {
+    const OFFSET_BASIS: u64 = 0xCBF2_9CE4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01B3;
+    string.bytes().fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(PRIME)
+    })
+}
@@ -4,7 +4,13 @@
 //! The [`StringInterner::get_or_intern`](`crate::StringInterner::get_or_intern`)
 //! method returns `Symbol` types that allow to look-up the original string
 //! using [`StringInterner::resolve`](`crate::StringInterner::resolve`).
+//!
+//! With the `serde-1` crate feature the built-in symbols are serialized as
+//! their `usize` value. See [`serde::as_usize`](`crate::serde::as_usize`)
+//! for user-defined symbols.
 
+#[cfg(feature = "backends")]
+use crate::TryReserveError;
 use core::num::{
     NonZeroU16,
     NonZeroU32,
@@ -44,6 +50,19 @@ where
     S::try_from_usize(index).expect("encountered invalid symbol")
 }
 
+/// Creates the symbol `S` from the given `usize` if it is valid.
+///
+/// Returns [`TryReserveError::CapacityOverflow`] if the symbols of `S` are
+/// exhausted.
+#[cfg(feature = "backends")]
+#[inline]
+pub(crate) fn try_valid_symbol<S>(index: usize) -> Result<S, TryReserveError>
+where
+    S: Symbol,
+{
+    S::try_from_usize(index).ok_or(TryReserveError::CapacityOverflow)
+}
+
 /// The symbol type that is used by default.
 pub type DefaultSymbol = SymbolU32;
 
@@ -73,7 +92,10 @@ macro_rules! gen_symbol_for {
         impl Symbol for $name {
             #[inline]
             fn try_from_usize(index: usize) -> Option<Self> {
-                <$non_zero>::new((index as $base_ty).wrapping_add(1))
+                <$base_ty>::try_from(index)
+                    .ok()?
+                    .checked_add(1)
+                    .and_then(<$non_zero>::new)
                     .map(|value| Self { value })
             }
 
@@ -136,6 +158,7 @@ mod tests {
             })
         );
         assert_eq!(SymbolU16::try_from_usize(u16::MAX as usize), None);
+        assert_eq!(SymbolU16::try_from_usize(u16::MAX as usize + 1), None);
         assert_eq!(SymbolU16::try_from_usize(usize::MAX), None);
     }
 
@@ -174,4 +197,27 @@ mod tests {
         try_from_usize_works_for_usize:
         struct SymbolUsize(NonZeroUsize; usize);
     );
+
+    #[test]
+    #[cfg(feature = "serde-1")]
+    fn serde_works() {
+        let symbol = SymbolU32::try_from_usize(42).unwrap();
+        assert_eq!(serde_json::to_string(&symbol).unwrap(), "42");
+        assert_eq!(serde_json::from_str::<SymbolU32>("42").unwrap(), symbol);
+        let symbol = SymbolUsize::try_from_usize(0).unwrap();
+        assert_eq!(serde_json::to_string(&symbol).unwrap(), "0");
+        assert_eq!(serde_json::from_str::<SymbolUsize>("0").unwrap(), symbol);
+    }
+
+    #[test]
+    #[cfg(feature = "serde-1")]
+    fn serde_invalid_fails() {
+        // The maximum value of the base type is reserved for the niche.
+        assert!(serde_json::from_str::<SymbolU16>("65535").is_err());
+        assert!(serde_json::from_str::<SymbolU16>("65536").is_err());
+        assert!(serde_json::from_str::<SymbolU16>("-1").is_err());
+        assert!(serde_json::from_str::<SymbolU32>("4294967295").is_err());
+        assert!(serde_json::from_str::<SymbolUsize>("18446744073709551615").is_err());
+        assert!(serde_json::from_str::<SymbolU16>("65534").is_ok());
+    }
 }
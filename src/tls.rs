@@ -0,0 +1,152 @@
+//! Per-thread string interners declared via [`thread_local_interner!`].
+//!
+//! [`thread_local_interner!`]: crate::thread_local_interner
+
+use crate::{
+    backend::Backend,
+    compat::Allocator,
+    InternError,
+    StringInterner,
+    Symbol,
+};
+use core::{
+    cell::RefCell,
+    fmt,
+    hash::BuildHasher,
+};
+use std::thread::LocalKey;
+
+/// Declares thread-local string interners.
+///
+/// Every thread lazily initializes its own interner from the given expression
+/// on first access. The interner is accessed within the scope of a closure via
+/// [`ThreadLocalInterner::with`].
+///
+/// Symbols are only valid for the interner of the thread that created them.
+/// Use a [`SymbolTranslator`](crate::SymbolTranslator) to convert symbols between
+/// the interners of different threads.
+///
+/// # Example
+///
+/// ```
+/// use string_interner::{thread_local_interner, StringInterner};
+///
+/// thread_local_interner! {
+///     static INTERNER: StringInterner = StringInterner::new();
+/// }
+///
+/// let tiger = INTERNER.get_or_intern("Tiger");
+/// assert_eq!(INTERNER.get("Tiger"), Some(tiger));
+/// INTERNER.with(|interner| {
+///     assert_eq!(interner.resolve(tiger), Some("Tiger"));
+/// });
+/// ```
+#[macro_export]
+macro_rules! thread_local_interner {
+    () => {};
+    (
+        $(#[$attr:meta])*
+        $vis:vis static $name:ident: $ty:ty = $init:expr;
+        $($rest:tt)*
+    ) => {
+        $(#[$attr])*
+        $vis static $name: $crate::ThreadLocalInterner<$ty> = {
+            ::std::thread_local! {
+                static INTERNER: ::core::cell::RefCell<$ty> =
+                    ::core::cell::RefCell::new($init);
+            }
+            $crate::ThreadLocalInterner::new(&INTERNER)
+        };
+        $crate::thread_local_interner!($($rest)*);
+    };
+}
+
+/// A thread-local interner declared via [`thread_local_interner!`].
+///
+/// # Note
+///
+/// Accessing the interner again from within [`ThreadLocalInterner::with`]
+/// panics since the interner is already borrowed.
+///
+/// [`thread_local_interner!`]: crate::thread_local_interner
+pub struct ThreadLocalInterner<T: 'static> {
+    key: &'static LocalKey<RefCell<T>>,
+}
+
+impl<T> fmt::Debug for ThreadLocalInterner<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ThreadLocalInterner").finish_non_exhaustive()
+    }
+}
+
+impl<T> ThreadLocalInterner<T> {
+    /// Creates a new thread-local interner from the given key.
+    ///
+    /// Usually called by [`thread_local_interner!`](crate::thread_local_interner).
+    #[inline]
+    pub const fn new(key: &'static LocalKey<RefCell<T>>) -> Self {
+        Self { key }
+    }
+
+    /// Calls `f` with the interner of the current thread.
+    ///
+    /// # Panics
+    ///
+    /// If the interner is already accessed by the current thread or if the
+    /// thread-local storage has been destroyed.
+    #[inline]
+    pub fn with<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce(&mut T) -> R,
+    {
+        self.key.with(|interner| f(&mut interner.borrow_mut()))
+    }
+}
+
+impl<B, H, A> ThreadLocalInterner<StringInterner<B, H, A>>
+where
+    B: Backend,
+    <B as Backend>::Symbol: Symbol,
+    H: BuildHasher,
+    A: Allocator,
+{
+    /// Returns the symbol for the given string in the interner of the current
+    /// thread if any.
+    #[inline]
+    pub fn get<T>(&self, string: T) -> Option<<B as Backend>::Symbol>
+    where
+        T: AsRef<str>,
+    {
+        self.with(|interner| interner.get(string))
+    }
+
+    /// Interns the given string into the interner of the current thread.
+    ///
+    /// # Panics
+    ///
+    /// If interning the string fails, see [`StringInterner::get_or_intern`].
+    /// Use [`ThreadLocalInterner::try_get_or_intern`] to handle this case.
+    #[inline]
+    pub fn get_or_intern<T>(&self, string: T) -> <B as Backend>::Symbol
+    where
+        T: AsRef<str>,
+    {
+        self.with(|interner| interner.get_or_intern(string))
+    }
+
+    /// Tries to intern the given string into the interner of the current thread.
+    ///
+    /// # Errors
+    ///
+    /// If interning the string fails, see [`StringInterner::try_get_or_intern`].
+    #[inline]
+    pub fn try_get_or_intern<T>(
+        &self,
+        string: T,
+    ) -> Result<<B as Backend>::Symbol, InternError>
+    where
+        T: AsRef<str>,
+    {
+        self.with(|interner| interner.try_get_or_intern(string))
+    }
+}
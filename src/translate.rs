@@ -0,0 +1,191 @@
+//! Memoized translation of symbols between two string interners.
+
+use crate::{
+    backend::{
+        Backend,
+        ContiguousBackend,
+    },
+    compat::{
+        Allocator,
+        Vec,
+    },
+    DefaultSymbol,
+    InternError,
+    StringInterner,
+    Symbol,
+};
+use core::{
+    hash::BuildHasher,
+    marker::PhantomData,
+};
+
+/// Lazily translates the symbols of one [`StringInterner`] into the symbols
+/// of another.
+///
+/// Translated symbols are memoized so that translating a symbol again only
+/// takes a lookup instead of resolving and interning its string. This allows
+/// to exchange symbols between interners, for example of different threads,
+/// without merging the interners.
+///
+/// # Note
+///
+/// A translator must always be used with the same pair of interners since its
+/// memoized symbols are only valid for them. Symbols stay valid since string
+/// interners never remove strings.
+///
+/// The memoized symbols are stored in a vector indexed by the symbols of the
+/// interner they are translated from which therefore requires a
+/// [`ContiguousBackend`]. Symbols of other backends such as the
+/// [`BufferBackend`](crate::backend::BufferBackend) are byte offsets that would
+/// grow the vector with the total length of the strings.
+///
+/// # Example
+///
+/// ```
+/// use string_interner::{StringInterner, SymbolTranslator};
+///
+/// let mut from = <StringInterner>::new();
+/// let mut to = <StringInterner>::new();
+/// to.get_or_intern("Horse");
+/// let tiger = from.get_or_intern("Tiger");
+/// let mut translator = <SymbolTranslator>::new();
+/// let translated = translator.translate(tiger, &from, &mut to).unwrap();
+/// assert_eq!(to.resolve(translated), Some("Tiger"));
+/// assert_eq!(translator.get(tiger), Some(translated));
+/// ```
+///
+/// Symbols of backends that are not contiguous cannot be translated:
+///
+/// ```compile_fail
+/// use string_interner::{backend::BufferBackend, StringInterner, SymbolTranslator};
+///
+/// let mut from = StringInterner::<BufferBackend>::new();
+/// let mut to = <StringInterner>::new();
+/// let tiger = from.get_or_intern("Tiger");
+/// <SymbolTranslator>::new().translate(tiger, &from, &mut to);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SymbolTranslator<S1 = DefaultSymbol, S2 = DefaultSymbol>
+where
+    S1: Symbol,
+    S2: Symbol,
+{
+    /// The translated symbols indexed by the symbols they are translated from.
+    translated: Vec<Option<S2>>,
+    marker: PhantomData<fn() -> S1>,
+}
+
+impl<S1, S2> Default for SymbolTranslator<S1, S2>
+where
+    S1: Symbol,
+    S2: Symbol,
+{
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S1, S2> SymbolTranslator<S1, S2>
+where
+    S1: Symbol,
+    S2: Symbol,
+{
+    /// Creates a new translator without memoized symbols.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            translated: Vec::new(),
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns the memoized translation of the symbol if any.
+    #[inline]
+    pub fn get(&self, symbol: S1) -> Option<S2> {
+        self.translated.get(symbol.to_usize()).copied().flatten()
+    }
+
+    /// Translates the symbol of `from` into the symbol of its string in `to`.
+    ///
+    /// Interns the string into `to` if it has not been translated before.
+    /// Returns `None` if the symbol cannot be resolved by `from`.
+    ///
+    /// # Panics
+    ///
+    /// If interning the string into `to` fails, see [`StringInterner::get_or_intern`].
+    /// Use [`SymbolTranslator::try_translate`] to handle this case.
+    pub fn translate<B1, H1, A1, B2, H2, A2>(
+        &mut self,
+        symbol: S1,
+        from: &StringInterner<B1, H1, A1>,
+        to: &mut StringInterner<B2, H2, A2>,
+    ) -> Option<S2>
+    where
+        B1: ContiguousBackend<Symbol = S1>,
+        H1: BuildHasher,
+        A1: Allocator,
+        B2: Backend<Symbol = S2>,
+        H2: BuildHasher,
+        A2: Allocator,
+    {
+        if let Some(translated) = self.get(symbol) {
+            return Some(translated)
+        }
+        let translated = to.get_or_intern(from.resolve(symbol)?);
+        self.memoize(symbol, translated);
+        Some(translated)
+    }
+
+    /// Tries to translate the symbol of `from` into the symbol of its string in `to`.
+    ///
+    /// Interns the string into `to` if it has not been translated before.
+    /// Returns `Ok(None)` if the symbol cannot be resolved by `from`.
+    ///
+    /// # Errors
+    ///
+    /// If interning the string into `to` fails, see
+    /// [`StringInterner::try_get_or_intern`].
+    pub fn try_translate<B1, H1, A1, B2, H2, A2>(
+        &mut self,
+        symbol: S1,
+        from: &StringInterner<B1, H1, A1>,
+        to: &mut StringInterner<B2, H2, A2>,
+    ) -> Result<Option<S2>, InternError>
+    where
+        B1: ContiguousBackend<Symbol = S1>,
+        H1: BuildHasher,
+        A1: Allocator,
+        B2: Backend<Symbol = S2>,
+        H2: BuildHasher,
+        A2: Allocator,
+    {
+        if let Some(translated) = self.get(symbol) {
+            return Ok(Some(translated))
+        }
+        let string = match from.resolve(symbol) {
+            Some(string) => string,
+            None => return Ok(None),
+        };
+        let translated = to.try_get_or_intern(string)?;
+        self.memoize(symbol, translated);
+        Ok(Some(translated))
+    }
+
+    /// Memoizes the translation of the symbol.
+    fn memoize(&mut self, symbol: S1, translated: S2) {
+        let index = symbol.to_usize();
+        if index >= self.translated.len() {
+            self.translated.resize(index + 1, None);
+        }
+        self.translated[index] = Some(translated);
+    }
+
+    /// Forgets all memoized symbols.
+    ///
+    /// Required before using the translator with a different pair of interners.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.translated.clear();
+    }
+}
@@ -0,0 +1,199 @@
+//! String interner that holds its strings weakly for self-cleaning caches.
+//!
+//! The [`WeakInterner`] hands out strong `Arc<str>` handles and only keeps weak
+//! references to the strings itself. Entries of strings whose handles are all
+//! dropped are removed, and their memory reclaimed, by [`WeakInterner::purge`].
+
+use crate::{
+    compat::{
+        Arc,
+        DefaultHashBuilder,
+        HashTable,
+        Weak,
+    },
+    interner::make_hash,
+};
+use core::{
+    fmt,
+    hash::BuildHasher,
+};
+
+/// An entry of the table of a [`WeakInterner`].
+struct Entry {
+    /// The cached hash of the string so that the table can be rehashed
+    /// without upgrading strings that might have been released.
+    hash: u64,
+    string: Weak<str>,
+}
+
+impl Entry {
+    /// Returns `true` if all handles to the string of the entry have been dropped.
+    fn is_dead(&self) -> bool {
+        self.string.strong_count() == 0
+    }
+}
+
+/// String interner that holds its strings weakly.
+///
+/// Interning returns strong `Arc<str>` handles that keep the string alive.
+/// The interner itself only holds weak references so that strings are no longer
+/// resolved once all their handles are dropped.
+///
+/// # Note
+///
+/// Since the bytes of an `Arc<str>` share its allocation with the reference
+/// counts, the weak reference of a table entry keeps the memory of its string
+/// allocated until the entry is removed by [`WeakInterner::purge`] or replaced
+/// by interning the string again.
+///
+/// # Example
+///
+/// ```
+/// use std::sync::Arc;
+/// use string_interner::WeakInterner;
+///
+/// let mut interner = <WeakInterner>::new();
+/// let tiger = interner.get_or_intern("Tiger");
+/// assert!(Arc::ptr_eq(&tiger, &interner.get_or_intern("Tiger")));
+/// drop(tiger);
+/// assert_eq!(interner.get("Tiger"), None);
+/// assert_eq!(interner.purge(), 1);
+/// assert!(interner.is_empty());
+/// ```
+pub struct WeakInterner<H = DefaultHashBuilder>
+where
+    H: BuildHasher,
+{
+    entries: HashTable<Entry>,
+    hasher: H,
+}
+
+impl<H> fmt::Debug for WeakInterner<H>
+where
+    H: BuildHasher,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WeakInterner")
+            .field("len", &self.len())
+            .finish()
+    }
+}
+
+impl<H> Default for WeakInterner<H>
+where
+    H: BuildHasher + Default,
+{
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<H> WeakInterner<H>
+where
+    H: BuildHasher + Default,
+{
+    /// Creates a new empty `WeakInterner`.
+    #[inline]
+    pub fn new() -> Self {
+        Self::with_hasher(H::default())
+    }
+}
+
+impl<H> WeakInterner<H>
+where
+    H: BuildHasher,
+{
+    /// Creates a new empty `WeakInterner` with the given hasher.
+    #[inline]
+    pub fn with_hasher(hash_builder: H) -> Self {
+        Self {
+            entries: HashTable::new(),
+            hasher: hash_builder,
+        }
+    }
+
+    /// Returns the number of table entries of the interner.
+    ///
+    /// # Note
+    ///
+    /// This includes entries of strings whose handles have all been dropped
+    /// until they are removed by [`WeakInterner::purge`].
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the interner has no table entries.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns a handle to the given string if it is interned and alive.
+    pub fn get<T>(&self, string: T) -> Option<Arc<str>>
+    where
+        T: AsRef<str>,
+    {
+        let string = string.as_ref();
+        self.get_hashed(make_hash(&self.hasher, string), string)
+    }
+
+    /// Returns a handle to the string with the given hash if it is interned and alive.
+    ///
+    /// Upgrades the weak reference of every probed entry only once.
+    fn get_hashed(&self, hash: u64, string: &str) -> Option<Arc<str>> {
+        let mut found = None;
+        self.entries.find(hash, |entry| {
+            found = entry.string.upgrade().filter(|alive| &**alive == string);
+            found.is_some()
+        });
+        found
+    }
+
+    /// Interns the given string and returns a handle to it.
+    ///
+    /// Interns the string again if its handles have all been dropped. The new
+    /// string replaces a dead entry with the same hash in place if there is one.
+    pub fn get_or_intern<T>(&mut self, string: T) -> Arc<str>
+    where
+        T: AsRef<str>,
+    {
+        let string = string.as_ref();
+        let hash = make_hash(&self.hasher, string);
+        if let Some(alive) = self.get_hashed(hash, string) {
+            return alive
+        }
+        let alive = Arc::<str>::from(string);
+        let weak = Arc::downgrade(&alive);
+        match self
+            .entries
+            .find_mut(hash, |entry| entry.hash == hash && entry.is_dead())
+        {
+            Some(dead) => dead.string = weak,
+            None => {
+                let entry = Entry { hash, string: weak };
+                self.entries.insert_unique(hash, entry, |entry| entry.hash);
+            }
+        }
+        alive
+    }
+
+    /// Removes the table entries of strings whose handles have all been dropped.
+    ///
+    /// This releases the memory of their strings. Returns the number of removed entries.
+    pub fn purge(&mut self) -> usize {
+        let len = self.len();
+        self.entries.retain(|entry| !entry.is_dead());
+        len - self.len()
+    }
+
+    /// Shrinks the capacity of the table to fit its entries.
+    ///
+    /// # Note
+    ///
+    /// Call this after [`WeakInterner::purge`] to release the memory of the table.
+    pub fn shrink_to_fit(&mut self) {
+        self.entries.shrink_to_fit(|entry| entry.hash);
+    }
+}
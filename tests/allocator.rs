@@ -41,11 +41,16 @@ impl TracingAllocator {
     }
 }
 
+impl Default for TracingAllocator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 unsafe impl GlobalAlloc for TracingAllocator {
     unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
         self.stats.push_allocations(layout);
-        let res = self.inner.alloc(layout);
-        res
+        self.inner.alloc(layout)
     }
 
     unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
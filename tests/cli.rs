@@ -0,0 +1,155 @@
+#![cfg(feature = "cli")]
+
+use std::{
+    path::PathBuf,
+    process::{
+        Command,
+        Output,
+    },
+    sync::atomic::{
+        AtomicUsize,
+        Ordering,
+    },
+};
+
+/// Temporary file path that is removed when dropped.
+struct TempPath(PathBuf);
+
+impl TempPath {
+    /// Returns a new unique temporary file path.
+    fn new() -> Self {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+        let name = format!(
+            "string-interner-cli-{}-{}.json",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::SeqCst)
+        );
+        Self(std::env::temp_dir().join(name))
+    }
+
+    /// Returns a new temporary file with the given contents.
+    fn with_contents(contents: &str) -> Self {
+        let path = Self::new();
+        std::fs::write(&path.0, contents).unwrap();
+        path
+    }
+
+    fn as_str(&self) -> &str {
+        self.0.to_str().unwrap()
+    }
+}
+
+impl Drop for TempPath {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+/// Runs the command-line tool with the given arguments.
+fn run(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_string-interner"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+/// Runs the command-line tool and returns its standard output on success.
+fn run_ok(args: &[&str]) -> String {
+    let output = run(args);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn stats_works() {
+    let file = TempPath::with_contents(r#"["", "a", "bb", "ccc", "dddd"]"#);
+    let stats = run_ok(&["stats", file.as_str()]);
+    assert!(stats.contains("strings: 5"));
+    assert!(stats.contains("bytes:   10"));
+    assert!(stats.contains("heap:    "));
+    assert!(stats.contains("2..4      2"));
+}
+
+#[test]
+fn lookup_and_resolve_works() {
+    let file = TempPath::with_contents(r#"["aa", "bb"]"#);
+    assert_eq!(run_ok(&["lookup", file.as_str(), "bb"]), "1\n");
+    assert_eq!(run_ok(&["resolve", file.as_str(), "0"]), "aa\n");
+    assert_eq!(
+        run_ok(&["--backend", "buffer", "lookup", file.as_str(), "bb"]),
+        "3\n"
+    );
+    assert!(!run(&["lookup", file.as_str(), "cc"]).status.success());
+    assert!(!run(&["resolve", file.as_str(), "2"]).status.success());
+}
+
+#[test]
+fn dump_works() {
+    let file = TempPath::with_contents(r#"["aa", "b\nb"]"#);
+    assert_eq!(run_ok(&["dump", file.as_str()]), "0\taa\n1\tb\\nb\n");
+}
+
+#[test]
+fn diff_works() {
+    let lhs = TempPath::with_contents(r#"["aa", "bb", "cc"]"#);
+    let rhs = TempPath::with_contents(r#"["aa", "cc", "dd"]"#);
+    assert_eq!(
+        run_ok(&["diff", lhs.as_str(), rhs.as_str()]),
+        "- 1\t\"bb\"\n~ 2 -> 1\t\"cc\"\n+ 2\t\"dd\"\n"
+    );
+}
+
+#[test]
+fn convert_works() {
+    let input = TempPath::with_contents(r#"["aa", "bb", "aa", "cc"]"#);
+    let buffer = TempPath::new();
+    run_ok(&["convert", "--backend", "buffer", input.as_str(), buffer.as_str()]);
+    assert_eq!(
+        std::fs::read_to_string(&buffer.0).unwrap(),
+        r#"[[0,"aa"],[3,"bb"],[6,"cc"]]"#
+    );
+    // The symbols of converted files are verified against the backend.
+    assert_eq!(
+        run_ok(&["--backend", "buffer", "lookup", buffer.as_str(), "cc"]),
+        "6\n"
+    );
+    assert_eq!(run(&["lookup", buffer.as_str(), "cc"]).status.code(), Some(1));
+    let string = TempPath::new();
+    run_ok(&[
+        "convert",
+        "--from",
+        "buffer",
+        "--backend",
+        "string",
+        buffer.as_str(),
+        string.as_str(),
+    ]);
+    assert_eq!(
+        std::fs::read_to_string(&string.0).unwrap(),
+        r#"[[0,"aa"],[1,"bb"],[2,"cc"]]"#
+    );
+    assert_eq!(run_ok(&["resolve", string.as_str(), "2"]), "cc\n");
+}
+
+#[test]
+fn invalid_usage_fails() {
+    let file = TempPath::with_contents("[]");
+    for args in [
+        &[][..],
+        &["unknown"],
+        &["stats"],
+        &["stats", file.as_str(), "extra"],
+        &["--backend", "invalid", "stats", file.as_str()],
+        &["convert", file.as_str(), file.as_str()],
+        &["convert", "--backend", "invalid", file.as_str(), file.as_str()],
+        &["convert", "--from", "string", file.as_str(), file.as_str()],
+        &["--from", "string", "stats", file.as_str()],
+    ] {
+        assert_eq!(run(args).status.code(), Some(2));
+    }
+    assert_eq!(run(&["stats", "/nonexistent/file"]).status.code(), Some(1));
+}
@@ -0,0 +1,130 @@
+#![cfg(all(feature = "shared-memory", target_os = "linux"))]
+
+use std::{
+    path::PathBuf,
+    process::Command,
+    sync::atomic::{
+        AtomicUsize,
+        Ordering,
+    },
+};
+use string_interner::{
+    shared::SharedInterner,
+    DefaultSymbol,
+    Symbol,
+};
+
+type Interner = SharedInterner<DefaultSymbol>;
+
+/// Environment variable holding the file path for [`child_process_interns`].
+const CHILD_PATH: &str = "STRING_INTERNER_SHARED_TEST_PATH";
+
+/// Temporary file path that is removed when dropped.
+struct TempPath(PathBuf);
+
+impl TempPath {
+    /// Returns a new unique temporary file path.
+    fn new() -> Self {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+        let name = format!(
+            "string-interner-{}-{}",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::SeqCst)
+        );
+        Self(std::env::temp_dir().join(name))
+    }
+}
+
+impl Drop for TempPath {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+#[test]
+fn create_and_open_works() {
+    let path = TempPath::new();
+    let mut writer = Interner::create(&path.0, 16, 256).unwrap();
+    let reader = Interner::open(&path.0).unwrap();
+    assert!(reader.is_empty());
+    let aa = writer.get_or_intern("aa").unwrap();
+    let bb = writer.get_or_intern("bb").unwrap();
+    assert_eq!(aa.to_usize(), 0);
+    assert_eq!(bb.to_usize(), 1);
+    assert_eq!(writer.get_or_intern("aa").unwrap(), aa);
+    assert_eq!(reader.len(), 2);
+    assert_eq!(reader.get("aa"), Some(aa));
+    assert_eq!(reader.get("cc"), None);
+    assert_eq!(reader.resolve(bb), Some("bb"));
+    assert_eq!(reader.resolve(DefaultSymbol::try_from_usize(2).unwrap()), None);
+}
+
+#[test]
+fn multiple_writers_agree() {
+    let path = TempPath::new();
+    let mut first = Interner::create(&path.0, 16, 256).unwrap();
+    let mut second = Interner::open(&path.0).unwrap();
+    let aa = first.get_or_intern("aa").unwrap();
+    assert_eq!(second.get_or_intern("aa").unwrap(), aa);
+    let bb = second.get_or_intern("bb").unwrap();
+    assert_eq!(first.get_or_intern("bb").unwrap(), bb);
+    assert_eq!(first.get_or_intern("").unwrap(), second.get("").unwrap());
+    assert_eq!(first.len(), 3);
+    assert_eq!(second.len(), 3);
+}
+
+#[test]
+fn capacity_exceeded_fails() {
+    let path = TempPath::new();
+    let mut interner = Interner::create(&path.0, 2, 4).unwrap();
+    interner.get_or_intern("aa").unwrap();
+    assert!(interner.get_or_intern("bbb").is_err());
+    interner.get_or_intern("bb").unwrap();
+    assert!(interner.get_or_intern("c").is_err());
+    assert_eq!(interner.len(), 2);
+}
+
+#[test]
+fn create_existing_fails() {
+    let path = TempPath::new();
+    let _interner = Interner::create(&path.0, 2, 4).unwrap();
+    assert!(Interner::create(&path.0, 2, 4).is_err());
+}
+
+#[test]
+fn open_invalid_fails() {
+    let path = TempPath::new();
+    std::fs::write(&path.0, b"not an interner file at all, just some bytes ....").unwrap();
+    assert!(Interner::open(&path.0).is_err());
+}
+
+/// Interns strings into the file given by the parent process of [`cross_process_works`].
+#[test]
+fn child_process_interns() {
+    let path = match std::env::var_os(CHILD_PATH) {
+        Some(path) => path,
+        None => return,
+    };
+    let mut interner = Interner::open(path).unwrap();
+    assert_eq!(interner.get("aa").map(Symbol::to_usize), Some(0));
+    interner.get_or_intern("aa").unwrap();
+    interner.get_or_intern("bb").unwrap();
+}
+
+#[test]
+fn cross_process_works() {
+    let path = TempPath::new();
+    let mut interner = Interner::create(&path.0, 16, 256).unwrap();
+    let aa = interner.get_or_intern("aa").unwrap();
+    let status = Command::new(std::env::current_exe().unwrap())
+        .args(["--exact", "child_process_interns", "--test-threads", "1"])
+        .env(CHILD_PATH, &path.0)
+        .status()
+        .unwrap();
+    assert!(status.success());
+    let bb = interner.get("bb").unwrap();
+    assert_eq!(interner.resolve(bb), Some("bb"));
+    assert_eq!(interner.get_or_intern("aa").unwrap(), aa);
+    assert_eq!(interner.get_or_intern("bb").unwrap(), bb);
+    assert_eq!(interner.len(), 2);
+}
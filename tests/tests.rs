@@ -5,7 +5,15 @@ use string_interner::{
     backend,
     DefaultHashBuilder,
     DefaultSymbol,
+    DeltaError,
+    InternError,
+    InternerLimits,
+    LineFormat,
+    MemoryUsage,
+    ReadLinesError,
     Symbol,
+    TryReserveError,
+    Watermark,
 };
 
 #[global_allocator]
@@ -62,6 +70,22 @@ impl BackendStats for backend::StringBackend<DefaultSymbol> {
     const NAME: &'static str = "StringBackend";
 }
 
+impl BackendStats for backend::BorrowedBackend<'static, DefaultSymbol> {
+    const MIN_OVERHEAD: f64 = 2.5;
+    const MAX_OVERHEAD: f64 = 2.76;
+    const MAX_ALLOCATIONS: usize = 1000040;
+    const MAX_DEALLOCATIONS: usize = 38;
+    const NAME: &'static str = "BorrowedBackend";
+}
+
+impl BackendStats for backend::SourceBackend<DefaultSymbol> {
+    const MIN_OVERHEAD: f64 = 2.5;
+    const MAX_OVERHEAD: f64 = 2.76;
+    const MAX_ALLOCATIONS: usize = 62;
+    const MAX_DEALLOCATIONS: usize = 59;
+    const NAME: &'static str = "SourceBackend";
+}
+
 impl BackendStats for backend::BufferBackend<DefaultSymbol> {
     const MIN_OVERHEAD: f64 = 1.35;
     const MAX_OVERHEAD: f64 = 1.58;
@@ -270,6 +294,113 @@ macro_rules! gen_tests_for_backend {
             assert_eq!(interner.len(), 3);
         }
 
+        #[test]
+        fn try_get_or_intern_works() {
+            let mut interner = StringInterner::try_with_capacity(4).unwrap();
+            let aa = interner.try_get_or_intern("aa").unwrap();
+            let bb = interner.try_get_or_intern("bb").unwrap();
+            assert_ne!(aa, bb);
+            assert_eq!(interner.try_get_or_intern("aa"), Ok(aa));
+            assert_eq!(interner.get_or_intern("bb"), bb);
+            assert_eq!(interner.resolve(aa), Some("aa"));
+            assert_eq!(interner.len(), 2);
+            assert_eq!(
+                interner.try_reserve(usize::MAX),
+                Err(TryReserveError::CapacityOverflow)
+            );
+            assert!(StringInterner::try_with_capacity(usize::MAX).is_err());
+            assert_eq!(interner.len(), 2);
+            assert_eq!(interner.resolve(bb), Some("bb"));
+            let cc = interner.try_get_or_intern("cc").unwrap();
+            assert_eq!(interner.get("cc"), Some(cc));
+        }
+
+        #[test]
+        fn limits_works() {
+            let mut interner = StringInterner::new();
+            assert_eq!(*interner.limits(), InternerLimits::UNLIMITED);
+            interner.get_or_intern("aaaa");
+            interner.set_limits(InternerLimits {
+                max_strings: 3,
+                max_total_bytes: 10,
+                max_string_len: 5,
+            });
+            assert_eq!(interner.remaining_capacity().strings, 2);
+            assert_eq!(interner.remaining_capacity().bytes, 6);
+            assert_eq!(
+                interner.try_get_or_intern("bbbbbb"),
+                Err(InternError::StringTooLong {
+                    len: 6,
+                    max_string_len: 5
+                })
+            );
+            let bb = interner.try_get_or_intern("bbbbb").unwrap();
+            assert_eq!(
+                interner.try_get_or_intern("cc"),
+                Err(InternError::ByteLimitExceeded {
+                    max_total_bytes: 10
+                })
+            );
+            let c = interner.try_get_or_intern("c").unwrap();
+            assert_eq!(interner.remaining_capacity().strings, 0);
+            assert_eq!(interner.remaining_capacity().bytes, 0);
+            assert_eq!(
+                interner.try_get_or_intern(""),
+                Err(InternError::StringLimitExceeded { max_strings: 3 })
+            );
+            // Already interned strings are not affected by the limits.
+            assert_eq!(interner.try_get_or_intern("bbbbb"), Ok(bb));
+            assert_eq!(interner.get_or_intern("c"), c);
+            assert_eq!(interner.len(), 3);
+            // Lowering the limits keeps the interned strings.
+            interner.set_limits(InternerLimits {
+                max_strings: 1,
+                ..InternerLimits::default()
+            });
+            assert_eq!(interner.remaining_capacity().strings, 0);
+            assert_eq!(interner.resolve(c), Some("c"));
+            #[cfg(feature = "serde-1")]
+            {
+                use serde::de::DeserializeSeed;
+                use string_interner::serde::InternSeed;
+
+                let mut deserializer = serde_json::Deserializer::from_str(r#""dd""#);
+                let error = InternSeed(&mut interner)
+                    .deserialize(&mut deserializer)
+                    .unwrap_err();
+                assert!(error.to_string().contains("limit of 1 interned strings"));
+            }
+        }
+
+        #[test]
+        #[should_panic]
+        fn get_or_intern_exceeding_limits_panics() {
+            let mut interner = StringInterner::new();
+            interner.set_limits(InternerLimits {
+                max_string_len: 1,
+                ..InternerLimits::default()
+            });
+            interner.get_or_intern("aa");
+        }
+
+        #[test]
+        fn try_extend_works() {
+            let mut interner = StringInterner::try_from_iter(["aa", "bb", "aa"]).unwrap();
+            assert_eq!(interner.len(), 2);
+            interner.set_limits(InternerLimits {
+                max_strings: 3,
+                ..InternerLimits::default()
+            });
+            assert_eq!(
+                interner.try_extend(["aa", "cc", "dd", "ee"]),
+                Err(InternError::StringLimitExceeded { max_strings: 3 })
+            );
+            // The strings preceding the failing one stay interned.
+            assert!(interner.get("cc").is_some());
+            assert_eq!(interner.get("dd"), None);
+            assert_eq!(interner.len(), 3);
+        }
+
         #[test]
         fn get_or_intern_static_works() {
             let mut interner = StringInterner::new();
@@ -373,9 +504,453 @@ macro_rules! gen_tests_for_backend {
             let expected_iter = symbols.into_iter().zip(strings);
             assert!(Iterator::eq(expected_iter, &interner));
         }
+
+        #[test]
+        fn apply_delta_works() {
+            let mut master = StringInterner::new();
+            let mut replica = StringInterner::new();
+            master.extend(["aa", "bb"]);
+            replica.apply_delta(&master.strings_since(replica.watermark())).unwrap();
+            let start = replica.watermark();
+            master.extend(["cc", "aa", "dd"]);
+            let delta = master.strings_since(start);
+            assert_eq!(delta.len(), 2);
+            assert_eq!(delta.start(), start);
+            assert_eq!(delta.end(), master.watermark());
+            replica.apply_delta(&delta).unwrap();
+            assert_eq!(replica, master);
+            assert_eq!(replica.watermark(), master.watermark());
+            for string in ["aa", "bb", "cc", "dd"] {
+                assert_eq!(replica.get(string), master.get(string));
+            }
+            // Applying the same delta twice is an error.
+            assert_eq!(
+                replica.apply_delta(&delta),
+                Err(DeltaError::WatermarkMismatch { expected: 2, actual: 4 }),
+            );
+            // A watermark ahead of the master yields an empty delta at the master's watermark.
+            let mut ahead = master.clone();
+            ahead.extend(["ee", "ff"]);
+            let delta = master.strings_since(ahead.watermark());
+            assert!(delta.is_empty());
+            assert_eq!(delta.start(), master.watermark());
+        }
+
+        #[test]
+        fn apply_delta_diverged_fails() {
+            let mut master = StringInterner::new();
+            let mut replica = StringInterner::new();
+            master.extend(["aa", "bb"]);
+            replica.extend(["bb"]);
+            let mut start = StringInterner::new();
+            start.extend(["aa"]);
+            let delta = master.strings_since(start.watermark());
+            // The replica holds the same number of strings but different ones.
+            assert!(matches!(
+                replica.apply_delta(&delta),
+                Err(DeltaError::FingerprintMismatch { .. }),
+            ));
+            assert_eq!(replica.len(), 1);
+            assert_ne!(replica.watermark(), start.watermark());
+            // The delta since the replica's own watermark ends at another fingerprint.
+            let delta = master.strings_since(replica.watermark());
+            assert_eq!(delta.start(), replica.watermark());
+            assert!(matches!(
+                replica.apply_delta(&delta),
+                Err(DeltaError::FingerprintMismatch { .. }),
+            ));
+            assert_eq!(replica.len(), 1);
+        }
+
+        #[test]
+        fn apply_delta_is_atomic() {
+            let mut master = StringInterner::new();
+            let mut replica = StringInterner::new();
+            master.extend(["aa", "bb", "cc", "dd"]);
+            replica.extend(["aa"]);
+            let expected = replica.clone();
+            replica.set_limits(InternerLimits {
+                max_strings: 3,
+                ..InternerLimits::default()
+            });
+            let delta = master.strings_since(replica.watermark());
+            assert_eq!(
+                replica.apply_delta(&delta),
+                Err(DeltaError::Intern(InternError::StringLimitExceeded { max_strings: 3 })),
+            );
+            // The strings appended before the failure have been removed.
+            assert_eq!(replica, expected);
+            assert_eq!(replica.get("bb"), None);
+            assert_eq!(replica.remaining_capacity().strings, 2);
+            replica.set_limits(InternerLimits::UNLIMITED);
+            replica.apply_delta(&delta).unwrap();
+            assert_eq!(replica, master);
+        }
+
+        #[test]
+        fn write_read_lines_works() {
+            let mut interner = StringInterner::new();
+            interner.extend(["aa", "", "b\tb\nb", "c\\c", "\u{1}\r"]);
+            for format in [LineFormat::Strings, LineFormat::Symbols] {
+                let mut buffer = Vec::new();
+                interner.write_lines(&mut buffer, format).unwrap();
+                assert_eq!(buffer.iter().filter(|&&b| b == b'\n').count(), interner.len());
+                let read = StringInterner::read_lines(&buffer[..], format).unwrap();
+                assert_eq!(read, interner);
+                for (symbol, string) in &interner {
+                    assert_eq!(read.resolve(symbol), Some(string));
+                }
+            }
+        }
+
+        #[test]
+        fn read_lines_symbols_fails() {
+            let mut interner = StringInterner::new();
+            interner.extend(["aa", "bb"]);
+            let aa = interner.get("aa").unwrap().to_usize();
+            let bb = interner.get("bb").unwrap().to_usize();
+            let read = |text: String| {
+                StringInterner::read_lines(text.as_bytes(), LineFormat::Symbols)
+            };
+            // Exact duplicates are accepted.
+            let text = format!("{aa}\taa\n{bb}\tbb\n{aa}\taa\n");
+            assert_eq!(read(text).unwrap(), interner);
+            // Exceeded limits.
+            let limits = InternerLimits {
+                max_string_len: 1,
+                ..InternerLimits::default()
+            };
+            let text = format!("{aa}\taa\n");
+            let limited = StringInterner::read_lines_with_limits(
+                text.as_bytes(),
+                LineFormat::Symbols,
+                limits,
+            );
+            assert!(matches!(
+                limited,
+                Err(ReadLinesError::Intern {
+                    line: 1,
+                    error: InternError::StringTooLong { .. },
+                })
+            ));
+            // Out of order entries.
+            let text = format!("{bb}\tbb\n{aa}\taa\n");
+            assert!(matches!(read(text), Err(ReadLinesError::OutOfOrder { line: 1, .. })));
+            // Conflicting symbols and strings.
+            let text = format!("{aa}\taa\n{aa}\tbb\n");
+            assert!(matches!(read(text), Err(ReadLinesError::Conflict { line: 2 })));
+            let text = format!("{aa}\taa\n{bb}\taa\n");
+            assert!(matches!(read(text), Err(ReadLinesError::Conflict { line: 2 })));
+            // Malformed lines.
+            let text = "aa\n".to_string();
+            assert!(matches!(read(text), Err(ReadLinesError::InvalidSymbol { line: 1 })));
+            let text = format!("{aa}\ta\\a\n");
+            assert!(matches!(read(text), Err(ReadLinesError::InvalidEscape { line: 1 })));
+        }
+
+        #[test]
+        #[cfg(feature = "serde-1")]
+        fn delta_serde_works() {
+            let mut master = StringInterner::new();
+            master.extend(["aa", "bb", "cc"]);
+            let delta = master.strings_since(Watermark::default());
+            let json = serde_json::to_string(&delta).unwrap();
+            let deserialized: string_interner::Delta = serde_json::from_str(&json).unwrap();
+            assert_eq!(deserialized, delta);
+        }
+
+        #[test]
+        fn memory_stats_works() {
+            let empty = StringInterner::new().memory_stats();
+            assert_eq!(empty.total(), Some(MemoryUsage::default()));
+            assert_eq!(empty.len_strings, 0);
+            assert!(empty.length_histogram.is_empty());
+
+            let mut interner = StringInterner::new();
+            interner.extend(["", "a", "bb", "ccc", "dddd", "a"]);
+            let stats = interner.memory_stats();
+            assert_eq!(stats.len_strings, 5);
+            assert_eq!(stats.string_bytes, 10);
+            assert_eq!(stats.length_histogram, [1, 1, 2, 1]);
+            let backend = stats.backend.unwrap();
+            assert!(backend.used_bytes >= stats.string_bytes);
+            assert!(backend.heap_bytes >= backend.used_bytes);
+            assert!(stats.dedup.used_bytes > 0);
+            assert!(stats.dedup.heap_bytes >= stats.dedup.used_bytes);
+            assert_eq!(
+                stats.total().unwrap().slack_bytes(),
+                backend.slack_bytes() + stats.dedup.slack_bytes()
+            );
+        }
+
+        #[test]
+        #[cfg(feature = "serde-1")]
+        fn serde_works() {
+            let mut interner = StringInterner::new();
+            interner.extend(["aa", "bb", "", "cc"]);
+            // Leaked since borrowing backends reference the input.
+            let json: &'static str = Box::leak(serde_json::to_string(&interner).unwrap().into());
+            assert_eq!(json, r#"["aa","bb","","cc"]"#);
+            let binary: &'static [u8] = Box::leak(bincode::serialize(&interner).unwrap().into());
+            // Binary formats use the sequence of strings as well.
+            assert_eq!(binary, bincode::serialize(&["aa", "bb", "", "cc"][..]).unwrap());
+            for deserialized in [
+                serde_json::from_str::<StringInterner>(json).unwrap(),
+                bincode::deserialize::<StringInterner>(binary).unwrap(),
+            ] {
+                assert_eq!(deserialized, interner);
+                // The deduplication table has been restored.
+                for (symbol, string) in &interner {
+                    assert_eq!(deserialized.get(string), Some(symbol));
+                }
+                let mut deserialized = deserialized;
+                assert_eq!(deserialized.get_or_intern("bb"), interner.get("bb").unwrap());
+                assert_eq!(deserialized.len(), interner.len());
+            }
+            // Duplicate strings are collapsed for human-readable formats.
+            let deserialized = serde_json::from_str::<StringInterner>(r#"["aa","bb","aa"]"#).unwrap();
+            assert_eq!(deserialized.len(), 2);
+            assert_eq!(deserialized.get("bb").and_then(|bb| deserialized.resolve(bb)), Some("bb"));
+        }
+
+        #[test]
+        #[cfg(feature = "serde-1")]
+        fn serde_with_symbols_works() {
+            #[derive(serde::Serialize, serde::Deserialize)]
+            struct Table {
+                #[serde(with = "string_interner::serde::with_symbols")]
+                interner: StringInterner,
+            }
+
+            let table = Table {
+                interner: ["aa", "bb", "", "cc"].into_iter().collect(),
+            };
+            let json = serde_json::to_string(&table).unwrap();
+            let restored: Table = serde_json::from_str(&json).unwrap();
+            assert_eq!(restored.interner, table.interner);
+            for (symbol, string) in &table.interner {
+                assert_eq!(restored.interner.resolve(symbol), Some(string));
+            }
+            // Duplicate strings are rejected.
+            let json = r#"{"interner":[[0,"aa"],[0,"aa"]]}"#;
+            let error = serde_json::from_str::<Table>(json).err().unwrap();
+            assert!(error.to_string().contains("duplicate string"));
+            // Symbols that cannot be restored are rejected.
+            let json = r#"{"interner":[[5,"aa"]]}"#;
+            let error = serde_json::from_str::<Table>(json).err().unwrap();
+            assert!(error.to_string().contains("cannot restore symbol 5"));
+            // Announced lengths are not trusted for preallocation.
+            let binary = bincode::serialize(&(1_u64 << 60)).unwrap();
+            assert!(bincode::deserialize::<Table>(&binary).is_err());
+        }
+
+        #[test]
+        #[cfg(feature = "serde-1")]
+        fn serde_with_storage_works() {
+            #[derive(serde::Serialize, serde::Deserialize)]
+            struct Table {
+                #[serde(with = "string_interner::serde::with_storage")]
+                interner: StringInterner,
+            }
+
+            let table = Table {
+                interner: ["aa", "bb", "", "cc"].into_iter().collect(),
+            };
+            let binary = bincode::serialize(&table).unwrap();
+            let json = serde_json::to_string(&table).unwrap();
+            assert_eq!(json, r#"{"interner":["aa","bb","","cc"]}"#);
+            for restored in [
+                bincode::deserialize::<Table>(&binary).unwrap(),
+                serde_json::from_str::<Table>(&json).unwrap(),
+            ] {
+                assert_eq!(restored.interner, table.interner);
+                for (symbol, string) in &table.interner {
+                    assert_eq!(restored.interner.get(string), Some(symbol));
+                }
+            }
+        }
+
+        #[test]
+        #[cfg(feature = "serde-1")]
+        fn serde_intern_seed_works() {
+            use bincode::Options as _;
+            use serde::de::DeserializeSeed;
+            use string_interner::serde::InternSeed;
+
+            let mut interner = StringInterner::new();
+            let aa = interner.get_or_intern("aa");
+            let mut deserialize = |json: &str| {
+                let mut deserializer = serde_json::Deserializer::from_str(json);
+                InternSeed(&mut interner).deserialize(&mut deserializer)
+            };
+            assert_eq!(deserialize(r#""aa""#).unwrap(), aa);
+            // Escaped strings are not borrowed from the input.
+            let bb = deserialize(r#""b\u0062""#).unwrap();
+            assert!(deserialize("42").is_err());
+            let binary = bincode::serialize("cc").unwrap();
+            let cc = bincode::Options::deserialize_seed(
+                bincode::DefaultOptions::new().with_fixint_encoding(),
+                InternSeed(&mut interner),
+                &binary,
+            )
+            .unwrap();
+            assert_eq!(interner.resolve(bb), Some("bb"));
+            assert_eq!(interner.resolve(cc), Some("cc"));
+            assert_eq!(interner.len(), 3);
+        }
+
+        #[test]
+        #[cfg(feature = "serde-1")]
+        fn serde_intern_keys_seed_works() {
+            use serde::de::DeserializeSeed;
+            use std::collections::HashMap;
+            use string_interner::serde::InternKeysSeed;
+
+            let mut interner = StringInterner::new();
+            let aa = interner.get_or_intern("aa");
+            let json = r#"{"bb": 2, "aa": 1, "bb": 3}"#;
+            let mut deserializer = serde_json::Deserializer::from_str(json);
+            let map: HashMap<_, u32> = InternKeysSeed::new(&mut interner)
+                .deserialize(&mut deserializer)
+                .unwrap();
+            let bb = interner.get("bb").unwrap();
+            assert_eq!(map.len(), 2);
+            assert_eq!(map[&aa], 1);
+            assert_eq!(map[&bb], 3);
+            let mut deserializer = serde_json::Deserializer::from_str(r#"{"cc": "x"}"#);
+            assert!(InternKeysSeed::<u32, _, _>::new(&mut interner)
+                .deserialize(&mut deserializer)
+                .is_err());
+        }
+
+        #[test]
+        #[cfg(feature = "serde-1")]
+        fn serde_as_str_works() {
+            use serde::{
+                Deserialize,
+                Serialize,
+            };
+            use string_interner::serde::{
+                as_str,
+                with_interner,
+                with_interner_mut,
+            };
+
+            #[derive(Debug, PartialEq, Serialize, Deserialize)]
+            struct Entry {
+                #[serde(with = "as_str")]
+                key: DefaultSymbol,
+                values: Vec<u32>,
+            }
+
+            let mut source = StringInterner::new();
+            let entries = vec![
+                Entry { key: source.get_or_intern("aa"), values: vec![1] },
+                Entry { key: source.get_or_intern("bb"), values: vec![2, 3] },
+            ];
+            let json = with_interner(&source, || serde_json::to_string(&entries)).unwrap();
+            assert_eq!(json, r#"[{"key":"aa","values":[1]},{"key":"bb","values":[2,3]}]"#);
+            let mut target = StringInterner::new();
+            let bb = target.get_or_intern("bb");
+            let deserialized: Vec<Entry> =
+                with_interner_mut(&mut target, || serde_json::from_str(&json)).unwrap();
+            assert_eq!(deserialized[1].key, bb);
+            assert_eq!(target.resolve(deserialized[0].key), Some("aa"));
+        }
+
+        #[test]
+        #[cfg(feature = "serde-1")]
+        fn serde_as_str_without_interner_fails() {
+            use serde::{
+                Deserialize,
+                Serialize,
+            };
+            use string_interner::serde::{
+                as_str,
+                with_interner,
+            };
+
+            #[derive(Debug, Serialize, Deserialize)]
+            struct Entry {
+                #[serde(with = "as_str")]
+                key: DefaultSymbol,
+            }
+
+            let mut interner = StringInterner::new();
+            let entry = Entry { key: interner.get_or_intern("aa") };
+            let error = serde_json::to_string(&entry).unwrap_err();
+            assert!(error.to_string().contains("no string interner in scope"));
+            let error = serde_json::from_str::<Entry>(r#"{"key":"aa"}"#).unwrap_err();
+            assert!(error.to_string().contains("no string interner in scope"));
+            // Deserialization requires a mutable string interner.
+            let result =
+                with_interner(&interner, || serde_json::from_str::<Entry>(r#"{"key":"aa"}"#));
+            assert!(result.unwrap_err().to_string().contains("not mutable"));
+            // Symbols unknown to the string interner in scope.
+            let other = StringInterner::new();
+            assert!(with_interner(&other, || serde_json::to_string(&entry)).is_err());
+        }
     };
 }
 
+mod borrowed_backend {
+    use super::*;
+
+    gen_tests_for_backend!(backend::BorrowedBackend<'static, DefaultSymbol>);
+
+    #[test]
+    fn get_or_intern_borrowed_works() {
+        let source = String::from("aa bb aa");
+        let (aa, bb, resolved) = {
+            let mut interner =
+                string_interner::StringInterner::<backend::BorrowedBackend<'_>>::new();
+            let aa = interner.get_or_intern_borrowed(&source[0..2]);
+            // Copied strings cannot be resolved as borrowed strings.
+            let bb = interner.get_or_intern("bb");
+            assert_eq!(interner.resolve_borrowed(bb), None);
+            assert_eq!(interner.get_or_intern_borrowed(&source[3..5]), bb);
+            assert_eq!(interner.get_or_intern_borrowed(&source[6..8]), aa);
+            assert_eq!(interner.len(), 2);
+            (aa, bb, [interner.resolve_borrowed(aa), interner.resolve_borrowed(bb)])
+        };
+        // The borrowed strings outlive the string interner.
+        assert_eq!(resolved, [Some("aa"), Some("bb")]);
+        assert!(std::ptr::eq(resolved[0].unwrap(), &source[0..2]));
+        assert!(std::ptr::eq(resolved[1].unwrap(), &source[3..5]));
+        assert_ne!(aa, bb);
+    }
+
+    #[test]
+    #[cfg(feature = "serde-1")]
+    fn serde_borrows_from_input() {
+        type Interner<'a> = string_interner::StringInterner<backend::BorrowedBackend<'a>>;
+
+        fn from_backend(backend: backend::BorrowedBackend) -> Interner {
+            Interner::try_from_backend(backend, Default::default(), Default::default()).unwrap()
+        }
+
+        let json = String::from(r#"["aa","b\nb"]"#);
+        let interner = from_backend(serde_json::from_str(&json).unwrap());
+        assert_eq!(interner.len(), 2);
+        let aa = interner.get("aa").unwrap();
+        assert!(std::ptr::eq(interner.resolve_borrowed(aa).unwrap(), &json[2..4]));
+        // Escaped strings are copied.
+        let bb = interner.get("b\nb").unwrap();
+        assert_eq!(interner.resolve_borrowed(bb), None);
+        assert_eq!(interner.resolve(bb), Some("b\nb"));
+
+        let binary = bincode::serialize(&["aa", "bb"][..]).unwrap();
+        let interner = from_backend(bincode::deserialize(&binary).unwrap());
+        let bb = interner.get("bb").unwrap();
+        assert!(interner.resolve_borrowed(bb).is_some());
+        assert_eq!(bincode::serialize(&interner).unwrap(), binary);
+        // The string interner copies the strings it deserializes.
+        let interner: Interner = serde_json::from_str(&json).unwrap();
+        let aa = interner.get("aa").unwrap();
+        assert_eq!(interner.resolve_borrowed(aa), None);
+    }
+}
+
 mod bucket_backend {
     use super::*;
 
@@ -392,10 +967,1314 @@ mod string_backend {
     use super::*;
 
     gen_tests_for_backend!(backend::StringBackend<DefaultSymbol>);
+
+    #[test]
+    #[cfg(feature = "serde-1")]
+    fn serde_raw_invalid_fails() {
+        #[derive(Debug, serde::Deserialize)]
+        struct Storage(
+            #[serde(with = "string_interner::serde::with_storage")]
+            #[allow(dead_code)]
+            StringInterner,
+        );
+
+        let deserialize = |ends: &[u64], buffer: &[u8]| {
+            let ends = ends.iter().flat_map(|end| end.to_le_bytes()).collect::<Vec<_>>();
+            let binary = bincode::serialize(&(ends, buffer.to_vec())).unwrap();
+            bincode::deserialize::<Storage>(&binary)
+        };
+        assert!(deserialize(&[2, 4], b"aabb").is_ok());
+        // Invalid UTF-8.
+        assert!(deserialize(&[2], b"\xFF\xFF").is_err());
+        // Descending, out of bounds or non char boundary end offsets.
+        assert!(deserialize(&[4, 2], b"aabb").is_err());
+        assert!(deserialize(&[2, 5], b"aabb").is_err());
+        assert!(deserialize(&[1], "ü".as_bytes()).is_err());
+        // Trailing bytes.
+        assert!(deserialize(&[2], b"aabb").is_err());
+        // Duplicate strings.
+        assert!(deserialize(&[2, 4], b"aaaa").is_err());
+    }
+}
+
+mod source_backend {
+    use super::*;
+    use std::sync::Arc;
+
+    gen_tests_for_backend!(backend::SourceBackend<DefaultSymbol>);
+
+    #[test]
+    fn register_source_works() {
+        let source: Arc<str> = Arc::from("aa bb aa");
+        let other: Arc<str> = Arc::from("bb cc");
+        let mut interner = StringInterner::new();
+        interner.register_source(source.clone());
+        interner.register_source(source.clone());
+        interner.register_source(Arc::from(""));
+        let aa = interner.get_or_intern(&source[0..2]);
+        assert_eq!(interner.get_or_intern(&source[6..8]), aa);
+        // Slices of registered sources are referenced.
+        let resolved = interner.resolve(aa).unwrap();
+        assert!(std::ptr::eq(resolved, &source[0..2]));
+        // Slices of unregistered sources are copied.
+        let cc = interner.get_or_intern(&other[3..5]);
+        assert!(!std::ptr::eq(interner.resolve(cc).unwrap(), &other[3..5]));
+        let bb = interner.get_or_intern(&source[3..5]);
+        assert!(std::ptr::eq(interner.resolve(bb).unwrap(), &source[3..5]));
+        assert_eq!(interner.get_or_intern(&other[0..2]), bb);
+        assert_eq!(interner.len(), 3);
+        // Clones and equality are independent of the storage of the strings.
+        let mut copied = StringInterner::new();
+        copied.extend(["aa", "cc", "bb"]);
+        assert_eq!(interner.clone(), interner);
+        assert_eq!(copied, interner);
+        // Dropping the string interner releases its sources.
+        assert_eq!(Arc::strong_count(&source), 2);
+        drop(interner);
+        assert_eq!(Arc::strong_count(&source), 1);
+    }
+
+    #[test]
+    #[cfg(feature = "serde-1")]
+    fn serde_copies_sources() {
+        let source: Arc<str> = Arc::from("aa bb");
+        let mut interner = StringInterner::new();
+        interner.register_source(source.clone());
+        let bb = interner.get_or_intern(&source[3..5]);
+        let json = serde_json::to_string(&interner).unwrap();
+        let deserialized = serde_json::from_str::<StringInterner>(&json).unwrap();
+        assert_eq!(deserialized, interner);
+        assert_eq!(Arc::strong_count(&source), 2);
+        assert!(!std::ptr::eq(deserialized.resolve(bb).unwrap(), &source[3..5]));
+    }
 }
 
 mod buffer_backend {
     use super::*;
 
     gen_tests_for_backend!(backend::BufferBackend<DefaultSymbol>);
+
+    #[test]
+    #[cfg(feature = "serde-1")]
+    fn serde_raw_invalid_fails() {
+        #[derive(Debug, serde::Deserialize)]
+        struct Storage(
+            #[serde(with = "string_interner::serde::with_storage")]
+            #[allow(dead_code)]
+            StringInterner,
+        );
+
+        let deserialize = |len_strings: u64, buffer: &[u8]| {
+            let binary = bincode::serialize(&(len_strings, buffer.to_vec())).unwrap();
+            bincode::deserialize::<Storage>(&binary)
+        };
+        assert!(deserialize(2, b"\x02aa\x02bb").is_ok());
+        // Mismatching number of strings.
+        assert!(deserialize(3, b"\x02aa\x02bb").is_err());
+        // Invalid UTF-8.
+        assert!(deserialize(1, b"\x02\xFF\xFF").is_err());
+        // Out of bounds string or length.
+        assert!(deserialize(1, b"\x03aa").is_err());
+        assert!(deserialize(1, b"\x80").is_err());
+        // Duplicate strings.
+        assert!(deserialize(2, b"\x02aa\x02aa").is_err());
+    }
+}
+
+mod bounded_interner {
+    use std::sync::{
+        Arc,
+        Mutex,
+    };
+    use string_interner::BoundedInterner;
+
+    #[test]
+    fn get_or_intern_works() {
+        let mut interner = <BoundedInterner>::new(3);
+        assert!(interner.is_empty());
+        let aa = interner.get_or_intern("aa");
+        let bb = interner.get_or_intern("bb");
+        assert_ne!(aa, bb);
+        assert_eq!(interner.get_or_intern("aa"), aa);
+        assert_eq!(interner.get("bb"), Some(bb));
+        assert_eq!(interner.get("cc"), None);
+        assert_eq!(interner.resolve(aa), Some("aa"));
+        assert_eq!(interner.len(), 2);
+        assert_eq!(interner.capacity(), 3);
+    }
+
+    #[test]
+    fn eviction_works() {
+        let mut interner = <BoundedInterner>::new(2);
+        let aa = interner.get_or_intern("aa");
+        let bb = interner.get_or_intern("bb");
+        // Interning and touching mark strings as used, `get` and `resolve` do not.
+        assert_eq!(interner.get_or_intern("aa"), aa);
+        assert_eq!(interner.get("bb"), Some(bb));
+        let cc = interner.get_or_intern("cc");
+        assert_eq!(interner.resolve(bb), None);
+        assert_eq!(interner.get("bb"), None);
+        assert!(!interner.touch(bb));
+        assert!(interner.touch(aa));
+        let dd = interner.get_or_intern("dd");
+        assert_eq!(interner.resolve(cc), None);
+        assert_eq!(interner.resolve(aa), Some("aa"));
+        assert_eq!(interner.resolve(dd), Some("dd"));
+        assert_eq!(interner.len(), 2);
+        // Evicted symbols never resolve to the strings that reuse their slots.
+        assert_eq!(cc.index(), dd.index());
+        assert_ne!(cc.generation(), dd.generation());
+        let bb2 = interner.get_or_intern("bb");
+        assert_ne!(bb, bb2);
+        assert_eq!(interner.resolve(bb2), Some("bb"));
+    }
+
+    #[test]
+    fn on_evict_works() {
+        let evicted = Arc::new(Mutex::new(Vec::new()));
+        let mut interner = <BoundedInterner>::new(1);
+        interner.set_on_evict({
+            let evicted = evicted.clone();
+            move |symbol, string| evicted.lock().unwrap().push((symbol, string.to_owned()))
+        });
+        let aa = interner.get_or_intern("aa");
+        let bb = interner.get_or_intern("bb");
+        interner.get_or_intern("cc");
+        assert_eq!(
+            *evicted.lock().unwrap(),
+            [(aa, String::from("aa")), (bb, String::from("bb"))]
+        );
+    }
+
+    #[test]
+    fn panicking_on_evict_leaves_interner_consistent() {
+        let mut interner = <BoundedInterner>::new(1);
+        interner.set_on_evict(|_, string| panic!("evicted {}", string));
+        let aa = interner.get_or_intern("aa");
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            interner.get_or_intern("bb");
+        }));
+        assert!(result.is_err());
+        // The string has been evicted before the callback panicked.
+        assert_eq!(interner.resolve(aa), None);
+        assert_eq!(interner.get("aa"), None);
+        assert!(interner.is_empty());
+        interner.set_on_evict(|_, _| ());
+        let bb = interner.get_or_intern("bb");
+        assert_eq!(bb.index(), aa.index());
+        assert_eq!(interner.resolve(bb), Some("bb"));
+        assert_eq!(interner.len(), 1);
+    }
+
+    #[test]
+    fn many_evictions_work() {
+        let mut interner = <BoundedInterner>::new(10);
+        let mut symbols = Vec::new();
+        for i in 0..1000 {
+            symbols.push(interner.get_or_intern(i.to_string()));
+            // Keep the first string alive by using it continuously.
+            interner.get_or_intern("0");
+        }
+        assert_eq!(interner.len(), 10);
+        assert_eq!(interner.resolve(symbols[0]), Some("0"));
+        for (i, &symbol) in symbols.iter().enumerate().skip(1) {
+            match interner.resolve(symbol) {
+                Some(string) => {
+                    assert!(i >= 991);
+                    assert_eq!(string, i.to_string());
+                }
+                None => assert!(i < 991),
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn zero_capacity_panics() {
+        <BoundedInterner>::new(0);
+    }
+}
+
+mod istr {
+    use std::collections::{
+        BTreeSet,
+        HashSet,
+    };
+    use string_interner::{
+        IStr,
+        IStrInterner,
+    };
+
+    #[test]
+    // The interior mutability of `IStr` does not affect its hash and ordering.
+    #[allow(clippy::mutable_key_type)]
+    fn intern_works() {
+        let interner = <IStrInterner>::new();
+        let aa = interner.intern("aa");
+        let bb = interner.intern(String::from("bb"));
+        assert_eq!(interner.intern("aa"), aa);
+        assert_ne!(aa, bb);
+        assert_eq!(aa, "aa");
+        assert_eq!(aa.len(), 2);
+        assert_eq!(bb.to_string(), "bb");
+        assert_eq!(format!("{:?}", aa), r#""aa""#);
+        let bb2 = interner.get("bb").unwrap();
+        assert!(IStr::ptr_eq(&bb, &bb2));
+        assert_eq!(IStr::strong_count(&bb), 2);
+        assert_eq!(interner.get("cc"), None);
+        assert_eq!(interner.len(), 2);
+        let set = [aa.clone(), bb.clone(), aa.clone()]
+            .into_iter()
+            .collect::<HashSet<_>>();
+        assert_eq!(set.len(), 2);
+        let sorted = [bb.clone(), aa.clone()]
+            .into_iter()
+            .collect::<BTreeSet<_>>();
+        assert_eq!(sorted.into_iter().collect::<Vec<_>>(), [aa, bb]);
+    }
+
+    #[test]
+    fn release_works() {
+        let interner = <IStrInterner>::new();
+        let aa = interner.intern("aa");
+        let aa2 = aa.clone();
+        let bb = interner.intern("bb");
+        drop(aa);
+        assert_eq!(interner.get("aa"), Some(aa2.clone()));
+        drop(aa2);
+        assert_eq!(interner.get("aa"), None);
+        assert_eq!(interner.len(), 1);
+        // Handles work without access to the interner.
+        drop(interner);
+        assert_eq!(bb, "bb");
+    }
+
+    #[test]
+    fn different_interners_are_not_equal() {
+        let lhs = <IStrInterner>::new();
+        let rhs = <IStrInterner>::new();
+        let aa = lhs.intern("aa");
+        assert_ne!(aa, rhs.intern("aa"));
+        assert_eq!(aa, lhs.clone().intern("aa"));
+    }
+
+    #[test]
+    fn concurrent_intern_and_release_works() {
+        let interner = <IStrInterner>::new();
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    for i in 0..2000 {
+                        let string = (i % 50).to_string();
+                        let handle = interner.intern(&*string);
+                        assert_eq!(handle, &*string);
+                        assert_eq!(interner.intern(string), handle);
+                    }
+                });
+            }
+        });
+        assert!(interner.is_empty());
+    }
+}
+
+mod weak_interner {
+    use std::sync::Arc;
+    use string_interner::WeakInterner;
+
+    #[test]
+    fn get_or_intern_works() {
+        let mut interner = <WeakInterner>::new();
+        let aa = interner.get_or_intern("aa");
+        let bb = interner.get_or_intern(String::from("bb"));
+        assert!(Arc::ptr_eq(&aa, &interner.get_or_intern("aa")));
+        assert!(Arc::ptr_eq(&bb, &interner.get("bb").unwrap()));
+        assert_eq!(&*aa, "aa");
+        assert_eq!(interner.get("cc"), None);
+        assert_eq!(interner.len(), 2);
+    }
+
+    #[test]
+    fn purge_works() {
+        let mut interner = <WeakInterner>::new();
+        let aa = interner.get_or_intern("aa");
+        let aa2 = aa.clone();
+        let bb = interner.get_or_intern("bb");
+        drop(aa);
+        assert_eq!(interner.purge(), 0);
+        assert_eq!(interner.get("aa"), Some(aa2.clone()));
+        drop(aa2);
+        assert_eq!(interner.get("aa"), None);
+        // Entries remain until purged.
+        assert_eq!(interner.len(), 2);
+        assert_eq!(interner.purge(), 1);
+        assert_eq!(interner.len(), 1);
+        interner.shrink_to_fit();
+        assert!(Arc::ptr_eq(&bb, &interner.get_or_intern("bb")));
+    }
+
+    #[test]
+    fn reintern_after_release_works() {
+        let mut interner = <WeakInterner>::new();
+        drop(interner.get_or_intern("aa"));
+        let aa = interner.get_or_intern("aa");
+        assert_eq!(&*aa, "aa");
+        assert!(Arc::ptr_eq(&aa, &interner.get_or_intern("aa")));
+        // The dead entry is replaced in place.
+        assert_eq!(interner.len(), 1);
+        assert_eq!(interner.purge(), 0);
+        assert!(Arc::ptr_eq(&aa, &interner.get("aa").unwrap()));
+        drop(aa);
+        assert_eq!(interner.purge(), 1);
+        assert!(interner.is_empty());
+    }
+
+    #[test]
+    fn transient_strings_stay_flat() {
+        let mut interner = <WeakInterner>::new();
+        for i in 0..10_000 {
+            let handle = interner.get_or_intern(i.to_string());
+            assert_eq!(*handle, i.to_string());
+            if i % 100 == 99 {
+                interner.purge();
+            }
+            assert!(interner.len() <= 100);
+        }
+        interner.purge();
+        assert!(interner.is_empty());
+    }
+}
+
+mod local_interner {
+    use string_interner::{
+        symbol::SymbolU16,
+        LocalInterner,
+    };
+
+    #[test]
+    fn intern_works() {
+        let interner = <LocalInterner>::new();
+        let aa = interner.intern("aa");
+        let bb = interner.intern(String::from("bb"));
+        assert_eq!(aa, "aa");
+        assert_eq!(bb, "bb");
+        assert_eq!(interner.intern("aa").as_ptr(), aa.as_ptr());
+        assert_eq!(interner.len(), 2);
+        let aa_sym = interner.get("aa").unwrap();
+        assert_eq!(interner.get_or_intern("aa"), aa_sym);
+        assert_eq!(interner.resolve(aa_sym), Some("aa"));
+        assert_eq!(interner.get("cc"), None);
+        let static_sym = interner.get_or_intern_static("static");
+        assert_eq!(interner.resolve(static_sym), Some("static"));
+    }
+
+    #[test]
+    fn references_stay_valid_while_interning() {
+        let interner = LocalInterner::<SymbolU16>::with_capacity(4);
+        let strings = (0..1000)
+            .map(|i| interner.intern(i.to_string()))
+            .collect::<Vec<&str>>();
+        for (i, string) in strings.into_iter().enumerate() {
+            assert_eq!(string, i.to_string());
+            assert_eq!(interner.intern(string).as_ptr(), string.as_ptr());
+        }
+        let inner = interner.into_inner();
+        assert_eq!(inner.len(), 1000);
+    }
+}
+
+mod append_interner {
+    use string_interner::{
+        AppendInterner,
+        AppendReader,
+        DefaultSymbol,
+        Symbol,
+    };
+
+    #[test]
+    fn get_or_intern_works() {
+        let mut interner = <AppendInterner>::new();
+        let reader = interner.reader();
+        assert!(reader.is_empty());
+        let aa = interner.get_or_intern("aa");
+        let bb = interner.get_or_intern(String::from("bb"));
+        let empty = interner.get_or_intern("");
+        assert_eq!(interner.get_or_intern("aa"), aa);
+        assert_eq!(interner.get("bb"), Some(bb));
+        assert_eq!(interner.get("cc"), None);
+        assert_eq!(interner.resolve(aa), Some("aa"));
+        assert_eq!(reader.resolve(bb), Some("bb"));
+        assert_eq!(reader.resolve(empty), Some(""));
+        assert_eq!(interner.len(), 3);
+        assert_eq!(reader.len(), 3);
+        let unknown = DefaultSymbol::try_from_usize(3).unwrap();
+        assert_eq!(reader.resolve(unknown), None);
+    }
+
+    #[test]
+    fn reader_outlives_interner() {
+        let mut interner = <AppendInterner>::new();
+        let symbols = (0..1000)
+            .map(|i| interner.get_or_intern(i.to_string()))
+            .collect::<Vec<_>>();
+        let reader = interner.reader().clone();
+        drop(interner);
+        for (i, symbol) in symbols.into_iter().enumerate() {
+            assert_eq!(reader.resolve(symbol), Some(&*i.to_string()));
+        }
+    }
+
+    #[test]
+    fn concurrent_resolve_works() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<AppendInterner>();
+        assert_send_sync::<AppendReader>();
+
+        const LEN: usize = 10_000;
+        let mut interner = <AppendInterner>::new();
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                let reader = interner.reader();
+                scope.spawn(move || {
+                    let mut resolved = 0;
+                    while resolved < LEN {
+                        let len = reader.len();
+                        for index in resolved..len {
+                            let symbol = DefaultSymbol::try_from_usize(index).unwrap();
+                            assert_eq!(reader.resolve(symbol), Some(&*index.to_string()));
+                        }
+                        resolved = len;
+                    }
+                });
+            }
+            for index in 0..LEN {
+                let symbol = interner.get_or_intern(index.to_string());
+                assert_eq!(symbol.to_usize(), index);
+            }
+        });
+    }
+}
+
+mod thread_local_interner {
+    use std::sync::Mutex;
+    use string_interner::{
+        symbol::SymbolU16,
+        thread_local_interner,
+        DefaultBackend,
+        DefaultSymbol,
+        InternError,
+        InternerLimits,
+        StringInterner,
+        Symbol,
+        SymbolTranslator,
+    };
+
+    thread_local_interner! {
+        static INTERNER: StringInterner = StringInterner::new();
+        /// An interner with a custom symbol type.
+        pub(crate) static SMALL: StringInterner<DefaultBackend<SymbolU16>> =
+            StringInterner::new();
+    }
+
+    #[test]
+    fn thread_local_interner_works() {
+        let aa = INTERNER.get_or_intern("aa");
+        let small = SMALL.get_or_intern("bb");
+        assert_eq!(INTERNER.get("aa"), Some(aa));
+        assert_eq!(SMALL.get("bb"), Some(small));
+        INTERNER.with(|interner| {
+            assert_eq!(interner.resolve(aa), Some("aa"));
+            assert_eq!(interner.len(), 1);
+        });
+        std::thread::spawn(|| {
+            // Every thread has its own interner.
+            assert_eq!(INTERNER.get("aa"), None);
+            INTERNER.get_or_intern("cc");
+            INTERNER.get_or_intern("aa");
+        })
+        .join()
+        .unwrap();
+        assert_eq!(INTERNER.get("cc"), None);
+    }
+
+    #[test]
+    fn translate_works() {
+        let mut from = <StringInterner>::new();
+        let mut to = StringInterner::<DefaultBackend<SymbolU16>>::new();
+        let aa = from.get_or_intern("aa");
+        let bb = from.get_or_intern("bb");
+        to.get_or_intern("bb");
+        let mut translator = SymbolTranslator::<DefaultSymbol, SymbolU16>::new();
+        assert_eq!(translator.get(bb), None);
+        let bb2 = translator.translate(bb, &from, &mut to).unwrap();
+        assert_eq!(bb2.to_usize(), 0);
+        let aa2 = translator.translate(aa, &from, &mut to).unwrap();
+        assert_eq!(to.resolve(aa2), Some("aa"));
+        assert_eq!(translator.get(aa), Some(aa2));
+        assert_eq!(translator.translate(aa, &from, &mut to), Some(aa2));
+        assert_eq!(to.len(), 2);
+        let unknown = DefaultSymbol::try_from_usize(10).unwrap();
+        assert_eq!(translator.translate(unknown, &from, &mut to), None);
+        translator.clear();
+        assert_eq!(translator.get(aa), None);
+    }
+
+    #[test]
+    fn try_translate_works() {
+        let mut from = <StringInterner>::new();
+        let mut to = <StringInterner>::new();
+        let aa = from.get_or_intern("aa");
+        let bb = from.get_or_intern("bb");
+        to.set_limits(InternerLimits {
+            max_strings: 1,
+            ..InternerLimits::default()
+        });
+        let mut translator = <SymbolTranslator>::new();
+        let aa2 = translator.try_translate(aa, &from, &mut to).unwrap();
+        assert_eq!(to.resolve(aa2.unwrap()), Some("aa"));
+        assert_eq!(
+            translator.try_translate(bb, &from, &mut to),
+            Err(InternError::StringLimitExceeded { max_strings: 1 })
+        );
+        assert_eq!(translator.get(bb), None);
+        let unknown = DefaultSymbol::try_from_usize(10).unwrap();
+        assert_eq!(translator.try_translate(unknown, &from, &mut to), Ok(None));
+    }
+
+    #[test]
+    fn translate_between_threads_works() {
+        let shared = Mutex::new(<StringInterner>::new());
+        let symbols = std::thread::scope(|scope| {
+            let handles = (0..4)
+                .map(|i| {
+                    let shared = &shared;
+                    scope.spawn(move || {
+                        let local = INTERNER.get_or_intern(format!("thread {}", i));
+                        let common = INTERNER.get_or_intern("common");
+                        let mut translator = <SymbolTranslator>::new();
+                        INTERNER.with(|interner| {
+                            let mut shared = shared.lock().unwrap();
+                            [local, common].map(|symbol| {
+                                translator
+                                    .translate(symbol, interner, &mut shared)
+                                    .unwrap()
+                            })
+                        })
+                    })
+                })
+                .collect::<Vec<_>>();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap())
+                .collect::<Vec<_>>()
+        });
+        let shared = shared.into_inner().unwrap();
+        assert_eq!(shared.len(), 5);
+        for [local, common] in symbols {
+            assert!(shared.resolve(local).unwrap().starts_with("thread "));
+            assert_eq!(shared.resolve(common), Some("common"));
+        }
+    }
+}
+
+mod layered_interner {
+    use string_interner::{
+        backend::BucketBackend,
+        DefaultSymbol,
+        InternError,
+        InternerLimits,
+        LayeredInterner,
+        StringInterner,
+        Symbol,
+    };
+
+    #[test]
+    fn get_or_intern_works() {
+        let base = ["aa", "bb"].into_iter().collect::<StringInterner>();
+        let aa = base.get("aa").unwrap();
+        let mut layered = LayeredInterner::new(&base);
+        assert_eq!(layered.get("aa"), Some(aa));
+        assert_eq!(layered.get_or_intern("aa"), aa);
+        assert!(!layered.is_local(aa));
+        let cc = layered.get_or_intern("cc");
+        let dd = layered.get_or_intern(String::from("dd"));
+        assert_eq!(cc.to_usize(), 2);
+        assert_eq!(dd.to_usize(), 3);
+        assert!(layered.is_local(cc));
+        assert_eq!(layered.get_or_intern("cc"), cc);
+        assert_eq!(layered.get("dd"), Some(dd));
+        assert_eq!(layered.get("ee"), None);
+        assert_eq!(layered.len(), 4);
+        assert_eq!(layered.local().len(), 2);
+        assert_eq!(base.get("cc"), None);
+    }
+
+    #[test]
+    fn bucket_backend_works() {
+        let mut base = StringInterner::<BucketBackend<DefaultSymbol>>::new();
+        let aa = base.get_or_intern("aa");
+        let bb = base.get_or_intern("bb");
+        let mut layered = LayeredInterner::new(&base);
+        let cc = layered.get_or_intern("cc");
+        assert_eq!(cc.to_usize(), 2);
+        assert_eq!(layered.get_or_intern("bb"), bb);
+        assert_eq!(layered.resolve(aa), Some("aa"));
+        assert_eq!(layered.resolve(bb), Some("bb"));
+        assert_eq!(layered.resolve(cc), Some("cc"));
+    }
+
+    #[test]
+    fn resolve_works() {
+        let base = ["aa", "bb"].into_iter().collect::<StringInterner>();
+        let mut layered = LayeredInterner::new(&base);
+        let aa = base.get("aa").unwrap();
+        let cc = layered.get_or_intern("cc");
+        assert_eq!(layered.resolve(aa), Some("aa"));
+        assert_eq!(layered.resolve(cc), Some("cc"));
+        let unknown = DefaultSymbol::try_from_usize(3).unwrap();
+        assert_eq!(layered.resolve(unknown), None);
+        let local = layered.into_local();
+        assert_eq!(local.resolve(DefaultSymbol::try_from_usize(0).unwrap()), Some("cc"));
+    }
+
+    #[test]
+    fn many_layers_share_base() {
+        let base = ["aa"].into_iter().collect::<StringInterner>();
+        let mut lhs = LayeredInterner::new(&base);
+        let mut rhs = LayeredInterner::new(&base);
+        let bb = lhs.get_or_intern("bb");
+        let cc = rhs.get_or_intern("cc");
+        // Local symbols of different layers overlap.
+        assert_eq!(bb, cc);
+        assert_eq!(lhs.get("cc"), None);
+        assert_eq!(lhs.get_or_intern("aa"), rhs.get_or_intern("aa"));
+    }
+
+    #[test]
+    fn with_local_works() {
+        let base = ["aa"].into_iter().collect::<StringInterner>();
+        let local = ["aa", "bb"].into_iter().collect::<StringInterner>();
+        let layered = LayeredInterner::with_local(&base, local);
+        let bb = layered.get("bb").unwrap();
+        assert_eq!(bb.to_usize(), 2);
+        assert_eq!(layered.resolve(bb), Some("bb"));
+        assert_eq!(layered.get("aa"), base.get("aa"));
+    }
+
+    #[test]
+    fn try_get_or_intern_works() {
+        let base = ["aa"].into_iter().collect::<StringInterner>();
+        let mut local = StringInterner::new();
+        local.set_limits(InternerLimits {
+            max_strings: 1,
+            ..InternerLimits::default()
+        });
+        let mut layered = LayeredInterner::with_local(&base, local);
+        assert_eq!(layered.try_get_or_intern("aa"), Ok(base.get("aa").unwrap()));
+        let bb = layered.try_get_or_intern("bb").unwrap();
+        assert_eq!(layered.resolve(bb), Some("bb"));
+        assert_eq!(
+            layered.try_get_or_intern("cc"),
+            Err(InternError::StringLimitExceeded { max_strings: 1 })
+        );
+        assert_eq!(layered.len(), 2);
+    }
+}
+
+mod persistent_interner {
+    use string_interner::{
+        DefaultSymbol,
+        PersistentInterner,
+        StringInterner,
+        Symbol,
+    };
+
+    #[test]
+    fn get_or_intern_works() {
+        let mut interner = <PersistentInterner>::new();
+        let aa = interner.get_or_intern("aa");
+        let bb = interner.get_or_intern(String::from("bb"));
+        let empty = interner.get_or_intern("");
+        assert_eq!(interner.get_or_intern("aa"), aa);
+        assert_eq!(interner.get("bb"), Some(bb));
+        assert_eq!(interner.get("cc"), None);
+        assert_eq!(interner.resolve(aa), Some("aa"));
+        assert_eq!(interner.resolve(empty), Some(""));
+        assert_eq!(interner.len(), 3);
+        let unknown = DefaultSymbol::try_from_usize(3).unwrap();
+        assert_eq!(interner.resolve(unknown), None);
+    }
+
+    #[test]
+    fn clones_intern_independently() {
+        let mut lhs = <PersistentInterner>::new();
+        let aa = lhs.get_or_intern("aa");
+        let mut rhs = lhs.clone();
+        let bb = lhs.get_or_intern("bb");
+        let cc = rhs.get_or_intern("cc");
+        assert_eq!(bb, cc);
+        assert_eq!(lhs.resolve(bb), Some("bb"));
+        assert_eq!(rhs.resolve(cc), Some("cc"));
+        assert_eq!(lhs.get("cc"), None);
+        assert_eq!(rhs.get("bb"), None);
+        assert_eq!(lhs.get("aa"), Some(aa));
+        assert_eq!(rhs.get("aa"), Some(aa));
+    }
+
+    #[test]
+    fn many_versions_match_string_interner() {
+        let mut versions = vec![(<PersistentInterner>::new(), <StringInterner>::new())];
+        for i in 0..5000 {
+            let index = (i * 7) % versions.len();
+            let (persistent, expected) = &mut versions[index];
+            let string = (i % 3000).to_string();
+            assert_eq!(
+                persistent.get_or_intern(&string),
+                expected.get_or_intern(&string)
+            );
+            if i % 250 == 0 {
+                let clone = versions[index].clone();
+                versions.push(clone);
+            }
+        }
+        for (persistent, expected) in &versions {
+            assert_eq!(persistent.len(), expected.len());
+            for (symbol, string) in expected {
+                assert_eq!(persistent.resolve(symbol), Some(string));
+                assert_eq!(persistent.get(string), Some(symbol));
+            }
+        }
+    }
+}
+
+mod namespaced_interner {
+    use string_interner::{
+        Namespace,
+        NamespacedInterner,
+    };
+
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+    enum Kind {
+        Ident,
+        Literal,
+        Label,
+    }
+
+    struct Ident;
+    struct Literal;
+    struct Label;
+
+    impl Namespace<Kind> for Ident {
+        const NAMESPACE: Kind = Kind::Ident;
+    }
+
+    impl Namespace<Kind> for Literal {
+        const NAMESPACE: Kind = Kind::Literal;
+    }
+
+    impl Namespace<Kind> for Label {
+        const NAMESPACE: Kind = Kind::Label;
+    }
+
+    #[test]
+    fn get_or_intern_works() {
+        let mut interner = <NamespacedInterner<Kind>>::new();
+        let ident = interner.get_or_intern(Ident, "aa");
+        let literal = interner.get_or_intern(Literal, String::from("aa"));
+        let label = interner.get_or_intern(Label, "bb");
+        assert_eq!(ident.namespace(), Kind::Ident);
+        assert_eq!(literal.namespace(), Kind::Literal);
+        assert_eq!(ident.storage_symbol(), literal.storage_symbol());
+        assert_eq!(interner.get_or_intern(Ident, "aa"), ident);
+        assert_eq!(interner.get(Literal, "aa"), Some(literal));
+        assert_eq!(interner.get(Label, "aa"), None);
+        assert_eq!(interner.get(Ident, "bb"), None);
+        assert_eq!(interner.len(), 3);
+        assert_eq!(interner.storage().len(), 2);
+        assert_eq!(interner.resolve(label), Some("bb"));
+    }
+
+    #[test]
+    fn const_namespaces_work() {
+        struct Ident;
+        struct Label;
+
+        impl Namespace<u8> for Ident {
+            const NAMESPACE: u8 = 0;
+        }
+
+        impl Namespace<u8> for Label {
+            const NAMESPACE: u8 = 1;
+        }
+
+        let mut interner = <NamespacedInterner<u8>>::new();
+        let ident = interner.get_or_intern(Ident, "aa");
+        let label = interner.get_or_intern(Label, "bb");
+        assert_eq!(label.namespace(), 1);
+        assert!(interner.contains(ident));
+        assert_eq!(interner.resolve(ident), Some("aa"));
+        assert_eq!(interner.resolve(label), Some("bb"));
+        assert_eq!(interner.get(Ident, "bb"), None);
+        // Symbols of other interners only resolve if their string has been
+        // interned into their namespace.
+        let mut other = <NamespacedInterner<u8>>::new();
+        let other_label = other.get_or_intern(Label, "cc");
+        assert_eq!(other_label.storage_symbol(), ident.storage_symbol());
+        assert!(!interner.contains(other_label));
+        assert_eq!(interner.resolve(other_label), None);
+    }
+}
+
+mod intern_map {
+    use string_interner::{
+        backend::BucketBackend,
+        intern_map::Entry,
+        DefaultSymbol,
+        InternMap,
+        Symbol,
+    };
+
+    #[test]
+    fn insert_and_get_works() {
+        let mut map = <InternMap<i32>>::new();
+        let (aa, old) = map.insert("aa", 1);
+        assert_eq!(old, None);
+        let (bb, _) = map.insert(String::from("bb"), 2);
+        assert_eq!(map.insert("aa", 3), (aa, Some(1)));
+        assert_eq!(map.get_by_str("aa"), Some(&3));
+        assert_eq!(map.get_by_symbol(bb), Some(&2));
+        assert_eq!(map.get_symbol("bb"), Some(bb));
+        assert_eq!(map.get_by_str("cc"), None);
+        let unknown = DefaultSymbol::try_from_usize(2).unwrap();
+        assert_eq!(map.get_by_symbol(unknown), None);
+        *map.get_by_str_mut("bb").unwrap() += 10;
+        *map.get_by_symbol_mut(aa).unwrap() += 10;
+        assert_eq!(map.values(), &[13, 12]);
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.interner().len(), 2);
+    }
+
+    #[test]
+    fn entry_works() {
+        let mut map = InternMap::<Vec<usize>, BucketBackend>::new();
+        for (index, word) in "a b a c b a".split(' ').enumerate() {
+            map.entry(word).or_default().push(index);
+        }
+        assert_eq!(map.get_by_str("a"), Some(&vec![0, 2, 5]));
+        assert_eq!(map.get_by_str("c"), Some(&vec![3]));
+        map.entry("a").and_modify(Vec::clear).or_insert_with(|| vec![9]);
+        map.entry("d").and_modify(Vec::clear).or_insert_with(|| vec![9]);
+        assert_eq!(map.get_by_str("a"), Some(&vec![]));
+        assert_eq!(map.get_by_str("d"), Some(&vec![9]));
+        match map.entry("b") {
+            Entry::Occupied(mut entry) => {
+                assert_eq!(entry.get(), &vec![1, 4]);
+                assert_eq!(entry.insert(vec![7]), vec![1, 4]);
+                assert_eq!(Some(entry.symbol()), map.get_symbol("b"));
+            }
+            Entry::Vacant(_) => panic!("expected occupied entry"),
+        }
+        match map.entry("e") {
+            Entry::Vacant(entry) => {
+                assert_eq!(entry.string(), "e");
+                entry.insert(vec![8]).push(9);
+            }
+            Entry::Occupied(_) => panic!("expected vacant entry"),
+        }
+        assert_eq!(map.get_by_str("e"), Some(&vec![8, 9]));
+        assert_eq!(map.len(), 5);
+    }
+
+    #[test]
+    fn iter_works() {
+        let mut map = <InternMap<usize>>::new();
+        for (index, word) in ["aa", "bb", "cc"].into_iter().enumerate() {
+            map.insert(word, index * 10);
+        }
+        let entries = map
+            .iter()
+            .map(|(symbol, string, &value)| (symbol.to_usize(), string, value))
+            .collect::<Vec<_>>();
+        assert_eq!(entries, [(0, "aa", 0), (1, "bb", 10), (2, "cc", 20)]);
+        assert_eq!((&map).into_iter().len(), 3);
+        assert_eq!(format!("{:?}", map), r#"{"aa": 0, "bb": 10, "cc": 20}"#);
+        let (interner, values) = map.clone().into_parts();
+        assert_eq!(interner.len(), values.len());
+    }
+}
+
+#[cfg(feature = "serde-1")]
+mod serde_impl {
+    use super::*;
+    use string_interner::backend::Backend;
+
+    /// A user-defined backend that implements neither `Serialize` nor `Deserialize`.
+    #[derive(Debug, Default)]
+    struct UserBackend(backend::SimpleBackend<DefaultSymbol>);
+
+    impl Backend for UserBackend {
+        type Symbol = DefaultSymbol;
+
+        fn with_capacity(cap: usize) -> Self {
+            Self(backend::SimpleBackend::with_capacity(cap))
+        }
+
+        fn intern(&mut self, string: &str) -> Self::Symbol {
+            self.0.intern(string)
+        }
+
+        fn shrink_to_fit(&mut self) {
+            self.0.shrink_to_fit()
+        }
+
+        fn resolve(&self, symbol: Self::Symbol) -> Option<&str> {
+            self.0.resolve(symbol)
+        }
+
+        unsafe fn resolve_unchecked(&self, symbol: Self::Symbol) -> &str {
+            unsafe { self.0.resolve_unchecked(symbol) }
+        }
+    }
+
+    impl<'a> IntoIterator for &'a UserBackend {
+        type Item = (DefaultSymbol, &'a str);
+        type IntoIter = <&'a backend::SimpleBackend<DefaultSymbol> as IntoIterator>::IntoIter;
+
+        fn into_iter(self) -> Self::IntoIter {
+            (&self.0).into_iter()
+        }
+    }
+
+    #[test]
+    fn user_backend_works() {
+        type StringInterner = string_interner::StringInterner<UserBackend>;
+
+        let mut interner = StringInterner::new();
+        interner.extend(["aa", "bb"]);
+        let json = serde_json::to_string(&interner).unwrap();
+        assert_eq!(json, r#"["aa","bb"]"#);
+        let binary = bincode::serialize(&interner).unwrap();
+        for deserialized in [
+            serde_json::from_str::<StringInterner>(&json).unwrap(),
+            bincode::deserialize::<StringInterner>(&binary).unwrap(),
+        ] {
+            assert_eq!(deserialized.get("bb"), interner.get("bb"));
+            assert_eq!(deserialized.len(), 2);
+        }
+    }
+
+    #[test]
+    fn symbol_exhaustion_fails() {
+        use string_interner::symbol::SymbolU16;
+
+        fn check<B>()
+        where
+            B: Backend<Symbol = SymbolU16>
+                + Default
+                + serde::Serialize
+                + for<'de> serde::Deserialize<'de>,
+            for<'a> &'a B: IntoIterator<Item = (SymbolU16, &'a str)>,
+        {
+            let strings = (0..=u16::MAX).map(|i| i.to_string()).collect::<Vec<_>>();
+            let json = serde_json::to_string(&strings).unwrap();
+            let interner = serde_json::from_str::<string_interner::StringInterner<B>>(&json);
+            assert!(interner.is_err());
+            assert!(serde_json::from_str::<B>(&json).is_err());
+        }
+
+        check::<backend::StringBackend<SymbolU16>>();
+        check::<backend::BufferBackend<SymbolU16>>();
+        check::<backend::SimpleBackend<SymbolU16>>();
+        check::<backend::BucketBackend<SymbolU16>>();
+        check::<backend::SourceBackend<SymbolU16>>();
+    }
+}
+
+#[cfg(feature = "allocator-api")]
+mod allocator_api {
+    use super::*;
+    use allocator_api2::alloc::{
+        AllocError,
+        Layout,
+    };
+    use core::ptr::NonNull;
+    use std::{
+        cell::Cell,
+        rc::Rc,
+    };
+    use string_interner::{
+        backend::{
+            Backend,
+            BackendIn,
+        },
+        Allocator,
+        Global,
+        InternMap,
+        StringInterner,
+    };
+
+    /// Allocator that counts the bytes it currently has allocated.
+    ///
+    /// Fails all allocations while `failing` is set.
+    #[derive(Debug, Default, Clone)]
+    struct CountingAllocator {
+        allocated: Rc<Cell<usize>>,
+        failing: Rc<Cell<bool>>,
+    }
+
+    impl CountingAllocator {
+        fn allocated(&self) -> usize {
+            self.allocated.get()
+        }
+
+        fn set_failing(&self, failing: bool) {
+            self.failing.set(failing)
+        }
+    }
+
+    unsafe impl Allocator for CountingAllocator {
+        fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+            if self.failing.get() {
+                return Err(AllocError)
+            }
+            let ptr = Global.allocate(layout)?;
+            self.allocated.set(self.allocated.get() + layout.size());
+            Ok(ptr)
+        }
+
+        unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+            self.allocated.set(self.allocated.get() - layout.size());
+            unsafe { Global.deallocate(ptr, layout) }
+        }
+    }
+
+    /// Asserts that the string interner allocates all its heap memory from its allocator.
+    fn assert_allocates_in<B>()
+    where
+        B: BackendIn<CountingAllocator, Symbol = DefaultSymbol> + Clone,
+        for<'a> &'a B: IntoIterator<Item = (DefaultSymbol, &'a str)>,
+    {
+        let alloc = CountingAllocator::default();
+        let mut interner =
+            StringInterner::<B, DefaultHashBuilder, _>::new_in(alloc.clone());
+        assert_eq!(alloc.allocated(), 0);
+        let symbols = (0..100)
+            .map(|i| interner.get_or_intern(i.to_string()))
+            .collect::<Vec<_>>();
+        assert_eq!(interner.get_or_intern("42"), symbols[42]);
+        assert_eq!(interner.resolve(symbols[7]), Some("7"));
+        assert!(alloc.allocated() > 0);
+        assert!(alloc.allocated() >= interner.memory_stats().total().unwrap().used_bytes);
+        let clone = interner.clone();
+        assert_eq!(clone.resolve(symbols[99]), Some("99"));
+        drop(interner);
+        drop(clone);
+        assert_eq!(alloc.allocated(), 0);
+    }
+
+    /// Asserts that failed allocations leave the string interner unchanged.
+    fn assert_try_intern_in<B>()
+    where
+        B: BackendIn<CountingAllocator, Symbol = DefaultSymbol>,
+        for<'a> &'a B: IntoIterator<Item = (DefaultSymbol, &'a str)>,
+    {
+        let alloc = CountingAllocator::default();
+        let mut interner =
+            StringInterner::<B, DefaultHashBuilder, _>::new_in(alloc.clone());
+        let mut symbols = Vec::new();
+        let mut failures = 0;
+        for i in 0..200 {
+            let string = i.to_string().repeat(i % 7 + 1);
+            alloc.set_failing(true);
+            if let Some(&first) = symbols.first() {
+                // Already interned strings do not require allocations.
+                assert_eq!(interner.try_get_or_intern("0"), Ok(first));
+            }
+            match interner.try_get_or_intern(&string) {
+                Ok(symbol) => symbols.push(symbol),
+                Err(error) => {
+                    failures += 1;
+                    assert!(matches!(
+                        error,
+                        InternError::Alloc(TryReserveError::AllocError { .. })
+                    ));
+                    assert_eq!(interner.len(), symbols.len());
+                    assert_eq!(interner.get(&string), None);
+                    alloc.set_failing(false);
+                    symbols.push(interner.try_get_or_intern(&string).unwrap());
+                }
+            }
+            alloc.set_failing(false);
+        }
+        assert!(failures > 0);
+        assert_eq!(interner.len(), symbols.len());
+        for (i, &symbol) in symbols.iter().enumerate() {
+            let string = i.to_string().repeat(i % 7 + 1);
+            assert_eq!(interner.resolve(symbol), Some(&*string));
+            assert_eq!(interner.get(&string), Some(symbol));
+        }
+        alloc.set_failing(true);
+        assert!(interner.try_reserve(1000).is_err());
+        assert_eq!(interner.len(), symbols.len());
+    }
+
+    #[test]
+    fn string_backend_allocates_in() {
+        assert_allocates_in::<backend::StringBackend<DefaultSymbol, CountingAllocator>>();
+        assert_try_intern_in::<backend::StringBackend<DefaultSymbol, CountingAllocator>>();
+    }
+
+    #[test]
+    fn buffer_backend_allocates_in() {
+        assert_allocates_in::<backend::BufferBackend<DefaultSymbol, CountingAllocator>>();
+        assert_try_intern_in::<backend::BufferBackend<DefaultSymbol, CountingAllocator>>();
+    }
+
+    #[test]
+    fn bucket_backend_allocates_in() {
+        assert_allocates_in::<backend::BucketBackend<DefaultSymbol, CountingAllocator>>();
+        assert_try_intern_in::<backend::BucketBackend<DefaultSymbol, CountingAllocator>>();
+    }
+
+    #[test]
+    fn simple_backend_allocates_in() {
+        assert_allocates_in::<backend::SimpleBackend<DefaultSymbol, CountingAllocator>>();
+        assert_try_intern_in::<backend::SimpleBackend<DefaultSymbol, CountingAllocator>>();
+    }
+
+    #[test]
+    fn borrowed_backend_allocates_in() {
+        assert_allocates_in::<
+            backend::BorrowedBackend<'static, DefaultSymbol, CountingAllocator>,
+        >();
+        assert_try_intern_in::<
+            backend::BorrowedBackend<'static, DefaultSymbol, CountingAllocator>,
+        >();
+    }
+
+    #[test]
+    fn source_backend_allocates_in() {
+        assert_allocates_in::<backend::SourceBackend<DefaultSymbol, CountingAllocator>>();
+        assert_try_intern_in::<backend::SourceBackend<DefaultSymbol, CountingAllocator>>();
+    }
+
+    #[test]
+    fn allocator_works() {
+        let alloc = CountingAllocator::default();
+        let interner = <StringInterner<
+            backend::StringBackend<DefaultSymbol, CountingAllocator>,
+            DefaultHashBuilder,
+            _,
+        >>::with_capacity_in(10, alloc.clone());
+        assert!(Rc::ptr_eq(&interner.allocator().allocated, &alloc.allocated));
+        assert!(alloc.allocated() > 0);
+        let default = <StringInterner>::new();
+        let _: &Global = default.allocator();
+    }
+
+    #[test]
+    fn intern_map_allocates_in() {
+        let mut map = InternMap::<
+            usize,
+            backend::StringBackend<DefaultSymbol, CountingAllocator>,
+            DefaultHashBuilder,
+            CountingAllocator,
+        >::new();
+        let alloc = map.interner().allocator().clone();
+        let mut failures = 0;
+        for i in 0..200 {
+            alloc.set_failing(true);
+            if let Err(error) = map.try_insert(i.to_string(), i) {
+                failures += 1;
+                assert!(matches!(
+                    error,
+                    InternError::Alloc(TryReserveError::AllocError { .. })
+                ));
+                // Strings are never interned without their values.
+                assert_eq!(map.interner().len(), map.len());
+                alloc.set_failing(false);
+                map.try_insert(i.to_string(), i).unwrap();
+            }
+            alloc.set_failing(false);
+        }
+        assert!(failures > 0);
+        assert_eq!(map.get_by_str("42"), Some(&42));
+        let strings = map.iter().map(|(_, string, _)| string.len()).sum::<usize>();
+        assert!(alloc.allocated() >= strings + core::mem::size_of_val(map.values()));
+        drop(map);
+        assert_eq!(alloc.allocated(), 0);
+    }
+
+    #[test]
+    fn namespaces_allocate_in() {
+        use string_interner::{
+            Namespace,
+            NamespacedInterner,
+        };
+
+        struct Ident;
+        struct Label;
+
+        impl Namespace<u8> for Ident {
+            const NAMESPACE: u8 = 0;
+        }
+
+        impl Namespace<u8> for Label {
+            const NAMESPACE: u8 = 1;
+        }
+
+        let alloc = CountingAllocator::default();
+        let mut storage = StringInterner::<
+            backend::StringBackend<DefaultSymbol, CountingAllocator>,
+            DefaultHashBuilder,
+            _,
+        >::new_in(alloc.clone());
+        storage.get_or_intern("aa");
+        let mut interner = NamespacedInterner::<u8, _, _, _>::with_storage(storage);
+        alloc.set_failing(true);
+        // The string is stored already but its namespace membership is allocated.
+        assert!(matches!(
+            interner.try_get_or_intern(Ident, "aa"),
+            Err(InternError::Alloc(TryReserveError::AllocError { .. }))
+        ));
+        alloc.set_failing(false);
+        assert_eq!(interner.get(Ident, "aa"), None);
+        let ident = interner.get_or_intern(Ident, "aa");
+        assert!(interner.contains(ident));
+        assert_eq!(interner.get(Label, "aa"), None);
+        drop(interner);
+        assert_eq!(alloc.allocated(), 0);
+    }
+
+    #[test]
+    fn allocator_without_default_works() {
+        let alloc = CountingAllocator::default();
+        let mut interner = StringInterner::<
+            backend::BucketBackend<DefaultSymbol, &CountingAllocator>,
+            DefaultHashBuilder,
+            _,
+        >::new_in(&alloc);
+        let symbols = (0..100)
+            .map(|i| interner.get_or_intern(i.to_string()))
+            .collect::<Vec<_>>();
+        assert_eq!(interner.resolve(symbols[42]), Some("42"));
+        assert!(alloc.allocated() > 0);
+        drop(interner);
+        assert_eq!(alloc.allocated(), 0);
+
+        type ArenaBackend<'a> =
+            backend::StringBackend<DefaultSymbol, &'a CountingAllocator>;
+        let mut backend = ArenaBackend::new_in(&alloc);
+        let symbol = backend.intern("aa");
+        assert_eq!(backend.resolve(symbol), Some("aa"));
+        let interner =
+            StringInterner::<ArenaBackend, DefaultHashBuilder, _>::try_from_backend(
+                backend,
+                DefaultHashBuilder::default(),
+                &alloc,
+            )
+            .unwrap();
+        assert_eq!(interner.get("aa"), Some(symbol));
+        drop(interner);
+        assert_eq!(alloc.allocated(), 0);
+    }
 }